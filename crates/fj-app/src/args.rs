@@ -1,8 +1,9 @@
 use std::{path::PathBuf, str::FromStr as _};
 
 use anyhow::anyhow;
+use fj_export::Format;
 use fj_host::Parameters;
-use fj_kernel::algorithms::Tolerance;
+use fj_kernel::{algorithms::Tolerance, geometry::Surface};
 use fj_math::Scalar;
 
 /// Fornjot - Experimental CAD System
@@ -16,6 +17,24 @@ pub struct Args {
     #[clap(short, long)]
     pub export: Option<PathBuf>,
 
+    /// Format of the exported file, instead of deriving it from the extension
+    ///
+    /// One of `3mf`, `dxf`, `gltf`, `glb`, `obj`, `ply`, `step`, `stl`,
+    /// `stl-ascii`, `stl-color`, `svg`.
+    #[clap(long)]
+    pub export_format: Option<Format>,
+
+    /// Export the section of the model with a plane, instead of the model
+    ///
+    /// The plane is given as `x=<value>`, `y=<value>`, or `z=<value>`. The
+    /// section can only be exported as DXF or SVG.
+    #[clap(long, parse(try_from_str = parse_section))]
+    pub section: Option<Surface>,
+
+    /// Include reference bodies when exporting
+    #[clap(long)]
+    pub export_references: bool,
+
     /// Parameters for the model, each in the form `key=value`
     #[clap(short, long, parse(try_from_str = parse_parameters))]
     pub parameters: Option<Parameters>,
@@ -63,3 +82,19 @@ fn parse_tolerance(input: &str) -> anyhow::Result<Tolerance> {
 
     Ok(tolerance)
 }
+
+fn parse_section(input: &str) -> anyhow::Result<Surface> {
+    let (axis, value) = input
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Expected section plane in the form `z=0`"))?;
+    let c = f64::from_str(value.trim())?;
+
+    let points = match axis.trim() {
+        "x" => [[c, 0., 0.], [c, 1., 0.], [c, 0., 1.]],
+        "y" => [[0., c, 0.], [0., c, 1.], [1., c, 0.]],
+        "z" => [[0., 0., c], [1., 0., c], [0., 1., c]],
+        axis => return Err(anyhow!("Unknown axis `{}`", axis)),
+    };
+
+    Ok(Surface::plane_from_points(points))
+}
@@ -18,9 +18,10 @@ mod config;
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Context as _};
-use fj_export::export;
+use fj_export::{export_as, export_section, Format, Metadata};
 use fj_host::{Model, Parameters};
-use fj_operations::shape_processor::ShapeProcessor;
+use fj_kernel::shape::{ValidationConfig, ValidationLevel};
+use fj_operations::{cache::ShapeCache, shape_processor::ShapeProcessor};
 use fj_window::run::run;
 use tracing_subscriber::fmt::format;
 use tracing_subscriber::EnvFilter;
@@ -56,16 +57,52 @@ fn main() -> anyhow::Result<()> {
     let model = Model::from_path(path.clone(), config.target_dir)
         .with_context(|| format!("Failed to load model: {}", path.display()))?;
     let parameters = args.parameters.unwrap_or_else(Parameters::empty);
+    let metadata = Metadata {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned()),
+        parameters: parameters.0.clone().into_iter().collect(),
+    };
 
     let shape_processor = ShapeProcessor {
         tolerance: args.tolerance,
+        include_references: true,
+        // Full validation is too slow for interactive editing. Structural
+        // validation still catches the worst issues.
+        validation_config: ValidationConfig {
+            level: ValidationLevel::Structural,
+            ..ValidationConfig::default()
+        },
+        cache: ShapeCache::new(),
     };
 
     if let Some(path) = args.export {
+        let shape_processor = ShapeProcessor {
+            include_references: args.export_references,
+            validation_config: ValidationConfig::default(),
+            ..shape_processor
+        };
+
         let shape = model.load_once(&parameters)?;
         let shape = shape_processor.process(&shape)?;
 
-        export(&shape.mesh, &path)?;
+        let format = match args.export_format {
+            Some(format) => format,
+            None => Format::from_path(&path)?,
+        };
+
+        match args.section {
+            Some(plane) => export_section(
+                &shape.shape,
+                &plane,
+                shape.tolerance,
+                &path,
+                format,
+            )?,
+            None => {
+                export_as(&shape.shape, &shape.mesh, &metadata, &path, format)?
+            }
+        }
 
         return Ok(());
     }
@@ -0,0 +1,979 @@
+//! Planar drawings, as exported to 2D file formats
+
+use std::f64::consts::PI;
+
+use fj_kernel::{
+    geometry::{Curve, Surface, SweptCurve},
+    shape::Shape,
+    topology::{Cycle, Edge, Face},
+};
+use fj_math::{NurbsCurve, Point, Scalar, Vector};
+
+use crate::Error;
+
+/// A drawing, made up of the cycles of a planar shape
+///
+/// The geometry is taken from the curves of the shape's edges, without any
+/// approximation. The only exception are edges that cover part of a NURBS
+/// curve, which are approximated by polylines.
+pub struct Drawing {
+    /// The faces of the drawing, each made up of its cycles
+    ///
+    /// The exterior cycles of each face come before its interior cycles.
+    pub faces: Vec<Vec<Path>>,
+}
+
+impl Drawing {
+    /// Create a drawing from the faces of a shape
+    ///
+    /// Returns an error, if the faces don't all lie in the same plane. The
+    /// drawing's x and y axes are aligned with the global x and y axes as far
+    /// as possible, so the drawing of a 2D model uses the model's coordinates.
+    pub fn from_shape(shape: &Shape) -> Result<Self, Error> {
+        let mut plane: Option<Plane> = None;
+        let mut faces = Vec::new();
+
+        for face in shape.faces().values() {
+            let brep = match &face {
+                Face::Face(brep) => brep,
+                Face::Triangles(_) => return Err(Error::NotPlanar),
+            };
+
+            let face_plane =
+                Plane::from_surface(&brep.surface()).ok_or(Error::NotPlanar)?;
+            let plane = match &plane {
+                Some(plane) => {
+                    if !plane.contains(&face_plane) {
+                        return Err(Error::NotPlanar);
+                    }
+                    plane
+                }
+                None => plane.insert(face_plane),
+            };
+
+            let paths = face
+                .exteriors()
+                .chain(face.interiors())
+                .map(|cycle| Path::from_cycle(&cycle, plane))
+                .collect();
+            faces.push(paths);
+        }
+
+        Ok(Self { faces })
+    }
+
+    /// Compute the bounding box of the drawing
+    ///
+    /// Returns the minimum and maximum coordinates. The box might be slightly
+    /// larger than necessary around splines. Returns `None`, if the drawing is
+    /// empty.
+    pub fn bounding_box(&self) -> Option<[[f64; 2]; 2]> {
+        let mut points = Vec::new();
+
+        for path in self.faces.iter().flatten() {
+            points.push(path.start);
+            for segment in &path.segments {
+                segment.extreme_points(&mut points);
+            }
+        }
+
+        let first = *points.first()?;
+        let bounding_box =
+            points.iter().fold([first, first], |[min, max], p| {
+                [
+                    [min[0].min(p[0]), min[1].min(p[1])],
+                    [max[0].max(p[0]), max[1].max(p[1])],
+                ]
+            });
+
+        Some(bounding_box)
+    }
+}
+
+/// A closed path, made up of connected segments
+pub struct Path {
+    /// The point where the path starts, and ends
+    pub start: [f64; 2],
+
+    /// The segments of the path
+    ///
+    /// Each segment starts where the previous one ends.
+    pub segments: Vec<Segment>,
+}
+
+impl Path {
+    fn from_cycle(cycle: &Cycle<3>, plane: &Plane) -> Self {
+        let segments: Vec<_> = cycle
+            .edges()
+            .flat_map(|edge| Segment::from_edge(&edge, plane))
+            .collect();
+
+        let start = segments.first().map(Segment::start).unwrap_or([0., 0.]);
+
+        Self { start, segments }
+    }
+}
+
+/// A segment of a path
+pub enum Segment {
+    /// A straight line
+    Line {
+        /// The start of the line
+        start: [f64; 2],
+
+        /// The end of the line
+        end: [f64; 2],
+    },
+
+    /// An arc of a circle or an ellipse
+    Arc(Arc),
+
+    /// A NURBS curve, over its whole domain
+    Spline(NurbsCurve<2>),
+
+    /// An approximation of part of a curve, that can't be represented exactly
+    Polyline(Vec<[f64; 2]>),
+}
+
+impl Segment {
+    /// Create the segments that represent an edge
+    ///
+    /// Most edges are represented by a single segment. Edges on NURBS curves
+    /// that consist of circular arcs result in one arc per circle.
+    fn from_edge(edge: &Edge<3>, plane: &Plane) -> Vec<Self> {
+        let curve = edge.curve();
+
+        let range = edge
+            .vertices
+            .as_ref()
+            .map(|[a, b]| [a.local().t, b.local().t]);
+
+        match curve {
+            Curve::Line(line) => {
+                // Lines are infinite, so every edge on a line is bounded.
+                let [start, end] = range
+                    .unwrap_or([Scalar::ZERO, Scalar::ONE])
+                    .map(|t| line.origin + line.direction * t)
+                    .map(|point| plane.point(point));
+
+                vec![Self::Line { start, end }]
+            }
+            Curve::Circle(circle) => vec![Self::Arc(Arc::new(
+                plane.point(circle.center),
+                plane.vector(circle.a),
+                plane.vector(circle.b),
+                range.unwrap_or([Scalar::ZERO, Scalar::PI * 2.]),
+            ))],
+            Curve::Ellipse(ellipse) => vec![Self::Arc(Arc::new(
+                plane.point(ellipse.center),
+                plane.vector(ellipse.a),
+                plane.vector(ellipse.b),
+                range.unwrap_or([Scalar::ZERO, Scalar::PI * 2.]),
+            ))],
+            Curve::Nurbs(nurbs) => {
+                let domain = nurbs.domain();
+
+                // The projection into the plane is an affine map, which NURBS
+                // curves are invariant under.
+                let nurbs = NurbsCurve {
+                    degree: nurbs.degree,
+                    control_points: nurbs
+                        .control_points
+                        .iter()
+                        .map(|&point| Point::from(plane.point(point)))
+                        .collect(),
+                    weights: nurbs.weights,
+                    knots: nurbs.knots,
+                };
+
+                let [start, end] = range.unwrap_or(domain);
+                if [start, end] == domain || [end, start] == domain {
+                    let nurbs = if [start, end] == domain {
+                        nurbs
+                    } else {
+                        nurbs.reverse()
+                    };
+
+                    // Sketches represent arcs as NURBS curves. Those are better
+                    // represented as actual arcs.
+                    return match circular_arcs(&nurbs) {
+                        Some(arcs) => arcs.into_iter().map(Self::Arc).collect(),
+                        None => vec![Self::Spline(nurbs)],
+                    };
+                }
+
+                let n = 8 * nurbs.control_points.len();
+                let points = (0..=n)
+                    .map(|i| {
+                        let t = start + (end - start) * (i as f64 / n as f64);
+                        let point = nurbs.point_from_curve_coords([t]);
+                        [point.u.into_f64(), point.v.into_f64()]
+                    })
+                    .collect();
+
+                vec![Self::Polyline(points)]
+            }
+        }
+    }
+
+    /// Access the start of the segment
+    pub fn start(&self) -> [f64; 2] {
+        match self {
+            Self::Line { start, .. } => *start,
+            Self::Arc(arc) => arc.point(arc.start),
+            Self::Spline(nurbs) => {
+                let [start, _] = nurbs.domain();
+                spline_point(nurbs, start)
+            }
+            Self::Polyline(points) => points[0],
+        }
+    }
+
+    /// Access the end of the segment
+    pub fn end(&self) -> [f64; 2] {
+        match self {
+            Self::Line { end, .. } => *end,
+            Self::Arc(arc) => arc.point(arc.start + arc.sweep),
+            Self::Spline(nurbs) => {
+                let [_, end] = nurbs.domain();
+                spline_point(nurbs, end)
+            }
+            Self::Polyline(points) => points[points.len() - 1],
+        }
+    }
+
+    /// Add points to `points`, whose bounding box contains the segment
+    fn extreme_points(&self, points: &mut Vec<[f64; 2]>) {
+        match self {
+            Self::Line { start, end } => points.extend([*start, *end]),
+            Self::Arc(arc) => {
+                points.extend([self.start(), self.end()]);
+
+                // The coordinates of an arc are extremal where their
+                // derivatives are zero, if those points are part of the arc.
+                let minor = arc.minor();
+                for axis in [0, 1] {
+                    let u = f64::atan2(minor[axis], arc.major[axis]);
+                    for u in [u, u + PI] {
+                        if arc.contains(u) {
+                            points.push(arc.point(u));
+                        }
+                    }
+                }
+            }
+            Self::Spline(nurbs) => {
+                // A NURBS curve lies within the convex hull of its control
+                // points.
+                points.extend(
+                    nurbs
+                        .control_points
+                        .iter()
+                        .map(|point| [point.u.into_f64(), point.v.into_f64()]),
+                );
+            }
+            Self::Polyline(polyline) => points.extend(polyline),
+        }
+    }
+}
+
+/// An arc of an ellipse, which might be a circle
+///
+/// The ellipse is defined by its center, semi-major axis, and the ratio of its
+/// semi-minor to its semi-major axis. The point at parameter `u` is
+/// `center + major * cos(u) + minor * sin(u)`, where `minor` is the semi-major
+/// axis, rotated counter-clockwise by 90 degrees and scaled by `ratio`.
+pub struct Arc {
+    /// The center of the ellipse
+    pub center: [f64; 2],
+
+    /// The semi-major axis of the ellipse
+    pub major: [f64; 2],
+
+    /// The ratio of the semi-minor to the semi-major axis
+    pub ratio: f64,
+
+    /// The parameter where the arc starts
+    pub start: f64,
+
+    /// The difference between the parameters where the arc ends and starts
+    ///
+    /// Positive values mean that the arc is counter-clockwise.
+    pub sweep: f64,
+}
+
+impl Arc {
+    /// Create an arc from conjugate semi-diameters and a range of parameters
+    ///
+    /// `a` and `b` don't need to be perpendicular. The point at parameter `t`
+    /// is `center + a * cos(t) + b * sin(t)`.
+    fn new(
+        center: [f64; 2],
+        a: [f64; 2],
+        b: [f64; 2],
+        range: [Scalar; 2],
+    ) -> Self {
+        let [t0, t1] = range.map(Scalar::into_f64);
+
+        // Rotate `a` and `b` into the principal axes. The distance from the
+        // center is extremal at the parameter `tau`, where the derivative of
+        // its square is zero.
+        let tau = f64::atan2(2. * dot(a, b), dot(a, a) - dot(b, b)) / 2.;
+        let (sin, cos) = tau.sin_cos();
+
+        let mut major = add(scale(a, cos), scale(b, sin));
+        let mut minor = add(scale(b, cos), scale(a, -sin));
+        let mut offset = tau;
+
+        if dot(minor, minor) > dot(major, major) {
+            // Shifting the parameter by a quarter turn swaps the axes.
+            (major, minor) = (minor, scale(major, -1.));
+            offset += PI / 2.;
+        }
+
+        // Clockwise ellipses are turned into counter-clockwise ones, by
+        // negating the parameter.
+        let direction = if cross(major, minor) < 0. { -1. } else { 1. };
+
+        Self {
+            center,
+            major,
+            ratio: (dot(minor, minor) / dot(major, major)).sqrt(),
+            start: (t0 - offset) * direction,
+            sweep: (t1 - t0) * direction,
+        }
+    }
+
+    /// Access the semi-minor axis
+    pub fn minor(&self) -> [f64; 2] {
+        let [x, y] = self.major;
+        [-y * self.ratio, x * self.ratio]
+    }
+
+    /// Compute the point at the given parameter
+    pub fn point(&self, u: f64) -> [f64; 2] {
+        let (sin, cos) = u.sin_cos();
+        add(
+            self.center,
+            add(scale(self.major, cos), scale(self.minor(), sin)),
+        )
+    }
+
+    /// Indicate whether the arc is part of a circle
+    pub fn is_circle(&self) -> bool {
+        (self.ratio - 1.).abs() < 1e-12
+    }
+
+    /// Indicate whether the arc covers the whole circle or ellipse
+    pub fn is_full(&self) -> bool {
+        self.sweep.abs() >= 2. * PI - 1e-12
+    }
+
+    /// Compute the radius of the circle, or the semi-major axis of the ellipse
+    pub fn radius(&self) -> f64 {
+        dot(self.major, self.major).sqrt()
+    }
+
+    /// Compute the angle of the semi-major axis, relative to the x axis
+    pub fn rotation(&self) -> f64 {
+        f64::atan2(self.major[1], self.major[0])
+    }
+
+    fn contains(&self, u: f64) -> bool {
+        let [min, max] = if self.sweep >= 0. {
+            [self.start, self.start + self.sweep]
+        } else {
+            [self.start + self.sweep, self.start]
+        };
+
+        let u = min + (u - min).rem_euclid(2. * PI);
+        u <= max
+    }
+}
+
+/// Indicate whether a spline is rational, meaning its weights differ
+pub fn is_rational(nurbs: &NurbsCurve<2>) -> bool {
+    nurbs.weights.windows(2).any(|w| w[0] != w[1])
+}
+
+/// The control points of a Bézier curve, and their weights
+pub type Bezier = Vec<([f64; 2], f64)>;
+
+/// Split a spline into Bézier curves
+///
+/// Returns the control points and weights of each Bézier curve. Returns
+/// `None`, if the knot vector of the spline isn't clamped.
+pub fn bezier_segments(nurbs: &NurbsCurve<2>) -> Option<Vec<Bezier>> {
+    let degree = nurbs.degree;
+    if degree == 0 {
+        return None;
+    }
+
+    let mut knots: Vec<f64> =
+        nurbs.knots.iter().copied().map(Scalar::into_f64).collect();
+
+    // Knot insertion works on the homogeneous control points, which makes it
+    // work for rational splines too.
+    let mut points: Vec<[f64; 3]> = nurbs
+        .control_points
+        .iter()
+        .zip(&nurbs.weights)
+        .map(|(point, weight)| {
+            let w = weight.into_f64();
+            [point.u.into_f64() * w, point.v.into_f64() * w, w]
+        })
+        .collect();
+
+    let clamped = knots.len() == points.len() + degree + 1
+        && knots[..=degree].iter().all(|&knot| knot == knots[0])
+        && knots[knots.len() - degree - 1..]
+            .iter()
+            .all(|&knot| knot == knots[knots.len() - 1]);
+    if !clamped {
+        return None;
+    }
+
+    // Insert every interior knot until its multiplicity equals the degree.
+    // Then each span of the curve is defined by its own control points.
+    let interior = knots[degree + 1..knots.len() - degree - 1].to_vec();
+    let mut distinct = interior.clone();
+    distinct.dedup();
+
+    for knot in distinct {
+        let multiplicity = interior.iter().filter(|&&k| k == knot).count();
+        for _ in multiplicity..degree {
+            insert_knot(degree, &mut knots, &mut points, knot);
+        }
+    }
+
+    let segments = points
+        .windows(degree + 1)
+        .step_by(degree)
+        .map(|points| {
+            points
+                .iter()
+                .map(|&[x, y, w]| ([x / w, y / w], w))
+                .collect()
+        })
+        .collect();
+
+    Some(segments)
+}
+
+/// Insert a knot into a B-spline with homogeneous control points, without
+/// changing its shape
+fn insert_knot(
+    degree: usize,
+    knots: &mut Vec<f64>,
+    points: &mut Vec<[f64; 3]>,
+    knot: f64,
+) {
+    // The span that contains the new knot.
+    let k = knots.iter().rposition(|&k| k <= knot).unwrap_or(0);
+
+    let mut new_points = Vec::with_capacity(points.len() + 1);
+    new_points.extend_from_slice(&points[..=k - degree]);
+    for i in k - degree + 1..=k {
+        let alpha = (knot - knots[i]) / (knots[i + degree] - knots[i]);
+        let a = points[i - 1];
+        let b = points[i];
+        new_points.push([0, 1, 2].map(|j| a[j] * (1. - alpha) + b[j] * alpha));
+    }
+    new_points.extend_from_slice(&points[k..]);
+
+    knots.insert(k + 1, knot);
+    *points = new_points;
+}
+
+/// Convert a spline into circular arcs
+///
+/// Returns `None`, if the spline isn't made up of circular arcs. Consecutive
+/// spans that lie on the same circle are merged into one arc.
+fn circular_arcs(nurbs: &NurbsCurve<2>) -> Option<Vec<Arc>> {
+    if nurbs.degree != 2 {
+        return None;
+    }
+
+    let mut arcs: Vec<Arc> = Vec::new();
+
+    for segment in bezier_segments(nurbs)? {
+        let arc = circular_arc([segment[0], segment[1], segment[2]])?;
+
+        if let Some(last) = arcs.last_mut() {
+            let epsilon = 1e-9 * (1. + last.radius());
+
+            let same_circle = dot(
+                add(arc.center, scale(last.center, -1.)),
+                add(arc.center, scale(last.center, -1.)),
+            )
+            .sqrt()
+                <= epsilon
+                && (arc.radius() - last.radius()).abs() <= epsilon;
+
+            if same_circle && (arc.sweep > 0.) == (last.sweep > 0.) {
+                last.sweep += arc.sweep;
+                continue;
+            }
+        }
+
+        arcs.push(arc);
+    }
+
+    Some(arcs)
+}
+
+/// Convert a rational quadratic Bézier curve into a circular arc
+///
+/// Returns `None`, if the curve isn't a circular arc.
+fn circular_arc(points: [([f64; 2], f64); 3]) -> Option<Arc> {
+    let [(p0, w0), (p1, w1), (p2, w2)] = points;
+
+    let a = add(p1, scale(p0, -1.));
+    let b = add(p2, scale(p1, -1.));
+    let chord = add(p2, scale(p0, -1.));
+
+    let [a_len, b_len, chord_len] = [a, b, chord].map(|v| dot(v, v).sqrt());
+    if a_len == 0. || chord_len == 0. || w0 <= 0. || w2 <= 0. {
+        return None;
+    }
+
+    // The legs of the control polygon of a circular arc are equally long, and
+    // the normalized middle weight is the cosine of the angle between a leg
+    // and the chord.
+    let cos = dot(a, chord) / (a_len * chord_len);
+    let weight = w1 / (w0 * w2).sqrt();
+    if (a_len - b_len).abs() > 1e-9 * a_len || (weight - cos).abs() > 1e-9 {
+        return None;
+    }
+
+    let middle = scale(add(p0, p2), 0.5);
+    let to_middle = add(middle, scale(p1, -1.));
+    let center =
+        add(p1, scale(to_middle, dot(a, a) / dot(to_middle, to_middle)));
+
+    let start = add(p0, scale(center, -1.));
+    let end = add(p2, scale(center, -1.));
+
+    Some(Arc {
+        center,
+        major: [dot(start, start).sqrt(), 0.],
+        ratio: 1.,
+        start: f64::atan2(start[1], start[0]),
+        sweep: f64::atan2(cross(start, end), dot(start, end)),
+    })
+}
+
+/// A plane that faces can lie in
+struct Plane {
+    origin: Vector<3>,
+    normal: Vector<3>,
+    u: Vector<3>,
+    v: Vector<3>,
+}
+
+impl Plane {
+    fn from_surface(surface: &Surface) -> Option<Self> {
+        let (line, path) = match surface {
+            Surface::SweptCurve(SweptCurve {
+                curve: Curve::Line(line),
+                path,
+            }) => (line, path),
+            _ => return None,
+        };
+
+        let mut normal = line.direction.cross(path).normalize();
+
+        // The orientation of the drawing should not depend on the orientation
+        // of the faces. Faces that face downwards would otherwise result in a
+        // mirrored drawing.
+        if (normal.z, normal.y, normal.x)
+            < (Scalar::ZERO, Scalar::ZERO, Scalar::ZERO)
+        {
+            normal = -normal;
+        }
+
+        // Align the axes of the drawing with the global axes, as far as the
+        // orientation of the plane allows it.
+        let u = [Vector::unit_x(), Vector::unit_y()]
+            .into_iter()
+            .map(|axis| axis - normal * axis.dot(&normal))
+            .find(|u| u.magnitude() > Scalar::from_f64(1e-6))?
+            .normalize();
+        let v = normal.cross(&u);
+
+        let origin = normal * line.origin.coords.dot(&normal);
+
+        Some(Self {
+            origin,
+            normal,
+            u,
+            v,
+        })
+    }
+
+    /// Indicate whether the other plane is the same as this one
+    fn contains(&self, other: &Plane) -> bool {
+        let epsilon = Scalar::from_f64(1e-9);
+
+        self.normal.cross(&other.normal).magnitude() <= epsilon
+            && (other.origin - self.origin).dot(&self.normal).abs()
+                <= epsilon * (Scalar::ONE + self.origin.magnitude())
+    }
+
+    fn point(&self, point: Point<3>) -> [f64; 2] {
+        self.vector(point.coords - self.origin)
+    }
+
+    fn vector(&self, vector: Vector<3>) -> [f64; 2] {
+        [vector.dot(&self.u), vector.dot(&self.v)].map(Scalar::into_f64)
+    }
+}
+
+fn spline_point(nurbs: &NurbsCurve<2>, t: Scalar) -> [f64; 2] {
+    let point = nurbs.point_from_curve_coords([t]);
+    [point.u.into_f64(), point.v.into_f64()]
+}
+
+fn add([ax, ay]: [f64; 2], [bx, by]: [f64; 2]) -> [f64; 2] {
+    [ax + bx, ay + by]
+}
+
+fn scale([x, y]: [f64; 2], factor: f64) -> [f64; 2] {
+    [x * factor, y * factor]
+}
+
+fn dot([ax, ay]: [f64; 2], [bx, by]: [f64; 2]) -> f64 {
+    ax * bx + ay * by
+}
+
+fn cross([ax, ay]: [f64; 2], [bx, by]: [f64; 2]) -> f64 {
+    ax * by - ay * bx
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::f64::consts::PI;
+
+    use fj_kernel::{
+        geometry::Surface,
+        shape::Shape,
+        topology::{Cycle, Edge, Face},
+    };
+    use fj_math::{Circle, NurbsCurve, Point, Scalar};
+
+    use super::{
+        bezier_segments, circular_arc, insert_knot, Arc, Bezier, Plane, Segment,
+    };
+
+    #[test]
+    fn bezier_segments_of_spline() {
+        let nurbs = NurbsCurve::from_control_points(
+            3,
+            [[0., 0.], [1., 2.], [3., 3.], [4., 1.], [6., 0.], [7., 2.]],
+        );
+        let segments = bezier_segments(&nurbs).unwrap();
+
+        // The knot vector has two distinct interior knots.
+        assert_eq!(segments.len(), 3);
+        assert!(segments.iter().all(|segment| segment.len() == 4));
+
+        let mut knots: Vec<_> =
+            nurbs.knots.iter().copied().map(Scalar::into_f64).collect();
+        knots.dedup();
+
+        for (segment, span) in segments.iter().zip(knots.windows(2)) {
+            for i in 0..=10 {
+                let s = i as f64 / 10.;
+                let t = span[0] + (span[1] - span[0]) * s;
+                assert_close(bezier_point(segment, s), spline_point(&nurbs, t));
+            }
+        }
+    }
+
+    #[test]
+    fn bezier_segments_of_rational_spline() {
+        let nurbs = NurbsCurve::arc([1., 2.], [2., 0.], [0., 2.], PI * 1.5);
+        let segments = bezier_segments(&nurbs).unwrap();
+
+        assert_eq!(segments.len(), 3);
+        for (i, segment) in segments.iter().enumerate() {
+            for j in 0..=10 {
+                let s = j as f64 / 10.;
+                let t = (i as f64 + s) / 3.;
+                assert_close(bezier_point(segment, s), spline_point(&nurbs, t));
+            }
+        }
+    }
+
+    #[test]
+    fn bezier_segments_of_unclamped_spline() {
+        let nurbs = NurbsCurve {
+            knots: (0..7).map(|i| Scalar::from_f64(i as f64)).collect(),
+            ..NurbsCurve::from_control_points(
+                2,
+                [[0., 0.], [1., 1.], [2., 0.], [3., 1.]],
+            )
+        };
+
+        assert!(bezier_segments(&nurbs).is_none());
+    }
+
+    #[test]
+    fn insert_knot_preserves_shape() {
+        let nurbs = NurbsCurve::arc([0., 0.], [1., 0.], [0., 1.], PI * 1.5);
+
+        let mut knots: Vec<_> =
+            nurbs.knots.iter().copied().map(Scalar::into_f64).collect();
+        let mut points: Vec<_> = nurbs
+            .control_points
+            .iter()
+            .zip(&nurbs.weights)
+            .map(|(point, weight)| {
+                let w = weight.into_f64();
+                [point.u.into_f64() * w, point.v.into_f64() * w, w]
+            })
+            .collect();
+
+        insert_knot(2, &mut knots, &mut points, 0.4);
+
+        assert_eq!(knots.len(), nurbs.knots.len() + 1);
+        assert_eq!(points.len(), nurbs.control_points.len() + 1);
+
+        let refined = NurbsCurve {
+            degree: 2,
+            control_points: points
+                .iter()
+                .map(|&[x, y, w]| Point::from([x / w, y / w]))
+                .collect(),
+            weights: points.iter().map(|&[_, _, w]| w.into()).collect(),
+            knots: knots.into_iter().map(Scalar::from_f64).collect(),
+        };
+        for i in 0..=20 {
+            let t = i as f64 / 20.;
+            assert_close(spline_point(&refined, t), spline_point(&nurbs, t));
+        }
+    }
+
+    #[test]
+    fn circular_arc_from_bezier() {
+        let nurbs = NurbsCurve::arc([1., 2.], [2., 0.], [0., 2.], PI / 2.);
+        let segment = &bezier_segments(&nurbs).unwrap()[0];
+
+        let arc = circular_arc([segment[0], segment[1], segment[2]]).unwrap();
+        assert_close(arc.center, [1., 2.]);
+        assert!((arc.radius() - 2.).abs() < 1e-9);
+        assert!(arc.start.abs() < 1e-9);
+        assert!((arc.sweep - PI / 2.).abs() < 1e-9);
+
+        // The same arc, in the opposite direction.
+        let arc = circular_arc([segment[2], segment[1], segment[0]]).unwrap();
+        assert!((arc.start - PI / 2.).abs() < 1e-9);
+        assert!((arc.sweep + PI / 2.).abs() < 1e-9);
+
+        // A quarter of an ellipse is not circular.
+        let nurbs = NurbsCurve::arc([0., 0.], [2., 0.], [0., 1.], PI / 2.);
+        let segment = &bezier_segments(&nurbs).unwrap()[0];
+        assert!(circular_arc([segment[0], segment[1], segment[2]]).is_none());
+
+        // Neither is a parabola.
+        assert!(
+            circular_arc([([0., 0.], 1.), ([1., 1.], 1.), ([2., 0.], 1.)])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn arc_from_conjugate_diameters() {
+        let cases = [
+            // Counter-clockwise, not perpendicular.
+            ([2., 0.], [1., 1.]),
+            // Counter-clockwise, minor axis given first.
+            ([0., 1.], [-3., 0.]),
+            // Clockwise.
+            ([1., 1.], [2., -1.]),
+        ];
+
+        for (a, b) in cases {
+            let center = [1., -1.];
+            let [t0, t1] = [0.5, 2.5];
+            let arc = Arc::new(center, a, b, [t0.into(), t1.into()]);
+
+            assert!(arc.ratio > 0. && arc.ratio <= 1.);
+
+            for i in 0..=10 {
+                let s = i as f64 / 10.;
+                let t = t0 + (t1 - t0) * s;
+                let expected = [
+                    center[0] + a[0] * t.cos() + b[0] * t.sin(),
+                    center[1] + a[1] * t.cos() + b[1] * t.sin(),
+                ];
+
+                assert_close(arc.point(arc.start + arc.sweep * s), expected);
+            }
+        }
+
+        let clockwise =
+            Arc::new([0., 0.], [1., 0.], [0., -1.], [0.0.into(), 1.0.into()]);
+        assert!(clockwise.sweep < 0.);
+    }
+
+    #[test]
+    fn segments_from_edges() {
+        let mut shape = Shape::new();
+        let plane = Plane::from_surface(&Surface::xy_plane()).unwrap();
+
+        let line = Edge::builder(&mut shape)
+            .build_line_segment_from_points([[1., 2., 0.], [3., 4., 0.]])
+            .unwrap();
+        match &Segment::from_edge(&line.get(), &plane)[..] {
+            [Segment::Line { start, end }] => {
+                assert_close(*start, [1., 2.]);
+                assert_close(*end, [3., 4.]);
+            }
+            _ => panic!("Expected line"),
+        }
+
+        let circle = Circle {
+            center: Point::from([1., 1., 0.]),
+            a: [2., 0., 0.].into(),
+            b: [0., 2., 0.].into(),
+        };
+        let arc = Edge::builder(&mut shape)
+            .build_arc_from_points(circle, [[1., 3., 0.], [3., 1., 0.]])
+            .unwrap();
+        match &Segment::from_edge(&arc.get(), &plane)[..] {
+            [segment @ Segment::Arc(arc)] => {
+                assert!(arc.is_circle() && !arc.is_full());
+                assert!((arc.sweep - PI * 1.5).abs() < 1e-9);
+                assert_close(segment.start(), [1., 3.]);
+                assert_close(segment.end(), [3., 1.]);
+            }
+            _ => panic!("Expected arc"),
+        }
+
+        let ellipse = Edge::builder(&mut shape)
+            .build_ellipse([1.0.into(), 2.0.into()])
+            .unwrap();
+        match &Segment::from_edge(&ellipse.get(), &plane)[..] {
+            [Segment::Arc(arc)] => {
+                assert!(!arc.is_circle() && arc.is_full());
+                assert!((arc.ratio - 0.5).abs() < 1e-9);
+                assert!((arc.radius() - 2.).abs() < 1e-9);
+            }
+            _ => panic!("Expected ellipse"),
+        }
+
+        // Sketches represent arcs as NURBS curves, whose spans are merged
+        // into one arc.
+        let nurbs = Edge::builder(&mut shape)
+            .build_nurbs(NurbsCurve::arc(
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [0., 1., 0.],
+                PI * 1.5,
+            ))
+            .unwrap();
+        match &Segment::from_edge(&nurbs.get(), &plane)[..] {
+            [segment @ Segment::Arc(arc)] => {
+                assert!(arc.is_circle());
+                assert!((arc.sweep - PI * 1.5).abs() < 1e-9);
+                assert_close(segment.start(), [1., 0.]);
+                assert_close(segment.end(), [0., -1.]);
+            }
+            _ => panic!("Expected arc"),
+        }
+
+        let spline = Edge::builder(&mut shape)
+            .build_bspline_from_points(
+                3,
+                [[0., 0., 0.], [1., 2., 0.], [3., 2., 0.], [4., 0., 0.]],
+            )
+            .unwrap();
+        match &Segment::from_edge(&spline.get(), &plane)[..] {
+            [segment @ Segment::Spline(_)] => {
+                assert_close(segment.start(), [0., 0.]);
+                assert_close(segment.end(), [4., 0.]);
+            }
+            _ => panic!("Expected spline"),
+        }
+    }
+
+    /// Create a plate with a semicircular end and a round hole
+    ///
+    /// The plate spans from `[-5, -5]` to `[5, 10]` in the xy plane. Its
+    /// rounded end is centered on `[0, 5]`, the hole of radius 2 on the origin.
+    pub fn rounded_plate() -> Shape {
+        let mut shape = Shape::new();
+
+        let mut line = |a: [f64; 2], b: [f64; 2]| {
+            Edge::builder(&mut shape)
+                .build_line_segment_from_points([
+                    [a[0], a[1], 0.],
+                    [b[0], b[1], 0.],
+                ])
+                .unwrap()
+        };
+        let lines = [
+            line([-5., 5.], [-5., -5.]),
+            line([-5., -5.], [5., -5.]),
+            line([5., -5.], [5., 5.]),
+        ];
+        let arc = Edge::builder(&mut shape)
+            .build_arc_from_points(
+                Circle {
+                    center: Point::from([0., 5., 0.]),
+                    a: [5., 0., 0.].into(),
+                    b: [0., 5., 0.].into(),
+                },
+                [[5., 5., 0.], [-5., 5., 0.]],
+            )
+            .unwrap();
+        let hole = Edge::builder(&mut shape).build_circle(2.0.into()).unwrap();
+
+        let exterior = shape
+            .insert(Cycle::new(lines.into_iter().chain([arc])))
+            .unwrap();
+        let interior = shape.insert(Cycle::new([hole])).unwrap();
+        let surface = shape.insert(Surface::xy_plane()).unwrap();
+        shape
+            .insert(Face::new(
+                surface,
+                [exterior],
+                [interior],
+                [255, 0, 0, 255],
+            ))
+            .unwrap();
+
+        shape
+    }
+
+    /// Evaluate a rational Bézier curve, using de Casteljau's algorithm
+    fn bezier_point(bezier: &Bezier, s: f64) -> [f64; 2] {
+        let mut points: Vec<_> = bezier
+            .iter()
+            .map(|&([x, y], w)| [x * w, y * w, w])
+            .collect();
+
+        while points.len() > 1 {
+            points = points
+                .windows(2)
+                .map(|p| [0, 1, 2].map(|i| p[0][i] * (1. - s) + p[1][i] * s))
+                .collect();
+        }
+
+        let [x, y, w] = points[0];
+        [x / w, y / w]
+    }
+
+    fn spline_point(nurbs: &NurbsCurve<2>, t: f64) -> [f64; 2] {
+        super::spline_point(nurbs, Scalar::from_f64(t))
+    }
+
+    fn assert_close(a: [f64; 2], b: [f64; 2]) {
+        assert!(
+            (a[0] - b[0]).abs() < 1e-9 && (a[1] - b[1]).abs() < 1e-9,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+}
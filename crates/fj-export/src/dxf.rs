@@ -0,0 +1,565 @@
+//! Export to DXF files
+
+use std::{
+    f64::consts::PI,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_kernel::shape::Shape;
+
+use crate::{
+    drawing::{is_rational, Arc, Drawing, Segment},
+    Error,
+};
+
+/// Export a planar shape to a DXF file
+///
+/// Every cycle of the shape is written as a sequence of `LINE`, `ARC`,
+/// `CIRCLE`, `ELLIPSE`, and `SPLINE` entities, which are exact representations
+/// of the shape's edges. See [`Drawing`] for the exceptions.
+///
+/// The file is written in the format of AutoCAD 2000 (`AC1015`), which is the
+/// oldest version that most applications still read. Besides the entities, it
+/// contains the tables, blocks, and objects that this version requires. The
+/// drawing units are set to millimeters.
+pub fn export(shape: &Shape, path: &Path) -> Result<(), Error> {
+    let drawing = Drawing::from_shape(shape)?;
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write(&drawing, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn write(drawing: &Drawing, file: &mut impl Write) -> io::Result<()> {
+    let segments: Vec<_> = drawing
+        .faces
+        .iter()
+        .flatten()
+        .flat_map(|path| &path.segments)
+        .collect();
+    let [min, max] = drawing.bounding_box().unwrap_or([[0., 0.]; 2]);
+
+    pair(file, 0, "SECTION")?;
+    pair(file, 2, "HEADER")?;
+    pair(file, 9, "$ACADVER")?;
+    pair(file, 1, "AC1015")?;
+    pair(file, 9, "$HANDSEED")?;
+    handle(file, 5, FIRST_ENTITY + segments.len() as u32)?;
+    pair(file, 9, "$INSUNITS")?;
+    pair(file, 70, 4)?;
+    pair(file, 9, "$MEASUREMENT")?;
+    pair(file, 70, 1)?;
+    pair(file, 9, "$EXTMIN")?;
+    point(file, 10, min)?;
+    pair(file, 9, "$EXTMAX")?;
+    point(file, 10, max)?;
+    pair(file, 0, "ENDSEC")?;
+
+    pair(file, 0, "SECTION")?;
+    pair(file, 2, "CLASSES")?;
+    pair(file, 0, "ENDSEC")?;
+
+    pair(file, 0, "SECTION")?;
+    pair(file, 2, "TABLES")?;
+    tables(file, [min, max])?;
+    pair(file, 0, "ENDSEC")?;
+
+    pair(file, 0, "SECTION")?;
+    pair(file, 2, "BLOCKS")?;
+    block(file, "*Model_Space", MODEL_SPACE)?;
+    block(file, "*Paper_Space", PAPER_SPACE)?;
+    pair(file, 0, "ENDSEC")?;
+
+    pair(file, 0, "SECTION")?;
+    pair(file, 2, "ENTITIES")?;
+    for (segment, h) in segments.into_iter().zip(FIRST_ENTITY..) {
+        entity(file, segment, h)?;
+    }
+    pair(file, 0, "ENDSEC")?;
+
+    pair(file, 0, "SECTION")?;
+    pair(file, 2, "OBJECTS")?;
+    pair(file, 0, "DICTIONARY")?;
+    handle(file, 5, ROOT_DICTIONARY)?;
+    handle(file, 330, 0)?;
+    pair(file, 100, "AcDbDictionary")?;
+    pair(file, 281, 1)?;
+    pair(file, 3, "ACAD_GROUP")?;
+    handle(file, 350, GROUP_DICTIONARY)?;
+    pair(file, 0, "DICTIONARY")?;
+    handle(file, 5, GROUP_DICTIONARY)?;
+    handle(file, 330, ROOT_DICTIONARY)?;
+    pair(file, 100, "AcDbDictionary")?;
+    pair(file, 281, 1)?;
+    pair(file, 0, "ENDSEC")?;
+
+    pair(file, 0, "EOF")?;
+
+    Ok(())
+}
+
+// Handles of the objects that every file contains. AutoCAD 2000 requires every
+// object to have a handle, and every table entry to refer to its owner.
+const BLOCK_RECORD_TABLE: u32 = 0x1;
+const LAYER_TABLE: u32 = 0x2;
+const STYLE_TABLE: u32 = 0x3;
+const LTYPE_TABLE: u32 = 0x5;
+const VIEW_TABLE: u32 = 0x6;
+const UCS_TABLE: u32 = 0x7;
+const VPORT_TABLE: u32 = 0x8;
+const APPID_TABLE: u32 = 0x9;
+const DIMSTYLE_TABLE: u32 = 0xA;
+const ROOT_DICTIONARY: u32 = 0xC;
+const GROUP_DICTIONARY: u32 = 0xD;
+const LAYER: u32 = 0x10;
+const STYLE: u32 = 0x11;
+const APPID: u32 = 0x12;
+const LTYPES: [(&str, u32); 3] =
+    [("ByBlock", 0x14), ("ByLayer", 0x15), ("Continuous", 0x16)];
+const PAPER_SPACE: Block = Block {
+    record: 0x1B,
+    begin: 0x1C,
+    end: 0x1D,
+};
+const MODEL_SPACE: Block = Block {
+    record: 0x1F,
+    begin: 0x20,
+    end: 0x21,
+};
+const DIMSTYLE: u32 = 0x27;
+const VPORT: u32 = 0x29;
+
+/// The handle of the first entity
+///
+/// The entities are numbered consecutively from here.
+const FIRST_ENTITY: u32 = 0x100;
+
+/// The handles of a block and its record in the `BLOCK_RECORD` table
+struct Block {
+    record: u32,
+    begin: u32,
+    end: u32,
+}
+
+fn tables(file: &mut impl Write, [min, max]: [[f64; 2]; 2]) -> io::Result<()> {
+    start_table(file, "VPORT", VPORT_TABLE, 1)?;
+    table_entry(file, "VPORT", VPORT, VPORT_TABLE, "AcDbViewportTableRecord")?;
+    pair(file, 2, "*Active")?;
+    pair(file, 70, 0)?;
+    pair(file, 10, 0.)?;
+    pair(file, 20, 0.)?;
+    pair(file, 11, 1.)?;
+    pair(file, 21, 1.)?;
+    pair(file, 12, (min[0] + max[0]) / 2.)?;
+    pair(file, 22, (min[1] + max[1]) / 2.)?;
+    pair(
+        file,
+        40,
+        (max[1] - min[1]).max(max[0] - min[0]).max(1.) * 1.1,
+    )?;
+    pair(file, 41, 1.)?;
+    pair(file, 0, "ENDTAB")?;
+
+    start_table(file, "LTYPE", LTYPE_TABLE, LTYPES.len())?;
+    for (name, h) in LTYPES {
+        table_entry(file, "LTYPE", h, LTYPE_TABLE, "AcDbLinetypeTableRecord")?;
+        pair(file, 2, name)?;
+        pair(file, 70, 0)?;
+        pair(
+            file,
+            3,
+            if name == "Continuous" {
+                "Solid line"
+            } else {
+                ""
+            },
+        )?;
+        pair(file, 72, 65)?;
+        pair(file, 73, 0)?;
+        pair(file, 40, 0.)?;
+    }
+    pair(file, 0, "ENDTAB")?;
+
+    start_table(file, "LAYER", LAYER_TABLE, 1)?;
+    table_entry(file, "LAYER", LAYER, LAYER_TABLE, "AcDbLayerTableRecord")?;
+    pair(file, 2, 0)?;
+    pair(file, 70, 0)?;
+    pair(file, 62, 7)?;
+    pair(file, 6, "Continuous")?;
+    pair(file, 0, "ENDTAB")?;
+
+    start_table(file, "STYLE", STYLE_TABLE, 1)?;
+    table_entry(
+        file,
+        "STYLE",
+        STYLE,
+        STYLE_TABLE,
+        "AcDbTextStyleTableRecord",
+    )?;
+    pair(file, 2, "Standard")?;
+    pair(file, 70, 0)?;
+    pair(file, 40, 0.)?;
+    pair(file, 41, 1.)?;
+    pair(file, 50, 0.)?;
+    pair(file, 71, 0)?;
+    pair(file, 42, 2.5)?;
+    pair(file, 3, "txt")?;
+    pair(file, 0, "ENDTAB")?;
+
+    start_table(file, "VIEW", VIEW_TABLE, 0)?;
+    pair(file, 0, "ENDTAB")?;
+
+    start_table(file, "UCS", UCS_TABLE, 0)?;
+    pair(file, 0, "ENDTAB")?;
+
+    start_table(file, "APPID", APPID_TABLE, 1)?;
+    table_entry(file, "APPID", APPID, APPID_TABLE, "AcDbRegAppTableRecord")?;
+    pair(file, 2, "ACAD")?;
+    pair(file, 70, 0)?;
+    pair(file, 0, "ENDTAB")?;
+
+    // The `DIMSTYLE` table and its entries use a different group code for
+    // their handles, and the table has an additional subclass marker.
+    pair(file, 0, "TABLE")?;
+    pair(file, 2, "DIMSTYLE")?;
+    handle(file, 5, DIMSTYLE_TABLE)?;
+    handle(file, 330, 0)?;
+    pair(file, 100, "AcDbSymbolTable")?;
+    pair(file, 70, 1)?;
+    pair(file, 100, "AcDbDimStyleTable")?;
+    pair(file, 0, "DIMSTYLE")?;
+    handle(file, 105, DIMSTYLE)?;
+    handle(file, 330, DIMSTYLE_TABLE)?;
+    pair(file, 100, "AcDbSymbolTableRecord")?;
+    pair(file, 100, "AcDbDimStyleTableRecord")?;
+    pair(file, 2, "Standard")?;
+    pair(file, 70, 0)?;
+    handle(file, 340, STYLE)?;
+    pair(file, 0, "ENDTAB")?;
+
+    start_table(file, "BLOCK_RECORD", BLOCK_RECORD_TABLE, 2)?;
+    for (name, block) in
+        [("*Model_Space", MODEL_SPACE), ("*Paper_Space", PAPER_SPACE)]
+    {
+        table_entry(
+            file,
+            "BLOCK_RECORD",
+            block.record,
+            BLOCK_RECORD_TABLE,
+            "AcDbBlockTableRecord",
+        )?;
+        pair(file, 2, name)?;
+    }
+    pair(file, 0, "ENDTAB")
+}
+
+fn start_table(
+    file: &mut impl Write,
+    name: &str,
+    h: u32,
+    entries: usize,
+) -> io::Result<()> {
+    pair(file, 0, "TABLE")?;
+    pair(file, 2, name)?;
+    handle(file, 5, h)?;
+    handle(file, 330, 0)?;
+    pair(file, 100, "AcDbSymbolTable")?;
+    pair(file, 70, entries)
+}
+
+fn table_entry(
+    file: &mut impl Write,
+    kind: &str,
+    h: u32,
+    owner: u32,
+    subclass: &str,
+) -> io::Result<()> {
+    pair(file, 0, kind)?;
+    handle(file, 5, h)?;
+    handle(file, 330, owner)?;
+    pair(file, 100, "AcDbSymbolTableRecord")?;
+    pair(file, 100, subclass)
+}
+
+fn block(file: &mut impl Write, name: &str, block: Block) -> io::Result<()> {
+    pair(file, 0, "BLOCK")?;
+    handle(file, 5, block.begin)?;
+    handle(file, 330, block.record)?;
+    pair(file, 100, "AcDbEntity")?;
+    if block.record == PAPER_SPACE.record {
+        pair(file, 67, 1)?;
+    }
+    pair(file, 8, 0)?;
+    pair(file, 100, "AcDbBlockBegin")?;
+    pair(file, 2, name)?;
+    pair(file, 70, 0)?;
+    point(file, 10, [0., 0.])?;
+    pair(file, 3, name)?;
+
+    pair(file, 0, "ENDBLK")?;
+    handle(file, 5, block.end)?;
+    handle(file, 330, block.record)?;
+    pair(file, 100, "AcDbEntity")?;
+    if block.record == PAPER_SPACE.record {
+        pair(file, 67, 1)?;
+    }
+    pair(file, 8, 0)?;
+    pair(file, 100, "AcDbBlockEnd")
+}
+
+fn entity(file: &mut impl Write, segment: &Segment, h: u32) -> io::Result<()> {
+    match segment {
+        Segment::Line { start, end } => {
+            start_entity(file, "LINE", h, "AcDbLine")?;
+            point(file, 10, *start)?;
+            point(file, 11, *end)?;
+        }
+        Segment::Arc(arc) if arc.is_circle() => {
+            if arc.is_full() {
+                start_entity(file, "CIRCLE", h, "AcDbCircle")?;
+                point(file, 10, arc.center)?;
+                pair(file, 40, arc.radius())?;
+            } else {
+                let [start, end] = ccw_range(arc).map(|u| {
+                    (arc.rotation() + u).to_degrees().rem_euclid(360.)
+                });
+
+                start_entity(file, "ARC", h, "AcDbCircle")?;
+                point(file, 10, arc.center)?;
+                pair(file, 40, arc.radius())?;
+                pair(file, 100, "AcDbArc")?;
+                pair(file, 50, start)?;
+                pair(file, 51, end)?;
+            }
+        }
+        Segment::Arc(arc) => {
+            let [start, end] = if arc.is_full() {
+                [0., 2. * PI]
+            } else {
+                ccw_range(arc).map(|u| u.rem_euclid(2. * PI))
+            };
+
+            start_entity(file, "ELLIPSE", h, "AcDbEllipse")?;
+            point(file, 10, arc.center)?;
+            point(file, 11, arc.major)?;
+            normal(file)?;
+            pair(file, 40, arc.ratio)?;
+            pair(file, 41, start)?;
+            pair(file, 42, end)?;
+        }
+        Segment::Spline(nurbs) => {
+            let rational = is_rational(nurbs);
+
+            // The curve is planar, and maybe rational.
+            let flags = if rational { 8 | 4 } else { 8 };
+
+            start_entity(file, "SPLINE", h, "AcDbSpline")?;
+            normal(file)?;
+            pair(file, 70, flags)?;
+            pair(file, 71, nurbs.degree)?;
+            pair(file, 72, nurbs.knots.len())?;
+            pair(file, 73, nurbs.control_points.len())?;
+            pair(file, 74, 0)?;
+            for knot in &nurbs.knots {
+                pair(file, 40, knot)?;
+            }
+            if rational {
+                for weight in &nurbs.weights {
+                    pair(file, 41, weight)?;
+                }
+            }
+            for control_point in &nurbs.control_points {
+                point(
+                    file,
+                    10,
+                    [control_point.u.into_f64(), control_point.v.into_f64()],
+                )?;
+            }
+        }
+        Segment::Polyline(points) => {
+            start_entity(file, "LWPOLYLINE", h, "AcDbPolyline")?;
+            pair(file, 90, points.len())?;
+            pair(file, 70, 0)?;
+            for &[x, y] in points {
+                pair(file, 10, x)?;
+                pair(file, 20, y)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the start and end parameters of an arc, in counter-clockwise order
+///
+/// DXF arcs always go counter-clockwise from their start to their end.
+fn ccw_range(arc: &Arc) -> [f64; 2] {
+    let end = arc.start + arc.sweep;
+
+    if arc.sweep >= 0. {
+        [arc.start, end]
+    } else {
+        [end, arc.start]
+    }
+}
+
+/// Start an entity in model space, on layer `0`
+fn start_entity(
+    file: &mut impl Write,
+    kind: &str,
+    h: u32,
+    subclass: &str,
+) -> io::Result<()> {
+    pair(file, 0, kind)?;
+    handle(file, 5, h)?;
+    handle(file, 330, MODEL_SPACE.record)?;
+    pair(file, 100, "AcDbEntity")?;
+    pair(file, 8, 0)?;
+    pair(file, 100, subclass)
+}
+
+fn point(file: &mut impl Write, code: u32, [x, y]: [f64; 2]) -> io::Result<()> {
+    pair(file, code, x)?;
+    pair(file, code + 10, y)?;
+    pair(file, code + 20, 0.)
+}
+
+/// Write the extrusion direction of an entity
+fn normal(file: &mut impl Write) -> io::Result<()> {
+    pair(file, 210, 0.)?;
+    pair(file, 220, 0.)?;
+    pair(file, 230, 1.)
+}
+
+/// Write a handle, which is a hexadecimal number
+fn handle(file: &mut impl Write, code: u32, h: u32) -> io::Result<()> {
+    pair(file, code, format_args!("{:X}", h))
+}
+
+fn pair(
+    file: &mut impl Write,
+    code: u32,
+    value: impl std::fmt::Display,
+) -> io::Result<()> {
+    writeln!(file, "{:>3}", code)?;
+    writeln!(file, "{}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use fj::{import::dxf_from_str, Shape2d, SketchSegment};
+
+    use crate::drawing::{tests::rounded_plate, Drawing};
+
+    #[test]
+    fn header() {
+        let dxf = to_string(&rounded_plate());
+        let pairs = pairs(&dxf);
+
+        assert_eq!(value(&pairs, "$ACADVER"), "AC1015");
+        assert_eq!(value(&pairs, "$INSUNITS"), "4");
+
+        // Every object has a unique handle, and the handle seed is larger than
+        // all of them.
+        let handles: Vec<_> = pairs
+            .windows(2)
+            .filter(|pairs| pairs[0] != (9, "$HANDSEED"))
+            .map(|pairs| pairs[1])
+            .filter(|(code, _)| *code == 5 || *code == 105)
+            .map(|(_, value)| u32::from_str_radix(value, 16).unwrap())
+            .collect();
+        let unique: HashSet<_> = handles.iter().collect();
+        assert_eq!(unique.len(), handles.len());
+
+        let seed = u32::from_str_radix(value(&pairs, "$HANDSEED"), 16).unwrap();
+        assert!(handles.iter().all(|&handle| handle < seed));
+
+        // Every owner is an object of the file.
+        for (code, value) in &pairs {
+            if *code == 330 && *value != "0" {
+                let owner = u32::from_str_radix(value, 16).unwrap();
+                assert!(unique.contains(&owner), "Unknown owner {}", value);
+            }
+        }
+
+        let sections: Vec<_> = pairs
+            .windows(2)
+            .filter(|pairs| pairs[0] == (0, "SECTION"))
+            .map(|pairs| pairs[1].1)
+            .collect();
+        assert_eq!(
+            sections,
+            ["HEADER", "CLASSES", "TABLES", "BLOCKS", "ENTITIES", "OBJECTS"]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let shapes = dxf_from_str(&to_string(&rounded_plate())).unwrap();
+        assert_eq!(shapes.len(), 1);
+
+        let difference = match &shapes[0] {
+            Shape2d::Difference(difference) => difference,
+            shape => panic!("Expected difference, got {:?}", shape),
+        };
+
+        let exterior = match difference.shape() {
+            Shape2d::Sketch(sketch) => sketch.to_segments(),
+            shape => panic!("Expected sketch, got {:?}", shape),
+        };
+        assert_eq!(exterior.len(), 4);
+        let arcs: Vec<_> = exterior
+            .iter()
+            .filter_map(|segment| match segment {
+                SketchSegment::Arc { center, .. } => Some(*center),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(arcs.len(), 1);
+        assert!(arcs[0][0].abs() < 1e-9 && (arcs[0][1] - 5.).abs() < 1e-9);
+
+        let hole = match difference.holes() {
+            [Shape2d::Sketch(sketch)] => sketch,
+            holes => panic!("Expected one hole, got {:?}", holes),
+        };
+        match &hole.to_segments()[..] {
+            [SketchSegment::Arc { center, end, .. }] => {
+                assert_eq!(*center, [0., 0.]);
+                assert_eq!(*end, [2., 0.]);
+            }
+            segments => panic!("Expected circle, got {:?}", segments),
+        }
+    }
+
+    fn to_string(shape: &fj_kernel::shape::Shape) -> String {
+        let drawing = Drawing::from_shape(shape).unwrap();
+
+        let mut dxf = Vec::new();
+        super::write(&drawing, &mut dxf).unwrap();
+
+        String::from_utf8(dxf).unwrap()
+    }
+
+    fn pairs(dxf: &str) -> Vec<(u32, &str)> {
+        let lines: Vec<_> = dxf.lines().collect();
+        lines
+            .chunks(2)
+            .map(|pair| (pair[0].trim().parse().unwrap(), pair[1]))
+            .collect()
+    }
+
+    /// Access the value of a header variable
+    fn value<'a>(pairs: &[(u32, &'a str)], variable: &str) -> &'a str {
+        let i = pairs
+            .iter()
+            .position(|&pair| pair == (9, variable))
+            .unwrap();
+        pairs[i + 1].1
+    }
+}
@@ -0,0 +1,309 @@
+//! Export to glTF files
+
+use std::{collections::HashMap, fs, path::Path};
+
+use fj_interop::mesh::{Color, Mesh};
+use fj_math::Point;
+
+use crate::Error;
+
+/// Export the mesh to a glTF file
+///
+/// If `binary` is `true`, a GLB file is written. Otherwise, the binary data is
+/// embedded into the JSON file, so the result is a single, self-contained
+/// file either way.
+///
+/// glTF only supports colors per vertex, so vertices that are shared by
+/// triangles of different colors are duplicated.
+///
+/// The vertices are written in the coordinate system of the model. A transform
+/// on the node that refers to the mesh converts them into the coordinate
+/// system of glTF, which uses meters and has its y axis pointing up.
+pub fn export(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+    binary: bool,
+) -> Result<(), Error> {
+    fs::write(path, to_bytes(mesh, binary))?;
+    Ok(())
+}
+
+fn to_bytes(mesh: &Mesh<Point<3>>, binary: bool) -> Vec<u8> {
+    let data = Data::new(mesh);
+
+    let buffer = if binary {
+        format!(r#"{{"byteLength":{}}}"#, data.buffer.len())
+    } else {
+        format!(
+            r#"{{"byteLength":{},"uri":"data:application/octet-stream;base64,{}"}}"#,
+            data.buffer.len(),
+            base64::encode(&data.buffer),
+        )
+    };
+    let json = data.json(&buffer);
+
+    if binary {
+        glb(json.into_bytes(), data.buffer)
+    } else {
+        json.into_bytes()
+    }
+}
+
+/// The geometry of a mesh, laid out in a glTF buffer
+struct Data {
+    buffer: Vec<u8>,
+    vertices: usize,
+    indices: usize,
+    min: [f32; 3],
+    max: [f32; 3],
+    transparent: bool,
+}
+
+impl Data {
+    fn new(mesh: &Mesh<Point<3>>) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let mut indices_by_vertex: HashMap<(Point<3>, Color), u32> =
+            HashMap::new();
+
+        for triangle in mesh.triangles() {
+            for point in triangle.points {
+                let index = *indices_by_vertex
+                    .entry((point, triangle.color))
+                    .or_insert_with(|| {
+                        vertices.push((point, triangle.color));
+                        vertices.len() as u32 - 1
+                    });
+                indices.push(index);
+            }
+        }
+
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        let mut transparent = false;
+
+        for (point, color) in &vertices {
+            let position = point.coords.components.map(|s| s.into_f32());
+            for i in 0..3 {
+                min[i] = min[i].min(position[i]);
+                max[i] = max[i].max(position[i]);
+            }
+            positions.extend(position);
+
+            let [r, g, b, a] = *color;
+            colors.extend([
+                srgb_to_linear(r),
+                srgb_to_linear(g),
+                srgb_to_linear(b),
+                f32::from(a) / 255.,
+            ]);
+            transparent |= a < u8::MAX;
+        }
+
+        let buffer = positions
+            .into_iter()
+            .chain(colors)
+            .flat_map(f32::to_le_bytes)
+            .chain(indices.iter().flat_map(|index| index.to_le_bytes()))
+            .collect();
+
+        Self {
+            buffer,
+            vertices: vertices.len(),
+            indices: indices.len(),
+            min,
+            max,
+            transparent,
+        }
+    }
+
+    fn json(&self, buffer: &str) -> String {
+        // glTF uses meters and has its y axis pointing up. This matrix scales
+        // from millimeters and rotates the z axis into the y axis. It's stored
+        // in column-major order.
+        const MATRIX: &str = "[0.001,0,0,0,0,0,-0.001,0,0,0.001,0,0,0,0,0,1]";
+
+        let asset = r#""asset":{"version":"2.0","generator":"Fornjot"}"#;
+        let scenes = r#""scene":0,"scenes":[{"nodes":[0]}]"#;
+
+        // A glTF file can't contain empty accessors, so an empty mesh results
+        // in a scene with a single node that doesn't refer to anything.
+        if self.indices == 0 {
+            return format!(
+                r#"{{{},{},"nodes":[{{"matrix":{}}}]}}"#,
+                asset, scenes, MATRIX
+            );
+        }
+
+        let positions = self.vertices * 12;
+        let colors = self.vertices * 16;
+        let indices = self.indices * 4;
+
+        let alpha_mode = if self.transparent { "BLEND" } else { "OPAQUE" };
+
+        format!(
+            concat!(
+                "{{{asset},{scenes},",
+                r#""nodes":[{{"mesh":0,"matrix":{matrix}}}],"#,
+                r#""meshes":[{{"primitives":[{{"#,
+                r#""attributes":{{"POSITION":0,"COLOR_0":1}},"#,
+                r#""indices":2,"material":0}}]}}],"#,
+                r#""materials":[{{"#,
+                r#""pbrMetallicRoughness":{{"metallicFactor":0}},"#,
+                r#""alphaMode":"{alpha_mode}"}}],"#,
+                r#""buffers":[{buffer}],"#,
+                r#""bufferViews":["#,
+                r#"{{"buffer":0,"byteOffset":0,"byteLength":{positions},"target":34962}},"#,
+                r#"{{"buffer":0,"byteOffset":{positions},"byteLength":{colors},"target":34962}},"#,
+                r#"{{"buffer":0,"byteOffset":{colors_end},"byteLength":{indices},"target":34963}}],"#,
+                r#""accessors":["#,
+                r#"{{"bufferView":0,"componentType":5126,"count":{vertices},"type":"VEC3","min":{min},"max":{max}}},"#,
+                r#"{{"bufferView":1,"componentType":5126,"count":{vertices},"type":"VEC4"}},"#,
+                r#"{{"bufferView":2,"componentType":5125,"count":{count},"type":"SCALAR"}}]}}"#,
+            ),
+            asset = asset,
+            scenes = scenes,
+            matrix = MATRIX,
+            alpha_mode = alpha_mode,
+            buffer = buffer,
+            positions = positions,
+            colors = colors,
+            colors_end = positions + colors,
+            indices = indices,
+            vertices = self.vertices,
+            count = self.indices,
+            min = array(self.min),
+            max = array(self.max),
+        )
+    }
+}
+
+/// Assemble a GLB file from its JSON and binary chunks
+fn glb(mut json: Vec<u8>, mut buffer: Vec<u8>) -> Vec<u8> {
+    const MAGIC: u32 = 0x46546C67;
+    const VERSION: u32 = 2;
+    const JSON: u32 = 0x4E4F534A;
+    const BIN: u32 = 0x004E4942;
+
+    // Chunks must be aligned to 4 bytes. The JSON chunk is padded with
+    // spaces, the binary chunk with zeros.
+    json.resize(json.len() + (4 - json.len() % 4) % 4, b' ');
+    buffer.resize(buffer.len() + (4 - buffer.len() % 4) % 4, 0);
+
+    let length = 12 + 8 + json.len() + 8 + buffer.len();
+
+    let mut glb = Vec::with_capacity(length);
+    for word in [MAGIC, VERSION, length as u32, json.len() as u32, JSON] {
+        glb.extend(word.to_le_bytes());
+    }
+    glb.extend(json);
+    for word in [buffer.len() as u32, BIN] {
+        glb.extend(word.to_le_bytes());
+    }
+    glb.extend(buffer);
+
+    glb
+}
+
+/// Convert an sRGB color component into a linear one, as used by glTF
+fn srgb_to_linear(component: u8) -> f32 {
+    let c = f32::from(component) / 255.;
+
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn array([x, y, z]: [f32; 3]) -> String {
+    format!("[{},{},{}]", x, y, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    #[test]
+    fn gltf() {
+        // The triangles share two vertices, but not their colors.
+        let mesh = mesh([[255, 0, 0, 255], [0, 0, 255, 255]]);
+        let json = String::from_utf8(super::to_bytes(&mesh, false)).unwrap();
+
+        assert!(json.contains(r#""version":"2.0""#));
+        assert!(json.contains(r#""count":6,"type":"VEC3""#));
+        assert!(json.contains(r#""count":6,"type":"VEC4""#));
+        assert!(json.contains(r#""count":6,"type":"SCALAR""#));
+        assert!(json.contains(r#""alphaMode":"OPAQUE""#));
+
+        let prefix = "data:application/octet-stream;base64,";
+        let start = json.find(prefix).unwrap() + prefix.len();
+        let end = start + json[start..].find('"').unwrap();
+        let buffer = base64::decode(&json[start..end]).unwrap();
+
+        // Positions and colors come first, followed by the indices, which
+        // start at 0.
+        assert_eq!(indices(&buffer[6 * (12 + 16)..]), [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn glb() {
+        // The triangles share two vertices, including their colors.
+        let mesh = mesh([[255, 0, 0, 128]; 2]);
+        let glb = super::to_bytes(&mesh, true);
+
+        assert_eq!(&glb[..4], b"glTF");
+        assert_eq!(word(&glb[4..]), 2);
+        assert_eq!(word(&glb[8..]) as usize, glb.len());
+
+        let json_len = word(&glb[12..]) as usize;
+        assert_eq!(&glb[16..20], b"JSON");
+        assert_eq!(json_len % 4, 0);
+
+        let json = std::str::from_utf8(&glb[20..20 + json_len]).unwrap();
+        assert!(json.contains(r#""count":4,"type":"VEC3""#));
+        assert!(json.contains(r#""count":6,"type":"SCALAR""#));
+        assert!(json.contains(r#""alphaMode":"BLEND""#));
+
+        let bin = &glb[20 + json_len..];
+        let bin_len = word(bin) as usize;
+        assert_eq!(&bin[4..8], b"BIN\0");
+        assert_eq!(bin_len, bin.len() - 8);
+
+        let buffer = &bin[8..];
+        assert_eq!(indices(&buffer[4 * (12 + 16)..]), [0, 1, 2, 1, 3, 2]);
+    }
+
+    #[test]
+    fn empty() {
+        let json = super::to_bytes(&Mesh::new(), false);
+        let json = String::from_utf8(json).unwrap();
+
+        assert!(!json.contains("accessors"));
+    }
+
+    fn mesh(colors: [[u8; 4]; 2]) -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+
+        let triangles = [
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+            [[1., 0., 0.], [1., 1., 0.], [0., 1., 0.]],
+        ];
+        for (points, color) in triangles.into_iter().zip(colors) {
+            mesh.push_triangle(points.map(Point::from), color);
+        }
+
+        mesh
+    }
+
+    fn indices(bytes: &[u8]) -> Vec<u32> {
+        bytes.chunks_exact(4).map(word).collect()
+    }
+
+    fn word(bytes: &[u8]) -> u32 {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
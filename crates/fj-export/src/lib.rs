@@ -14,102 +14,211 @@
 
 #![warn(missing_docs)]
 
-use std::{fs::File, path::Path};
+mod drawing;
+mod dxf;
+mod gltf;
+mod obj;
+mod ply;
+mod step;
+mod stl;
+mod svg;
+mod threemf;
+
+use std::{collections::BTreeMap, io, path::Path, str::FromStr};
 
 use thiserror::Error;
 
 use fj_interop::mesh::Mesh;
-use fj_math::{Point, Triangle};
+use fj_kernel::{
+    algorithms::{section, SectionError, Tolerance},
+    geometry::Surface,
+    shape::{Shape, ValidationError, ValidationLevel},
+};
+use fj_math::Point;
 
-/// Export the provided mesh to the file at the given path.
+/// Export the provided shape to the file at the given path.
 ///
 /// This function will create a file if it does not exist, and will truncate it if it does.
 ///
-/// Currently 3MF & STL file types are supported. The case insensitive file extension of
-/// the provided path is used to switch between supported types.
-pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    match path.extension() {
-        Some(extension) if extension.to_ascii_uppercase() == "3MF" => {
-            export_3mf(mesh, path)
-        }
-        Some(extension) if extension.to_ascii_uppercase() == "STL" => {
-            export_stl(mesh, path)
-        }
-        Some(extension) => Err(Error::InvalidExtension(
-            extension.to_string_lossy().into_owned(),
-        )),
-        None => Err(Error::NoExtension),
+/// The case insensitive file extension of the provided path is used to determine the file
+/// format. See [`Format::from_path`] for the supported extensions. Use [`export_as`] to
+/// select a format explicitly.
+pub fn export(
+    shape: &Shape,
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+    path: &Path,
+) -> Result<(), Error> {
+    export_as(shape, mesh, metadata, path, Format::from_path(path)?)
+}
+
+/// Export the provided shape to the file at the given path, in the given format.
+///
+/// This function will create a file if it does not exist, and will truncate it if it does.
+///
+/// DXF, STEP, and SVG files are written from the boundary representation in `shape`, all other
+/// formats from `mesh`, which is the triangulation of `shape`. DXF and SVG files can only be
+/// written for shapes whose faces all lie in one plane, like those of 2D models.
+///
+/// If the validation level of `shape` is [`ValidationLevel::Full`] or [`ValidationLevel::Deferred`],
+/// its shells are checked to be closed manifolds first. See [`Shape::validate_manifold`].
+pub fn export_as(
+    shape: &Shape,
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+    path: &Path,
+    format: Format,
+) -> Result<(), Error> {
+    validate_manifold(shape)?;
+
+    match format {
+        Format::ThreeMf => threemf::export(mesh, path),
+        Format::Dxf => dxf::export(shape, path),
+        Format::Gltf => gltf::export(mesh, path, false),
+        Format::Glb => gltf::export(mesh, path, true),
+        Format::Obj => obj::export(mesh, path),
+        Format::Ply => ply::export(mesh, path),
+        Format::Step => step::export(shape, path),
+        Format::Stl => stl::export(mesh, metadata, false, path),
+        Format::StlAscii => stl::export_ascii(mesh, metadata, path),
+        Format::StlColor => stl::export(mesh, metadata, true, path),
+        Format::Svg => svg::export(shape, path),
     }
 }
 
-fn export_3mf(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let vertices = mesh.vertices().map(|vertex| vertex.into()).collect();
-
-    let indices: Vec<_> = mesh.indices().collect();
-    let triangles = indices
-        .chunks(3)
-        .map(|triangle| {
-            [
-                triangle[0] as usize,
-                triangle[1] as usize,
-                triangle[2] as usize,
-            ]
-        })
-        .collect();
-
-    let mesh = threemf::TriangleMesh {
-        vertices,
-        triangles,
+/// Export the section of the provided shape with a plane to the file at the given path
+///
+/// This function will create a file if it does not exist, and will truncate it if it does.
+///
+/// The section is computed using [`section`], with the given tolerance, and written as a drawing.
+/// Only DXF and SVG files can be written, as the section is planar. `plane` must be a plane.
+///
+/// `shape` is validated the same way as by [`export_as`].
+pub fn export_section(
+    shape: &Shape,
+    plane: &Surface,
+    tolerance: Tolerance,
+    path: &Path,
+    format: Format,
+) -> Result<(), Error> {
+    let export = match format {
+        Format::Dxf => dxf::export,
+        Format::Svg => svg::export,
+        format => return Err(Error::InvalidSectionFormat(format)),
     };
+    validate_manifold(shape)?;
 
-    threemf::write(path, &mesh)?;
+    let section =
+        section(shape, plane, tolerance, [255, 0, 0, 255]).map_err(Box::new)?;
+    export(&section, path)
+}
+
+/// Validate that the shells of a shape are closed manifolds, if the shape is fully validated
+///
+/// Shapes with open, inconsistently oriented or inverted shells result in files that other
+/// applications can't make sense of. Shapes that are only validated structurally are exported as
+/// they are.
+fn validate_manifold(shape: &Shape) -> Result<(), Error> {
+    match shape.validation_config().level {
+        ValidationLevel::Full | ValidationLevel::Deferred => {
+            shape.validate_manifold().map_err(Box::new)?;
+        }
+        ValidationLevel::Off | ValidationLevel::Structural => {}
+    }
 
     Ok(())
 }
 
-fn export_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let points = mesh
-        .triangles()
-        .map(|triangle| triangle.points)
-        .collect::<Vec<_>>();
-
-    let vertices = points.iter().map(|points| {
-        points.map(|point| point.coords.components.map(|s| s.into_f32()))
-    });
-
-    let normals = points
-        .iter()
-        .map(|&points| points.into())
-        .map(|triangle: Triangle<3>| triangle.normal())
-        .map(|vector| vector.components.map(|s| s.into_f32()));
-
-    let triangles = vertices
-        .zip(normals)
-        .map(|([v1, v2, v3], normal)| stl::Triangle {
-            normal,
-            v1,
-            v2,
-            v3,
-            attr_byte_count: 0,
-        })
-        .collect::<Vec<_>>();
-
-    let mut file = File::create(path)?;
-
-    let binary_stl_file = stl::BinaryStlFile {
-        header: stl::BinaryStlHeader {
-            header: [0u8; 80],
-            num_triangles: triangles
-                .len()
-                .try_into()
-                .map_err(|_| Error::InvalidTriangleCount)?,
-        },
-        triangles,
-    };
+/// Information about the exported model
+///
+/// Formats that have room for it include this information in the exported
+/// file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Metadata {
+    /// The name of the model
+    pub name: Option<String>,
+
+    /// The parameters that the model was created with
+    pub parameters: BTreeMap<String, String>,
+}
 
-    stl::write_stl(&mut file, &binary_stl_file)?;
+/// A file format that shapes can be exported to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// 3D Manufacturing Format
+    ThreeMf,
 
-    Ok(())
+    /// Drawing Exchange Format, for planar shapes
+    Dxf,
+
+    /// glTF, with the binary data embedded into the JSON file
+    Gltf,
+
+    /// Binary glTF
+    Glb,
+
+    /// Wavefront OBJ
+    Obj,
+
+    /// Polygon File Format, with per-triangle colors
+    Ply,
+
+    /// STEP, written according to the AP214 schema
+    Step,
+
+    /// Binary STL
+    Stl,
+
+    /// ASCII STL
+    StlAscii,
+
+    /// Binary STL, with the color of each triangle in its attribute bytes
+    StlColor,
+
+    /// Scalable Vector Graphics, for planar shapes
+    Svg,
+}
+
+impl Format {
+    /// Determine the format from the extension of the provided path
+    ///
+    /// The extension is parsed as the name of a format, as described in the
+    /// implementation of [`FromStr`]. That means `stl` files are written in
+    /// the binary variant, without colors. The other STL variants have to be
+    /// selected explicitly.
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        let extension = path.extension().ok_or(Error::NoExtension)?;
+        let extension = extension.to_string_lossy();
+
+        extension
+            .parse()
+            .map_err(|_| Error::InvalidExtension(extension.into_owned()))
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    /// Parse the name of a format
+    ///
+    /// Recognizes the names `3mf`, `dxf`, `gltf`, `glb`, `obj`, `ply`, `step`,
+    /// `stp`, `stl`, `stl-ascii`, `stl-color`, and `svg`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "3mf" => Ok(Self::ThreeMf),
+            "dxf" => Ok(Self::Dxf),
+            "gltf" => Ok(Self::Gltf),
+            "glb" => Ok(Self::Glb),
+            "obj" => Ok(Self::Obj),
+            "ply" => Ok(Self::Ply),
+            "step" | "stp" => Ok(Self::Step),
+            "stl" => Ok(Self::Stl),
+            "stl-ascii" => Ok(Self::StlAscii),
+            "stl-color" => Ok(Self::StlColor),
+            "svg" => Ok(Self::Svg),
+            _ => Err(Error::InvalidFormat(s.to_owned())),
+        }
+    }
 }
 
 /// An error that can occur while exporting
@@ -123,15 +232,259 @@ pub enum Error {
     #[error("unrecognised extension found `{0:?}`")]
     InvalidExtension(String),
 
+    /// Unrecognised format name found
+    #[error("unrecognised format found `{0:?}`")]
+    InvalidFormat(String),
+
     /// I/O error whilst exporting to file
     #[error("I/O error whilst exporting to file")]
-    Io(#[from] std::io::Error),
+    Io(#[from] io::Error),
 
     /// Maximum triangle count exceeded
     #[error("maximum triangle count exceeded")]
     InvalidTriangleCount,
 
-    /// Threemf error whilst exporting to 3MF file
-    #[error("threemf error whilst exporting to 3MF file")]
-    ThreeMF(#[from] threemf::Error),
+    /// Shape is not planar
+    #[error("shape is not planar, which is required for 2D formats")]
+    NotPlanar,
+
+    /// Section can't be exported to a 3D format
+    #[error("section can't be exported as `{0:?}`, only as DXF or SVG")]
+    InvalidSectionFormat(Format),
+
+    /// Shape is invalid
+    #[error("invalid shape")]
+    Validation(#[from] Box<ValidationError>),
+
+    /// Section of the shape couldn't be computed
+    #[error("section couldn't be computed")]
+    Section(#[from] Box<SectionError>),
+
+    /// Degenerate triangle found
+    #[error("degenerate triangle found `{0:?}`")]
+    DegenerateTriangle([Point<3>; 3]),
+
+    /// Zip error whilst exporting to 3MF file
+    #[error("zip error whilst exporting to 3MF file")]
+    Zip(#[from] zip::result::ZipError),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process};
+
+    use fj_interop::{debug::DebugInfo, mesh::Mesh};
+    use fj_kernel::{
+        algorithms::{sweep_shape, triangulate, Tolerance},
+        geometry::Surface,
+        shape::{Shape, ValidationConfig, ValidationError, ValidationLevel},
+        topology::{Face, Shell},
+    };
+    use fj_math::{Point, Vector};
+
+    use crate::{Error, Format, Metadata};
+
+    #[test]
+    fn format_from_path() {
+        let format = |path: &str| Format::from_path(path.as_ref());
+
+        assert_eq!(format("model.3mf").unwrap(), Format::ThreeMf);
+        assert_eq!(format("model.GLB").unwrap(), Format::Glb);
+        assert_eq!(format("dir.d/model.Stp").unwrap(), Format::Step);
+        assert_eq!(format("model.stl").unwrap(), Format::Stl);
+
+        assert!(matches!(format("model"), Err(Error::NoExtension)));
+        assert!(matches!(
+            format("model.txt"),
+            Err(Error::InvalidExtension(extension)) if extension == "txt"
+        ));
+    }
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!("stl-ascii".parse::<Format>().unwrap(), Format::StlAscii);
+        assert_eq!("STL-Color".parse::<Format>().unwrap(), Format::StlColor);
+        assert!(matches!(
+            "stl-binary".parse::<Format>(),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn export_is_deterministic() {
+        let formats = [
+            (Format::ThreeMf, "3mf"),
+            (Format::Gltf, "gltf"),
+            (Format::Glb, "glb"),
+            (Format::Obj, "obj"),
+            (Format::Ply, "ply"),
+            (Format::Step, "step"),
+            (Format::Stl, "stl"),
+            (Format::StlAscii, "stl"),
+            (Format::StlColor, "stl"),
+        ];
+
+        for (format, extension) in formats {
+            // Build the model from scratch for every export, so nothing is
+            // shared between them, except for the code that produced them.
+            let [a, b] = [0, 1].map(|run| {
+                let (shape, mesh) = model();
+
+                // Some formats contain the file name, so it must be the same
+                // for both exports.
+                let dir = std::env::temp_dir().join(format!(
+                    "fj-export-deterministic-{}-{}",
+                    process::id(),
+                    run
+                ));
+                fs::create_dir_all(&dir).unwrap();
+                let path = dir.join(format!("model.{}", extension));
+
+                super::export_as(
+                    &shape,
+                    &mesh,
+                    &Metadata::default(),
+                    &path,
+                    format,
+                )
+                .unwrap();
+
+                let data = fs::read(&path).unwrap();
+                fs::remove_dir_all(&dir).unwrap();
+
+                data
+            });
+
+            assert!(a == b, "Exporting as {:?} is not deterministic", format);
+        }
+    }
+
+    #[test]
+    fn export_section() {
+        let (shape, _) = model();
+        let plane = Surface::plane_from_points([
+            [0., 0., 0.5],
+            [1., 0., 0.5],
+            [0., 1., 0.5],
+        ]);
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        let dir = std::env::temp_dir()
+            .join(format!("fj-export-section-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("section.svg");
+
+        super::export_section(&shape, &plane, tolerance, &path, Format::Svg)
+            .unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+
+        let result = super::export_section(
+            &shape,
+            &plane,
+            tolerance,
+            &dir.join("section.stl"),
+            Format::Stl,
+        );
+        fs::remove_dir_all(&dir).unwrap();
+
+        // The section is the plate that the model was swept from.
+        let shapes = fj::import::svg_from_str(&svg).unwrap();
+        match &shapes[..] {
+            [fj::Shape2d::Difference(difference)] => {
+                assert_eq!(difference.holes().len(), 2);
+            }
+            shapes => panic!("Expected plate with holes, got {:?}", shapes),
+        }
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidSectionFormat(Format::Stl))
+        ));
+    }
+
+    #[test]
+    fn export_validates_manifold() {
+        let (mut shape, mesh) = model();
+
+        // Add a second shell, which lacks one of the faces of the model.
+        let mut faces = shape.shells().next().unwrap().get().faces;
+        faces.pop();
+        shape.insert(Shell::new(faces)).unwrap();
+
+        let dir = std::env::temp_dir()
+            .join(format!("fj-export-manifold-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("model.stl");
+
+        let full = super::export_as(
+            &shape,
+            &mesh,
+            &Metadata::default(),
+            &path,
+            Format::Stl,
+        );
+
+        let shape = shape.with_validation_config(ValidationConfig {
+            level: ValidationLevel::Structural,
+            ..ValidationConfig::default()
+        });
+        let structural = super::export_as(
+            &shape,
+            &mesh,
+            &Metadata::default(),
+            &path,
+            Format::Stl,
+        );
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(
+            full,
+            Err(Error::Validation(error))
+                if matches!(*error, ValidationError::Manifold(_))
+        ));
+        assert!(structural.is_ok());
+    }
+
+    /// A plate with two holes
+    ///
+    /// The holes are stored in a set during triangulation, and the rectangles
+    /// can be triangulated in two equally valid ways, which makes this a good
+    /// test for any source of non-determinism.
+    fn model() -> (Shape, Mesh<Point<3>>) {
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        let mut sketch = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut sketch)
+            .with_exterior_polygon([
+                [0., 0., 0.],
+                [6., 0., 0.],
+                [6., 4., 0.],
+                [0., 4., 0.],
+            ])
+            .with_interior_polygon([
+                [1., 1., 0.],
+                [1., 3., 0.],
+                [2., 3., 0.],
+                [2., 1., 0.],
+            ])
+            .with_interior_polygon([
+                [4., 1., 0.],
+                [4., 3., 0.],
+                [5., 3., 0.],
+                [5., 1., 0.],
+            ])
+            .build()
+            .unwrap();
+
+        let shape = sweep_shape(
+            sketch,
+            Vector::from([0., 0., 1.]),
+            tolerance,
+            [255, 0, 0, 255],
+        )
+        .unwrap();
+        let mesh = triangulate(shape.clone(), tolerance, &mut DebugInfo::new());
+
+        (shape, mesh)
+    }
 }
@@ -0,0 +1,85 @@
+//! Export to Wavefront OBJ files
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::mesh::Mesh;
+use fj_math::Point;
+
+use crate::Error;
+
+/// Export the mesh to an OBJ file
+///
+/// Colors are not written. OBJ can only express them through a separate
+/// material library, and tools that import OBJ files rarely agree on how to
+/// interpret it.
+pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write(mesh, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn write(mesh: &Mesh<Point<3>>, file: &mut impl Write) -> Result<(), Error> {
+    writeln!(file, "# Exported by Fornjot")?;
+
+    for vertex in mesh.vertices() {
+        writeln!(file, "v {} {} {}", vertex.x, vertex.y, vertex.z)?;
+    }
+
+    let indices: Vec<_> = mesh.indices().collect();
+    for triangle in indices.chunks(3) {
+        // Indices in OBJ files start at 1.
+        writeln!(
+            file,
+            "f {} {} {}",
+            triangle[0] + 1,
+            triangle[1] + 1,
+            triangle[2] + 1
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    #[test]
+    fn write() {
+        let mut mesh = Mesh::new();
+        for points in [
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+            [[1., 0., 0.], [1., 1., 0.], [0., 1., 0.]],
+        ] {
+            mesh.push_triangle(points.map(Point::from), [255, 0, 0, 255]);
+        }
+
+        let mut data = Vec::new();
+        super::write(&mesh, &mut data).unwrap();
+
+        let text = String::from_utf8(data).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+
+        // Shared vertices are only written once, and referred to by indices
+        // that start at 1.
+        assert_eq!(
+            lines,
+            [
+                "# Exported by Fornjot",
+                "v 0 0 0",
+                "v 1 0 0",
+                "v 0 1 0",
+                "v 1 1 0",
+                "f 1 2 3",
+                "f 2 4 3",
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Export to PLY files
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::mesh::Mesh;
+use fj_math::Point;
+
+use crate::Error;
+
+/// Export the mesh to a binary PLY file
+///
+/// Each face carries the color of its triangle, as `red`, `green`, `blue`, and
+/// `alpha` properties.
+pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write(mesh, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn write(mesh: &Mesh<Point<3>>, file: &mut impl Write) -> Result<(), Error> {
+    let indices: Vec<_> = mesh.indices().collect();
+
+    // The mesh pushes the vertices of each triangle in order, so every chunk of
+    // three indices belongs to the triangle at the same position.
+    let faces: Vec<_> = indices.chunks(3).zip(mesh.triangles()).collect();
+
+    write!(
+        file,
+        "ply\n\
+        format binary_little_endian 1.0\n\
+        comment Exported by Fornjot\n\
+        element vertex {}\n\
+        property float x\n\
+        property float y\n\
+        property float z\n\
+        element face {}\n\
+        property list uchar uint vertex_indices\n\
+        property uchar red\n\
+        property uchar green\n\
+        property uchar blue\n\
+        property uchar alpha\n\
+        end_header\n",
+        mesh.vertices().count(),
+        faces.len(),
+    )?;
+
+    for vertex in mesh.vertices() {
+        for coordinate in vertex.coords.components {
+            file.write_all(&coordinate.into_f32().to_le_bytes())?;
+        }
+    }
+
+    for (indices, triangle) in faces {
+        file.write_all(&[3])?;
+        for index in indices {
+            file.write_all(&index.to_le_bytes())?;
+        }
+        file.write_all(&triangle.color)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    #[test]
+    fn write() {
+        let mut mesh = Mesh::new();
+        for (points, color) in [
+            ([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]], [255, 0, 0, 255]),
+            ([[1., 0., 0.], [1., 1., 0.], [0., 1., 0.]], [0, 0, 255, 128]),
+        ] {
+            mesh.push_triangle(points.map(Point::from), color);
+        }
+
+        let mut data = Vec::new();
+        super::write(&mesh, &mut data).unwrap();
+
+        let end = b"end_header\n";
+        let header_len = data
+            .windows(end.len())
+            .position(|window| window == end)
+            .unwrap()
+            + end.len();
+
+        let header = std::str::from_utf8(&data[..header_len]).unwrap();
+        assert!(header.starts_with("ply\nformat binary_little_endian 1.0\n"));
+        assert!(header.contains("element vertex 4\n"));
+        assert!(header.contains("element face 2\n"));
+
+        // 4 vertices with 3 floats each, then 2 faces with a count, 3 indices,
+        // and 4 color components each.
+        let body = &data[header_len..];
+        assert_eq!(body.len(), 4 * 12 + 2 * (1 + 12 + 4));
+
+        let faces = &body[4 * 12..];
+        let face = |i: usize| {
+            let face = &faces[i * 17..(i + 1) * 17];
+            let indices: Vec<_> = face[1..13]
+                .chunks(4)
+                .map(|index| {
+                    u32::from_le_bytes([index[0], index[1], index[2], index[3]])
+                })
+                .collect();
+
+            (face[0], indices, &face[13..])
+        };
+
+        // Indices in PLY files start at 0.
+        assert_eq!(face(0), (3, vec![0, 1, 2], &[255, 0, 0, 255][..]));
+        assert_eq!(face(1), (3, vec![1, 3, 2], &[0, 0, 255, 128][..]));
+    }
+}
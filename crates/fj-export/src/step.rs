@@ -0,0 +1,1146 @@
+//! Export to STEP files
+//!
+//! Writes the boundary representation of a shape, as a STEP file using the
+//! AP214 schema. Only entities that are also part of AP242 are used, so the
+//! files can be read by software that supports either.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write as _,
+    fs,
+    path::Path,
+};
+
+use fj_kernel::{
+    geometry::{Curve, Surface, SweptCurve},
+    shape::Shape,
+    topology::{Cycle, Edge, Face, Shell, Solid},
+};
+use fj_math::{NurbsCurve, NurbsSurface, Point, Scalar, Triangle, Vector};
+
+use crate::Error;
+
+/// Export the boundary representation of a shape to a STEP file
+///
+/// Solids are written as manifold solid boundary representations. Faces that
+/// are not part of a solid are written into a single open shell, as a surface
+/// model. Faces that are represented by triangles are exported as one planar
+/// face per triangle. Lengths are written in millimeters.
+///
+/// # Limitations
+///
+/// Triangle faces don't share their edges with the other faces of a shell. A
+/// solid that contains such faces is therefore not closed, and its faces are
+/// written into the surface model instead.
+///
+/// Face colors are not exported.
+pub fn export(shape: &Shape, path: &Path) -> Result<(), Error> {
+    let name = path
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let step = to_string(shape, &name);
+    fs::write(path, step)?;
+
+    Ok(())
+}
+
+/// Write the boundary representation of a shape in STEP format
+fn to_string(shape: &Shape, name: &str) -> String {
+    let mut writer = Writer::default();
+
+    let context = writer.context();
+    let product = writer.product(name);
+
+    let mut solids = Vec::new();
+    let mut faces_of_solids = HashSet::new();
+    for solid in shape.solids() {
+        let solid = solid.get();
+
+        let shells: Vec<_> =
+            solid.all_shells().map(|shell| shell.get()).collect();
+        let is_closed = shells
+            .iter()
+            .flat_map(|shell| shell.faces())
+            .all(|face| matches!(face, Face::Face(_)));
+        if !is_closed {
+            continue;
+        }
+
+        faces_of_solids.extend(
+            shells.iter().flat_map(|shell| shell.faces.iter().cloned()),
+        );
+        solids.push(writer.solid(&solid));
+    }
+
+    let mut faces = Vec::new();
+    for face in shape.faces() {
+        if faces_of_solids.contains(&face) {
+            continue;
+        }
+
+        match face.get() {
+            face @ Face::Face(_) => faces.push(writer.face(&face, false)),
+            Face::Triangles(triangles) => {
+                for (triangle, _) in triangles {
+                    faces.push(writer.triangle(&triangle));
+                }
+            }
+        }
+    }
+
+    let origin =
+        writer.placement(Point::origin(), Vector::unit_z(), Vector::unit_x());
+
+    let brep = if solids.is_empty() {
+        None
+    } else {
+        let mut items = solids;
+        items.push(origin);
+
+        Some(writer.add(format!(
+            "ADVANCED_BREP_SHAPE_REPRESENTATION('',{},#{})",
+            list(&items),
+            context
+        )))
+    };
+    let surface_model = if brep.is_some() && faces.is_empty() {
+        None
+    } else {
+        let shell = writer.add(format!("OPEN_SHELL('',{})", list(&faces)));
+        let model =
+            writer.add(format!("SHELL_BASED_SURFACE_MODEL('',(#{}))", shell));
+
+        Some(writer.add(format!(
+            "MANIFOLD_SURFACE_SHAPE_REPRESENTATION('',(#{},#{}),#{})",
+            model, origin, context
+        )))
+    };
+
+    let representation = match (brep, surface_model) {
+        (Some(brep), Some(surface_model)) => {
+            writer.add(format!(
+                "SHAPE_REPRESENTATION_RELATIONSHIP('','',#{},#{})",
+                brep, surface_model
+            ));
+            brep
+        }
+        (Some(representation), None) | (None, Some(representation)) => {
+            representation
+        }
+        (None, None) => unreachable!("Surface model is written without solids"),
+    };
+    writer.add(format!(
+        "SHAPE_DEFINITION_REPRESENTATION(#{},#{})",
+        product, representation
+    ));
+
+    let mut step = String::new();
+
+    step.push_str("ISO-10303-21;\n");
+    step.push_str("HEADER;\n");
+    step.push_str("FILE_DESCRIPTION(('Fornjot model'),'2;1');\n");
+    let _ = writeln!(
+        step,
+        "FILE_NAME({},'',(''),(''),'Fornjot','Fornjot','');",
+        string(name)
+    );
+    step.push_str(
+        "FILE_SCHEMA(('AUTOMOTIVE_DESIGN { 1 0 10303 214 1 1 1 1 }'));\n",
+    );
+    step.push_str("ENDSEC;\n");
+
+    step.push_str("DATA;\n");
+    for (i, entity) in writer.entities.iter().enumerate() {
+        let _ = writeln!(step, "#{}={};", i + 1, entity);
+    }
+    step.push_str("ENDSEC;\n");
+    step.push_str("END-ISO-10303-21;\n");
+
+    step
+}
+
+/// Collects the entities of a STEP file
+///
+/// Entities are identified by their index plus one, which is how they are
+/// referenced in the file. Geometry that is used multiple times is only
+/// written once.
+#[derive(Default)]
+struct Writer {
+    entities: Vec<String>,
+
+    points: HashMap<Point<3>, usize>,
+    vertices: HashMap<Point<3>, usize>,
+    curves: HashMap<Curve<3>, usize>,
+    surfaces: HashMap<Surface, usize>,
+    edges: HashMap<(usize, [usize; 2]), (usize, usize)>,
+    lines: HashMap<[usize; 2], usize>,
+}
+
+impl Writer {
+    /// Add an entity and return its id
+    fn add(&mut self, entity: String) -> usize {
+        self.entities.push(entity);
+        self.entities.len()
+    }
+
+    /// Add the geometric context, including units, and return its id
+    fn context(&mut self) -> usize {
+        let length = self.add(
+            "(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.))".to_owned(),
+        );
+        let angle = self.add(
+            "(NAMED_UNIT(*) PLANE_ANGLE_UNIT() SI_UNIT($,.RADIAN.))".to_owned(),
+        );
+        let solid_angle = self.add(
+            "(NAMED_UNIT(*) SI_UNIT($,.STERADIAN.) SOLID_ANGLE_UNIT())"
+                .to_owned(),
+        );
+        let uncertainty = self.add(format!(
+            "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE(1.E-07),#{},\
+            'distance_accuracy_value','confusion accuracy')",
+            length
+        ));
+
+        self.add(format!(
+            "(GEOMETRIC_REPRESENTATION_CONTEXT(3) \
+            GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT((#{})) \
+            GLOBAL_UNIT_ASSIGNED_CONTEXT((#{},#{},#{})) \
+            REPRESENTATION_CONTEXT('',''))",
+            uncertainty, length, angle, solid_angle
+        ))
+    }
+
+    /// Add the product structure and return the id of its shape definition
+    fn product(&mut self, name: &str) -> usize {
+        let name = string(name);
+
+        let application = self.add(
+            "APPLICATION_CONTEXT(\
+            'core data for automotive mechanical design processes')"
+                .to_owned(),
+        );
+        self.add(format!(
+            "APPLICATION_PROTOCOL_DEFINITION('international standard',\
+            'automotive_design',2000,#{})",
+            application
+        ));
+        let context = self
+            .add(format!("PRODUCT_CONTEXT('',#{},'mechanical')", application));
+        let product =
+            self.add(format!("PRODUCT({},{},'',(#{}))", name, name, context));
+        self.add(format!(
+            "PRODUCT_RELATED_PRODUCT_CATEGORY('part',$,(#{}))",
+            product
+        ));
+        let formation = self
+            .add(format!("PRODUCT_DEFINITION_FORMATION('','',#{})", product));
+        let definition_context = self.add(format!(
+            "PRODUCT_DEFINITION_CONTEXT('part definition',#{},'design')",
+            application
+        ));
+        let definition = self.add(format!(
+            "PRODUCT_DEFINITION('design','',#{},#{})",
+            formation, definition_context
+        ));
+
+        self.add(format!("PRODUCT_DEFINITION_SHAPE('','',#{})", definition))
+    }
+
+    fn point(&mut self, point: Point<3>) -> usize {
+        if let Some(&id) = self.points.get(&point) {
+            return id;
+        }
+
+        let id = self.add(format!(
+            "CARTESIAN_POINT('',{})",
+            reals(point.coords.components)
+        ));
+        self.points.insert(point, id);
+
+        id
+    }
+
+    fn direction(&mut self, direction: Vector<3>) -> usize {
+        let direction = direction.normalize();
+        self.add(format!("DIRECTION('',{})", reals(direction.components)))
+    }
+
+    fn vector(&mut self, vector: Vector<3>) -> usize {
+        let direction = self.direction(vector);
+        self.add(format!(
+            "VECTOR('',#{},{})",
+            direction,
+            real(vector.magnitude())
+        ))
+    }
+
+    fn placement(
+        &mut self,
+        location: Point<3>,
+        axis: Vector<3>,
+        reference: Vector<3>,
+    ) -> usize {
+        let location = self.point(location);
+        let axis = self.direction(axis);
+        let reference = self.direction(reference);
+
+        self.add(format!(
+            "AXIS2_PLACEMENT_3D('',#{},#{},#{})",
+            location, axis, reference
+        ))
+    }
+
+    fn vertex(&mut self, point: Point<3>) -> usize {
+        if let Some(&id) = self.vertices.get(&point) {
+            return id;
+        }
+
+        let point_id = self.point(point);
+        let id = self.add(format!("VERTEX_POINT('',#{})", point_id));
+        self.vertices.insert(point, id);
+
+        id
+    }
+
+    fn curve(&mut self, curve: &Curve<3>) -> usize {
+        if let Some(&id) = self.curves.get(curve) {
+            return id;
+        }
+
+        let id = match curve {
+            Curve::Circle(circle) => {
+                let placement = self.placement(
+                    circle.center,
+                    circle.a.cross(&circle.b),
+                    circle.a,
+                );
+                self.add(format!(
+                    "CIRCLE('',#{},{})",
+                    placement,
+                    real(circle.a.magnitude())
+                ))
+            }
+            Curve::Ellipse(ellipse) => {
+                let [a, b] = principal_axes(ellipse.a, ellipse.b);
+                let placement = self.placement(ellipse.center, a.cross(&b), a);
+                self.add(format!(
+                    "ELLIPSE('',#{},{},{})",
+                    placement,
+                    real(a.magnitude()),
+                    real(b.magnitude())
+                ))
+            }
+            Curve::Line(line) => {
+                let origin = self.point(line.origin);
+                let vector = self.vector(line.direction);
+                self.add(format!("LINE('',#{},#{})", origin, vector))
+            }
+            Curve::Nurbs(curve) => self.nurbs_curve(curve),
+        };
+        self.curves.insert(curve.clone(), id);
+
+        id
+    }
+
+    fn nurbs_curve(&mut self, curve: &NurbsCurve<3>) -> usize {
+        let points: Vec<_> = curve
+            .control_points
+            .iter()
+            .map(|&point| self.point(point))
+            .collect();
+        let (multiplicities, knots) = knots(&curve.knots);
+        let closed = logical(curve.is_closed());
+
+        if is_rational(&curve.weights) {
+            self.add(format!(
+                "(BOUNDED_CURVE() \
+                B_SPLINE_CURVE({},{},.UNSPECIFIED.,{},.F.) \
+                B_SPLINE_CURVE_WITH_KNOTS({},{},.UNSPECIFIED.) \
+                CURVE() GEOMETRIC_REPRESENTATION_ITEM() \
+                RATIONAL_B_SPLINE_CURVE({}) \
+                REPRESENTATION_ITEM(''))",
+                curve.degree,
+                list(&points),
+                closed,
+                multiplicities,
+                knots,
+                reals(curve.weights.iter().copied()),
+            ))
+        } else {
+            self.add(format!(
+                "B_SPLINE_CURVE_WITH_KNOTS('',{},{},.UNSPECIFIED.,{},.F.,\
+                {},{},.UNSPECIFIED.)",
+                curve.degree,
+                list(&points),
+                closed,
+                multiplicities,
+                knots,
+            ))
+        }
+    }
+
+    fn surface(&mut self, surface: &Surface) -> usize {
+        if let Some(&id) = self.surfaces.get(surface) {
+            return id;
+        }
+
+        let id = match surface {
+            Surface::SweptCurve(SweptCurve {
+                curve: Curve::Line(line),
+                path,
+            }) => {
+                let placement = self.placement(
+                    line.origin,
+                    line.direction.cross(path),
+                    line.direction,
+                );
+                self.add(format!("PLANE('',#{})", placement))
+            }
+            Surface::SweptCurve(SweptCurve { curve, path }) => {
+                let curve = self.curve(curve);
+                let path = self.vector(*path);
+                self.add(format!(
+                    "SURFACE_OF_LINEAR_EXTRUSION('',#{},#{})",
+                    curve, path
+                ))
+            }
+            Surface::Nurbs(surface) => self.nurbs_surface(surface),
+        };
+        self.surfaces.insert(surface.clone(), id);
+
+        id
+    }
+
+    fn nurbs_surface(&mut self, surface: &NurbsSurface) -> usize {
+        let points: Vec<_> = surface
+            .control_points
+            .iter()
+            .map(|row| {
+                let row: Vec<_> =
+                    row.iter().map(|&point| self.point(point)).collect();
+                list(&row)
+            })
+            .collect();
+        let points = format!("({})", points.join(","));
+
+        let [degree_u, degree_v] = surface.degree;
+        let (multiplicities_u, knots_u) = knots(&surface.knots[0]);
+        let (multiplicities_v, knots_v) = knots(&surface.knots[1]);
+
+        let weights = surface.weights.iter().flatten().copied();
+        if is_rational(&weights.collect::<Vec<_>>()) {
+            let weights: Vec<_> = surface
+                .weights
+                .iter()
+                .map(|row| reals(row.iter().copied()))
+                .collect();
+
+            self.add(format!(
+                "(BOUNDED_SURFACE() \
+                B_SPLINE_SURFACE({},{},{},.UNSPECIFIED.,.F.,.F.,.F.) \
+                B_SPLINE_SURFACE_WITH_KNOTS({},{},{},{},.UNSPECIFIED.) \
+                GEOMETRIC_REPRESENTATION_ITEM() \
+                RATIONAL_B_SPLINE_SURFACE(({})) \
+                REPRESENTATION_ITEM('') SURFACE())",
+                degree_u,
+                degree_v,
+                points,
+                multiplicities_u,
+                multiplicities_v,
+                knots_u,
+                knots_v,
+                weights.join(","),
+            ))
+        } else {
+            self.add(format!(
+                "B_SPLINE_SURFACE_WITH_KNOTS('',{},{},{},.UNSPECIFIED.,.F.,\
+                .F.,.F.,{},{},{},{},.UNSPECIFIED.)",
+                degree_u,
+                degree_v,
+                points,
+                multiplicities_u,
+                multiplicities_v,
+                knots_u,
+                knots_v,
+            ))
+        }
+    }
+
+    /// Add a solid and return its id
+    fn solid(&mut self, solid: &Solid) -> usize {
+        let exterior = self.closed_shell(&solid.exterior(), false);
+
+        let mut voids = Vec::new();
+        for shell in solid.interiors() {
+            // The faces of a cavity point into it, but STEP expects them to
+            // point out of the shell that bounds it, and reverses the shell
+            // afterwards.
+            let shell = self.closed_shell(&shell, true);
+            voids.push(
+                self.add(format!("ORIENTED_CLOSED_SHELL('',*,#{},.F.)", shell)),
+            );
+        }
+
+        if voids.is_empty() {
+            self.add(format!("MANIFOLD_SOLID_BREP('',#{})", exterior))
+        } else {
+            self.add(format!(
+                "BREP_WITH_VOIDS('',#{},{})",
+                exterior,
+                list(&voids)
+            ))
+        }
+    }
+
+    fn closed_shell(&mut self, shell: &Shell, reversed: bool) -> usize {
+        let faces: Vec<_> = shell
+            .faces()
+            .map(|face| self.face(&face, reversed))
+            .collect();
+        self.add(format!("CLOSED_SHELL('',{})", list(&faces)))
+    }
+
+    /// Add an edge and return the id of its edge curve
+    ///
+    /// Edges that are shared between faces are only written once, regardless
+    /// of the direction in which those faces traverse them. Also returns
+    /// whether the edge curve has the same direction as `edge`.
+    fn edge(&mut self, edge: &Edge<3>) -> (usize, bool) {
+        let curve = edge.curve();
+
+        let (start, end, same_sense) = match &edge.vertices {
+            Some([a, b]) => {
+                let same_sense = a.local() < b.local();
+                let [a, b] = [a, b].map(|vertex| vertex.canonical().get());
+
+                (a.point(), b.point(), same_sense)
+            }
+            None => {
+                // A continuous edge starts and ends at the same point. Any
+                // point on the curve would do, as long as it's consistent.
+                let point = start_of_curve(&curve);
+                (point, point, true)
+            }
+        };
+
+        let start_id = self.vertex(start);
+        let end_id = self.vertex(end);
+        let key = if start_id <= end_id {
+            [start_id, end_id]
+        } else {
+            [end_id, start_id]
+        };
+
+        // Faces that share a straight edge don't necessarily share its line,
+        // so those are identified by their vertices alone.
+        if let (Curve::Line(_), Some(_)) = (&curve, &edge.vertices) {
+            let edge = self.line_segment(key, [start, end], start_id);
+            return (edge, key[0] == start_id);
+        }
+
+        let curve = self.curve(&curve);
+        if let Some(&(edge, start)) = self.edges.get(&(curve, key)) {
+            return (edge, start == start_id);
+        }
+
+        let edge = self.add(format!(
+            "EDGE_CURVE('',#{},#{},#{},{})",
+            start_id,
+            end_id,
+            curve,
+            logical(same_sense)
+        ));
+        self.edges.insert((curve, key), (edge, start_id));
+
+        (edge, true)
+    }
+
+    /// Add an edge loop for a cycle and return its id
+    ///
+    /// The edges of a cycle are not ordered, and don't say in which direction
+    /// they are traversed. They are chained here, such that each edge starts
+    /// where the previous one ends, and the loop goes counter-clockwise around
+    /// the normal of `surface`, if `counter_clockwise` is `true`, or clockwise
+    /// otherwise.
+    fn cycle(
+        &mut self,
+        cycle: &Cycle<3>,
+        surface: &Surface,
+        counter_clockwise: bool,
+    ) -> usize {
+        let edges: Vec<_> = cycle
+            .edges()
+            .map(|edge| {
+                let [start, end] = match edge.vertices() {
+                    Some(vertices) => vertices.map(|vertex| vertex.point()),
+                    None => [start_of_curve(&edge.curve()); 2],
+                };
+                let ids = [start, end].map(|point| self.vertex(point));
+
+                (edge, ids)
+            })
+            .collect();
+
+        let mut edges = chain(edges);
+
+        let points: Vec<_> = edges
+            .iter()
+            .flat_map(|(edge, forward)| points_on_edge(edge, *forward))
+            .map(|point| surface.point_to_surface_coords(point).local())
+            .collect();
+        if (signed_area(&points) > Scalar::ZERO) != counter_clockwise {
+            edges.reverse();
+            for (_, forward) in &mut edges {
+                *forward = !*forward;
+            }
+        }
+
+        let edges: Vec<_> = edges
+            .iter()
+            .map(|(edge, forward)| {
+                let (edge, same_direction) = self.edge(edge);
+                self.add(format!(
+                    "ORIENTED_EDGE('',*,*,#{},{})",
+                    edge,
+                    logical(same_direction == *forward)
+                ))
+            })
+            .collect();
+        self.add(format!("EDGE_LOOP('',{})", list(&edges)))
+    }
+
+    /// Add a face and return its id
+    ///
+    /// If `reversed` is `true`, the face points against the normal of its
+    /// surface.
+    fn face(&mut self, face: &Face, reversed: bool) -> usize {
+        let surface = face.surface();
+        let exteriors: Vec<_> = face.exteriors().collect();
+
+        // STEP only allows a single outer bound per face. If there are
+        // multiple exteriors, they all become regular bounds.
+        let outer = if exteriors.len() == 1 {
+            "FACE_OUTER_BOUND"
+        } else {
+            "FACE_BOUND"
+        };
+
+        // The face must be to the left of its bounds, when looking at it from
+        // the direction that it points to.
+        let mut bounds = Vec::new();
+        for cycle in &exteriors {
+            let cycle = self.cycle(cycle, &surface, !reversed);
+            bounds.push(self.add(format!("{}('',#{},.T.)", outer, cycle)));
+        }
+        for cycle in face.interiors() {
+            let cycle = self.cycle(&cycle, &surface, reversed);
+            bounds.push(self.add(format!("FACE_BOUND('',#{},.T.)", cycle)));
+        }
+
+        let surface = self.surface(&surface);
+
+        self.add(format!(
+            "ADVANCED_FACE('',{},#{},{})",
+            list(&bounds),
+            surface,
+            logical(!reversed)
+        ))
+    }
+
+    /// Add a planar face for a triangle
+    fn triangle(&mut self, triangle: &Triangle<3>) -> usize {
+        let [a, b, c] = triangle.points();
+
+        let mut edges = Vec::new();
+        for [start, end] in [[a, b], [b, c], [c, a]] {
+            let start_id = self.vertex(start);
+            let end_id = self.vertex(end);
+
+            // Adjacent triangles share their edges, but traverse them in
+            // opposite directions.
+            let key = if start_id < end_id {
+                [start_id, end_id]
+            } else {
+                [end_id, start_id]
+            };
+            let edge = self.line_segment(key, [start, end], start_id);
+
+            edges.push(self.add(format!(
+                "ORIENTED_EDGE('',*,*,#{},{})",
+                edge,
+                logical(key[0] == start_id)
+            )));
+        }
+
+        let cycle = self.add(format!("EDGE_LOOP('',{})", list(&edges)));
+        let bound = self.add(format!("FACE_OUTER_BOUND('',#{},.T.)", cycle));
+
+        let normal = (b - a).cross(&(c - a));
+        let placement = self.placement(a, normal, b - a);
+        let plane = self.add(format!("PLANE('',#{})", placement));
+
+        self.add(format!("ADVANCED_FACE('',(#{}),#{},.T.)", bound, plane))
+    }
+
+    /// Add a straight edge between two vertices and return its id
+    ///
+    /// The edge goes from the vertex with the lower id to the one with the
+    /// higher id. `points` are the points of the vertices, with the first one
+    /// belonging to `first_id`.
+    fn line_segment(
+        &mut self,
+        key: [usize; 2],
+        points: [Point<3>; 2],
+        first_id: usize,
+    ) -> usize {
+        if let Some(&edge) = self.lines.get(&key) {
+            return edge;
+        }
+
+        let [a, b] = if key[0] == first_id {
+            points
+        } else {
+            let [a, b] = points;
+            [b, a]
+        };
+
+        let origin = self.point(a);
+        let vector = self.vector(b - a);
+        let line = self.add(format!("LINE('',#{},#{})", origin, vector));
+
+        let [start, end] = key;
+        let edge = self
+            .add(format!("EDGE_CURVE('',#{},#{},#{},.T.)", start, end, line));
+        self.lines.insert(key, edge);
+
+        edge
+    }
+}
+
+/// Chain the edges of a cycle, so each starts where the previous one ends
+///
+/// Takes the edges along with the ids of their start and end vertices, and
+/// returns them in order, along with whether each is traversed in its own
+/// direction. Edges that don't connect to any other edge, like continuous
+/// edges, are taken in the order in which they appear in the cycle.
+fn chain(mut edges: Vec<(Edge<3>, [usize; 2])>) -> Vec<(Edge<3>, bool)> {
+    let mut chained = Vec::new();
+    let mut end = None;
+
+    while !edges.is_empty() {
+        // Edges that start where the previous one ended are preferred, so
+        // edges are only traversed backwards where necessary.
+        let next = end.and_then(|end| {
+            let forward = edges
+                .iter()
+                .position(|(_, [start, _])| *start == end)
+                .map(|i| (i, true));
+            let backward = || {
+                edges
+                    .iter()
+                    .position(|(_, [_, edge_end])| *edge_end == end)
+                    .map(|i| (i, false))
+            };
+
+            forward.or_else(backward)
+        });
+        let (i, forward) = next.unwrap_or((0, true));
+
+        let (edge, [start, edge_end]) = edges.remove(i);
+
+        end = Some(if forward { edge_end } else { start });
+        chained.push((edge, forward));
+    }
+
+    chained
+}
+
+/// Sample points on an edge, in the direction in which it is traversed
+///
+/// The end of the edge is not included, as it is the start of the next one.
+fn points_on_edge(edge: &Edge<3>, forward: bool) -> Vec<Point<3>> {
+    const SAMPLES: usize = 8;
+
+    let curve = edge.curve();
+
+    let [a, b] = match &edge.vertices {
+        Some(vertices) => vertices.clone().map(|vertex| vertex.local().t),
+        None => match &curve {
+            Curve::Nurbs(curve) => curve.domain(),
+            _ => [Scalar::ZERO, Scalar::PI * 2.],
+        },
+    };
+    let [a, b] = if forward { [a, b] } else { [b, a] };
+
+    (0..SAMPLES)
+        .map(|i| {
+            let t = a + (b - a) * (i as f64 / SAMPLES as f64);
+            curve.point_from_curve_coords([t])
+        })
+        .collect()
+}
+
+/// Compute the signed area of a polygon
+///
+/// The area is positive, if the polygon is counter-clockwise.
+fn signed_area(points: &[Point<2>]) -> Scalar {
+    let mut area = Scalar::ZERO;
+
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += a.u * b.v - a.v * b.u;
+    }
+
+    area / 2.
+}
+
+/// Compute the point at which a continuous edge on the curve starts and ends
+fn start_of_curve(curve: &Curve<3>) -> Point<3> {
+    match curve {
+        Curve::Circle(circle) => circle.center + circle.a,
+        Curve::Ellipse(ellipse) => ellipse.center + ellipse.a,
+        Curve::Line(line) => line.origin,
+        Curve::Nurbs(curve) => curve.control_points[0],
+    }
+}
+
+/// Compute the principal axes of an ellipse
+///
+/// The kernel defines an ellipse using two conjugate semi-diameters, `a` and
+/// `b`, which are not necessarily perpendicular. STEP requires the principal
+/// axes, which are. The returned axes keep the orientation of the ellipse.
+fn principal_axes(a: Vector<3>, b: Vector<3>) -> [Vector<3>; 2] {
+    // The points of the ellipse are `a * cos(t) + b * sin(t)`. The principal
+    // axes are where the distance from the center is extremal, which is where
+    // the derivative of the squared distance is zero.
+    let t = Scalar::atan2(a.dot(&b) * 2., a.dot(&a) - b.dot(&b)) / 2.;
+    let (sin, cos) = t.sin_cos();
+
+    [a * cos + b * sin, b * cos - a * sin]
+}
+
+/// Convert a knot vector into distinct knots and their multiplicities
+fn knots(knots: &[Scalar]) -> (String, String) {
+    let mut distinct: Vec<Scalar> = Vec::new();
+    let mut multiplicities: Vec<usize> = Vec::new();
+
+    for &knot in knots {
+        match distinct.last() {
+            Some(&last) if last == knot => {
+                if let Some(multiplicity) = multiplicities.last_mut() {
+                    *multiplicity += 1;
+                }
+            }
+            _ => {
+                distinct.push(knot);
+                multiplicities.push(1);
+            }
+        }
+    }
+
+    let multiplicities: Vec<_> = multiplicities
+        .iter()
+        .map(|multiplicity| multiplicity.to_string())
+        .collect();
+
+    (format!("({})", multiplicities.join(",")), reals(distinct))
+}
+
+fn is_rational(weights: &[Scalar]) -> bool {
+    weights.windows(2).any(|weights| weights[0] != weights[1])
+}
+
+/// Format a list of entity references
+fn list(ids: &[usize]) -> String {
+    let ids: Vec<_> = ids.iter().map(|id| format!("#{}", id)).collect();
+    format!("({})", ids.join(","))
+}
+
+/// Format a list of real numbers
+fn reals(values: impl IntoIterator<Item = Scalar>) -> String {
+    let values: Vec<_> = values.into_iter().map(real).collect();
+    format!("({})", values.join(","))
+}
+
+/// Format a real number
+///
+/// STEP requires real numbers to contain a decimal point.
+fn real(value: Scalar) -> String {
+    let value = value.into_f64().to_string();
+
+    if value.contains('.') {
+        value
+    } else {
+        format!("{}.", value)
+    }
+}
+
+fn logical(value: bool) -> &'static str {
+    if value {
+        ".T."
+    } else {
+        ".F."
+    }
+}
+
+/// Format a string
+///
+/// Quotes and backslashes are escaped. Characters outside of printable ASCII
+/// are encoded as UTF-16, as STEP requires.
+fn string(value: &str) -> String {
+    let mut string = String::from("'");
+
+    for c in value.chars() {
+        match c {
+            '\'' => string.push_str("''"),
+            '\\' => string.push_str("\\\\"),
+            ' '..='~' => string.push(c),
+            _ => {
+                string.push_str("\\X2\\");
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    let _ = write!(string, "{:04X}", unit);
+                }
+                string.push_str("\\X0\\");
+            }
+        }
+    }
+
+    string.push('\'');
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use fj_kernel::{
+        algorithms::{sweep_shape, Tolerance},
+        geometry::Surface,
+        shape::Shape,
+        topology::{Face, Solid},
+    };
+    use fj_math::{Scalar, Vector};
+
+    use super::to_string;
+
+    #[test]
+    fn swept_cube() {
+        let shape = sweep(
+            [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]],
+            None,
+        );
+        let step = Step::parse(&to_string(&shape, "cube"));
+
+        assert_eq!(step.count("VERTEX_POINT"), 8);
+        assert_eq!(step.count("EDGE_CURVE"), 12);
+        assert_eq!(step.count("ADVANCED_FACE"), 6);
+        assert_eq!(step.count("CLOSED_SHELL"), 1);
+        assert_eq!(step.count("MANIFOLD_SOLID_BREP"), 1);
+        assert_eq!(step.count("OPEN_SHELL"), 0);
+
+        step.assert_closed();
+    }
+
+    #[test]
+    fn built_cube() {
+        // Unlike the swept cube, the faces of this one don't share their
+        // edges, only their vertices.
+        let mut shape = Shape::new();
+        Solid::builder(&mut shape)
+            .build_cube_from_edge_size(Scalar::ONE)
+            .unwrap();
+        let step = Step::parse(&to_string(&shape, "cube"));
+
+        assert_eq!(step.count("VERTEX_POINT"), 8);
+        assert_eq!(step.count("EDGE_CURVE"), 12);
+        assert_eq!(step.count("ADVANCED_FACE"), 6);
+        assert_eq!(step.count("MANIFOLD_SOLID_BREP"), 1);
+
+        step.assert_closed();
+    }
+
+    #[test]
+    fn plate_with_hole() {
+        let shape = sweep(
+            [[0., 0., 0.], [3., 0., 0.], [3., 3., 0.], [0., 3., 0.]],
+            Some([[1., 1., 0.], [1., 2., 0.], [2., 2., 0.], [2., 1., 0.]]),
+        );
+        let step = Step::parse(&to_string(&shape, "plate"));
+
+        assert_eq!(step.count("VERTEX_POINT"), 16);
+        assert_eq!(step.count("EDGE_CURVE"), 24);
+        assert_eq!(step.count("ADVANCED_FACE"), 10);
+        assert_eq!(step.count("MANIFOLD_SOLID_BREP"), 1);
+
+        step.assert_closed();
+    }
+
+    #[test]
+    fn faces_without_solid() {
+        let mut shape = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .build()
+            .unwrap();
+        let step = Step::parse(&to_string(&shape, "triangle"));
+
+        assert_eq!(step.count("OPEN_SHELL"), 1);
+        assert_eq!(step.count("MANIFOLD_SOLID_BREP"), 0);
+        assert_eq!(step.count("EDGE_CURVE"), 3);
+        step.assert_loops_are_closed();
+
+        // The face is to the left of its loop, when looking at it from above.
+        let points: Vec<_> = step.loops()[0]
+            .iter()
+            .map(|&(edge, orientation)| {
+                let refs = step.refs(edge);
+                let vertex = if orientation { refs[0] } else { refs[1] };
+                step.point(vertex)
+            })
+            .collect();
+        let [a, b, c] = [points[0], points[1], points[2]];
+        let area =
+            (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+        assert!(area > 0.);
+    }
+
+    fn sweep(
+        exterior: [[f64; 3]; 4],
+        interior: Option<[[f64; 3]; 4]>,
+    ) -> Shape {
+        let mut sketch = Shape::new();
+        let mut face = Face::builder(Surface::xy_plane(), &mut sketch)
+            .with_exterior_polygon(exterior);
+        if let Some(interior) = interior {
+            face = face.with_interior_polygon(interior);
+        }
+        face.build().unwrap();
+
+        sweep_shape(
+            sketch,
+            Vector::from([0., 0., 1.]),
+            Tolerance::from_scalar(0.1).unwrap(),
+            [255, 0, 0, 255],
+        )
+        .unwrap()
+    }
+
+    /// The entities of a STEP file, by id
+    struct Step(HashMap<usize, String>);
+
+    impl Step {
+        fn parse(step: &str) -> Self {
+            let entities = step
+                .lines()
+                .filter_map(|line| {
+                    let (id, entity) =
+                        line.strip_prefix('#')?.split_once('=')?;
+                    let entity = entity.strip_suffix(';')?;
+                    Some((id.parse().unwrap(), entity.to_owned()))
+                })
+                .collect();
+
+            Self(entities)
+        }
+
+        fn count(&self, name: &str) -> usize {
+            self.0
+                .values()
+                .filter(|entity| entity.starts_with(&format!("{}(", name)))
+                .count()
+        }
+
+        /// The ids that an entity refers to, in order
+        fn refs(&self, id: usize) -> Vec<usize> {
+            self.0[&id]
+                .split('#')
+                .skip(1)
+                .map(|rest| {
+                    let digits: String =
+                        rest.chars().take_while(char::is_ascii_digit).collect();
+                    digits.parse().unwrap()
+                })
+                .collect()
+        }
+
+        /// The coordinates of a vertex
+        fn point(&self, vertex: usize) -> [f64; 3] {
+            let point = self.refs(vertex)[0];
+            let coords = &self.0[&point];
+
+            let start = coords.find(",(").unwrap() + 2;
+            let end = coords.rfind("))").unwrap();
+            let coords: Vec<f64> = coords[start..end]
+                .split(',')
+                .map(|coord| coord.parse().unwrap())
+                .collect();
+
+            [coords[0], coords[1], coords[2]]
+        }
+
+        /// The edges of all loops, with their edge curve and orientation
+        fn loops(&self) -> Vec<Vec<(usize, bool)>> {
+            self.0
+                .iter()
+                .filter(|(_, entity)| entity.starts_with("EDGE_LOOP("))
+                .map(|(&id, _)| {
+                    self.refs(id)
+                        .into_iter()
+                        .map(|oriented_edge| {
+                            let edge = self.refs(oriented_edge)[0];
+                            let orientation =
+                                self.0[&oriented_edge].ends_with(".T.)");
+                            (edge, orientation)
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+
+        /// Check that every edge of a loop ends where the next one starts
+        fn assert_loops_are_closed(&self) {
+            for edges in self.loops() {
+                let vertices: Vec<_> = edges
+                    .iter()
+                    .map(|&(edge, orientation)| {
+                        let refs = self.refs(edge);
+                        if orientation {
+                            [refs[0], refs[1]]
+                        } else {
+                            [refs[1], refs[0]]
+                        }
+                    })
+                    .collect();
+
+                for (i, [_, end]) in vertices.iter().enumerate() {
+                    let [start, _] = vertices[(i + 1) % vertices.len()];
+                    assert_eq!(*end, start, "Loop is not closed: {:?}", edges);
+                }
+            }
+        }
+
+        /// Check that the loops are closed and that the faces form a closed
+        /// shell, with every edge traversed once in each direction
+        fn assert_closed(&self) {
+            self.assert_loops_are_closed();
+
+            let mut uses: HashMap<usize, Vec<bool>> = HashMap::new();
+            for (edge, orientation) in self.loops().into_iter().flatten() {
+                uses.entry(edge).or_default().push(orientation);
+            }
+
+            assert_eq!(uses.len(), self.count("EDGE_CURVE"));
+            for (edge, mut orientations) in uses {
+                orientations.sort();
+                assert_eq!(
+                    orientations,
+                    [false, true],
+                    "Edge curve #{} is not traversed once in each direction",
+                    edge
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! Export to STL files
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::mesh::{Color, Mesh};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{Error, Metadata};
+
+/// The size of the header of a binary STL file, excluding the triangle count
+const HEADER: usize = 80;
+
+/// Export the mesh to a binary STL file
+///
+/// The triangles are written to the file as they are read from the mesh,
+/// without collecting them first. The header names the model and the
+/// parameters it was created with, as far as they fit.
+///
+/// If `color` is `true`, the color of each triangle is written to its
+/// attribute bytes, using the convention that VisCAM and SolidView introduced.
+/// The alpha channel is discarded.
+///
+/// Returns an error, if the mesh contains a degenerate triangle. In that case,
+/// no file is created.
+pub fn export(
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+    color: bool,
+    path: &Path,
+) -> Result<(), Error> {
+    check(mesh)?;
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write(mesh, metadata, color, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Export the mesh to an ASCII STL file
+///
+/// The solid is named after the model, if a name is available. Returns an
+/// error, if the mesh contains a degenerate triangle. In that case, no file is
+/// created.
+pub fn export_ascii(
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+    path: &Path,
+) -> Result<(), Error> {
+    check(mesh)?;
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write_ascii(mesh, metadata, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn check(mesh: &Mesh<Point<3>>) -> Result<(), Error> {
+    mesh.triangles()
+        .try_for_each(|triangle| normal(triangle.points).map(|_| ()))
+}
+
+fn write(
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+    color: bool,
+    file: &mut impl Write,
+) -> Result<(), Error> {
+    let num_triangles: u32 = mesh
+        .triangles()
+        .count()
+        .try_into()
+        .map_err(|_| Error::InvalidTriangleCount)?;
+
+    file.write_all(&header(metadata))?;
+    file.write_all(&num_triangles.to_le_bytes())?;
+
+    for triangle in mesh.triangles() {
+        let normal = normal(triangle.points)?;
+
+        for vector in [normal]
+            .into_iter()
+            .chain(triangle.points.map(|p| p.coords))
+        {
+            for component in vector.components {
+                file.write_all(&component.into_f32().to_le_bytes())?;
+            }
+        }
+
+        let attribute = if color {
+            color_attribute(triangle.color)
+        } else {
+            0
+        };
+        file.write_all(&attribute.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write_ascii(
+    mesh: &Mesh<Point<3>>,
+    metadata: &Metadata,
+    file: &mut impl Write,
+) -> Result<(), Error> {
+    // The name of the solid ends at the first whitespace.
+    let name = metadata
+        .name
+        .as_deref()
+        .unwrap_or("fornjot")
+        .replace(char::is_whitespace, "_");
+
+    writeln!(file, "solid {}", name)?;
+
+    for triangle in mesh.triangles() {
+        let normal = normal(triangle.points)?;
+
+        writeln!(
+            file,
+            "  facet normal {} {} {}",
+            normal.x, normal.y, normal.z
+        )?;
+        writeln!(file, "    outer loop")?;
+        for point in triangle.points {
+            writeln!(file, "      vertex {} {} {}", point.x, point.y, point.z)?;
+        }
+        writeln!(file, "    endloop")?;
+        writeln!(file, "  endfacet")?;
+    }
+
+    writeln!(file, "endsolid {}", name)?;
+
+    Ok(())
+}
+
+/// Compute the normal of a triangle
+///
+/// Returns an error, if the triangle is degenerate.
+fn normal(points: [Point<3>; 3]) -> Result<Vector<3>, Error> {
+    let [a, b, c] = points;
+    let normal = (b - a).cross(&(c - a));
+
+    if normal.magnitude() == Scalar::ZERO {
+        return Err(Error::DegenerateTriangle(points));
+    }
+
+    Ok(normal.normalize())
+}
+
+/// Create the header of a binary STL file
+///
+/// The header is free-form text. It must not start with "solid", or readers
+/// might mistake the file for an ASCII STL file.
+fn header(metadata: &Metadata) -> [u8; HEADER] {
+    let mut text = String::from("Fornjot");
+
+    if let Some(name) = &metadata.name {
+        text.push_str(" model ");
+        text.push_str(name);
+    }
+
+    if !metadata.parameters.is_empty() {
+        let parameters: Vec<_> = metadata
+            .parameters
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+
+        text.push_str(" (");
+        text.push_str(&parameters.join(", "));
+        text.push(')');
+    }
+
+    // Don't cut a character in half, if the text is too long.
+    let mut end = text.len().min(HEADER);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    let mut header = [0; HEADER];
+    header[..end].copy_from_slice(&text.as_bytes()[..end]);
+    header
+}
+
+/// Encode a color in the attribute bytes of a triangle
+///
+/// Bit 15 marks the color as valid. Bits 10-14 hold red, bits 5-9 green, and
+/// bits 0-4 blue.
+fn color_attribute([r, g, b, _]: Color) -> u16 {
+    let [r, g, b] = [r, g, b].map(|component| u16::from(component >> 3));
+    0x8000 | r << 10 | g << 5 | b
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    use crate::{Error, Metadata};
+
+    #[test]
+    fn write() {
+        let mesh = mesh(&[
+            ([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]], [255, 0, 0, 255]),
+            ([[0., 0., 0.], [0., 0., 1.], [1., 0., 0.]], [0, 255, 8, 255]),
+        ]);
+
+        let mut data = Vec::new();
+        super::write(&mesh, &Metadata::default(), false, &mut data).unwrap();
+
+        assert_eq!(data.len(), 84 + 2 * 50);
+        assert_eq!(&data[80..84], &2u32.to_le_bytes());
+
+        let triangle = &data[84..134];
+        assert_eq!(floats(&triangle[..12]), [0., 0., 1.]);
+        assert_eq!(floats(&triangle[12..24]), [0., 0., 0.]);
+        assert_eq!(floats(&triangle[24..36]), [1., 0., 0.]);
+        assert_eq!(floats(&triangle[36..48]), [0., 1., 0.]);
+        assert_eq!(&triangle[48..], &[0, 0]);
+
+        let triangle = &data[134..];
+        assert_eq!(floats(&triangle[..12]), [0., 1., 0.]);
+        assert_eq!(&triangle[48..], &[0, 0]);
+    }
+
+    #[test]
+    fn write_color() {
+        let mesh = mesh(&[
+            ([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]], [255, 0, 0, 255]),
+            ([[0., 0., 0.], [0., 0., 1.], [1., 0., 0.]], [0, 255, 8, 128]),
+        ]);
+
+        let mut data = Vec::new();
+        super::write(&mesh, &Metadata::default(), true, &mut data).unwrap();
+
+        assert_eq!(&data[132..134], &0b1_11111_00000_00000u16.to_le_bytes());
+        assert_eq!(&data[182..184], &0b1_00000_11111_00001u16.to_le_bytes());
+    }
+
+    #[test]
+    fn header() {
+        let metadata = Metadata {
+            name: Some("spacer".into()),
+            parameters: [("outer", "1.0"), ("inner", "0.5")]
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        };
+
+        let header = super::header(&metadata);
+        let text = String::from_utf8_lossy(&header);
+        assert!(text.starts_with("Fornjot model spacer (inner=0.5, outer=1.0)"));
+        assert!(header[43..].iter().all(|&byte| byte == 0));
+
+        // Text that doesn't fit is truncated, without splitting characters.
+        let metadata = Metadata {
+            name: Some(format!("x{}", "ä".repeat(50))),
+            ..Metadata::default()
+        };
+
+        let header = super::header(&metadata);
+        assert!(std::str::from_utf8(&header[..79]).is_ok());
+        assert_eq!(header[79], 0);
+
+        // A header that starts with "solid" would be mistaken for ASCII STL.
+        let metadata = Metadata {
+            name: Some("solid".into()),
+            ..Metadata::default()
+        };
+        assert!(!super::header(&metadata).starts_with(b"solid"));
+    }
+
+    #[test]
+    fn reject_degenerate_triangles() {
+        let mesh = mesh(&[
+            ([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]], [255, 0, 0, 255]),
+            ([[0., 0., 0.], [1., 1., 1.], [2., 2., 2.]], [255, 0, 0, 255]),
+        ]);
+
+        let mut data = Vec::new();
+        let result =
+            super::write(&mesh, &Metadata::default(), false, &mut data);
+        assert!(matches!(result, Err(Error::DegenerateTriangle(_))));
+
+        let result = super::check(&mesh);
+        assert!(matches!(result, Err(Error::DegenerateTriangle(_))));
+
+        let result =
+            super::write_ascii(&mesh, &Metadata::default(), &mut Vec::new());
+        assert!(matches!(result, Err(Error::DegenerateTriangle(_))));
+    }
+
+    #[test]
+    fn write_ascii() {
+        let mesh = mesh(&[(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+            [255, 0, 0, 255],
+        )]);
+        let metadata = Metadata {
+            name: Some("my model".into()),
+            ..Metadata::default()
+        };
+
+        let mut data = Vec::new();
+        super::write_ascii(&mesh, &metadata, &mut data).unwrap();
+
+        let text = String::from_utf8(data).unwrap();
+        let lines: Vec<_> = text.lines().map(str::trim).collect();
+        assert_eq!(
+            lines,
+            [
+                "solid my_model",
+                "facet normal 0 0 1",
+                "outer loop",
+                "vertex 0 0 0",
+                "vertex 1 0 0",
+                "vertex 0 1 0",
+                "endloop",
+                "endfacet",
+                "endsolid my_model",
+            ]
+        );
+    }
+
+    fn mesh(triangles: &[([[f64; 3]; 3], [u8; 4])]) -> Mesh<Point<3>> {
+        let mut mesh = Mesh::new();
+
+        for &(points, color) in triangles {
+            mesh.push_triangle(points.map(Point::from), color);
+        }
+
+        mesh
+    }
+
+    fn floats(bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks(4)
+            .map(|bytes| {
+                f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+            })
+            .collect()
+    }
+}
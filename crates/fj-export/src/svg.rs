@@ -0,0 +1,229 @@
+//! Export to SVG files
+
+use std::{
+    f64::consts::PI,
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path as FilePath,
+};
+
+use fj_kernel::shape::Shape;
+
+use crate::{
+    drawing::{bezier_segments, is_rational, Arc, Drawing, Path, Segment},
+    Error,
+};
+
+/// Export a planar shape to an SVG file
+///
+/// Every face of the shape is written as a `path` element that consists of
+/// the face's cycles. Lines, arcs, and non-rational splines of up to degree 3
+/// are represented exactly. See [`Drawing`] for the exceptions. Other splines
+/// are approximated by polylines.
+///
+/// The paths are drawn with thin black lines and are not filled, as is
+/// expected by laser cutters. One unit in the file is one millimeter.
+pub fn export(shape: &Shape, path: &FilePath) -> Result<(), Error> {
+    let drawing = Drawing::from_shape(shape)?;
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write(&drawing, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn write(drawing: &Drawing, file: &mut impl Write) -> io::Result<()> {
+    let [[min_x, min_y], [max_x, max_y]] =
+        drawing.bounding_box().unwrap_or([[0., 0.], [0., 0.]]);
+    let [width, height] = [max_x - min_x, max_y - min_y];
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        file,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}mm" height="{}mm" viewBox="{} {} {} {}">"#,
+        round(width),
+        round(height),
+        round(min_x),
+        round(-max_y),
+        round(width),
+        round(height)
+    )?;
+
+    // SVG's y axis points down. Flip the drawing, so its coordinates can be
+    // used as they are.
+    writeln!(
+        file,
+        r#"  <g transform="scale(1 -1)" fill="none" stroke="black" stroke-width="0.1">"#
+    )?;
+
+    for face in &drawing.faces {
+        let mut data = String::new();
+        for path in face {
+            path_data(path, &mut data);
+        }
+
+        writeln!(file, r#"    <path d="{}"/>"#, data.trim_end())?;
+    }
+
+    writeln!(file, "  </g>")?;
+    writeln!(file, "</svg>")?;
+
+    Ok(())
+}
+
+fn path_data(path: &Path, data: &mut String) {
+    let [x, y] = path.start;
+    command(data, 'M', &[[x, y]]);
+
+    for segment in &path.segments {
+        match segment {
+            Segment::Line { end, .. } => command(data, 'L', &[*end]),
+            Segment::Arc(arc) => {
+                // A full circle or ellipse can't be drawn with a single arc
+                // command, as its start and end would be the same.
+                let parts = if arc.is_full() { 2 } else { 1 };
+                let sweep = arc.sweep / f64::from(parts);
+
+                for i in 1..=parts {
+                    let end = arc.point(arc.start + sweep * f64::from(i));
+                    arc_command(data, arc, sweep, end);
+                }
+            }
+            Segment::Spline(nurbs) => match bezier_segments(nurbs) {
+                Some(segments) if !is_rational(nurbs) && nurbs.degree <= 3 => {
+                    for segment in segments {
+                        let points: Vec<_> = segment
+                            .into_iter()
+                            .map(|(point, _)| point)
+                            .collect();
+                        let kind = match points.len() {
+                            2 => 'L',
+                            3 => 'Q',
+                            _ => 'C',
+                        };
+                        command(data, kind, &points[1..]);
+                    }
+                }
+                _ => {
+                    let [start, end] = nurbs.domain();
+                    let n = 8 * nurbs.control_points.len();
+
+                    let points: Vec<_> = (1..=n)
+                        .map(|i| {
+                            let t =
+                                start + (end - start) * (i as f64 / n as f64);
+                            let point = nurbs.point_from_curve_coords([t]);
+                            [point.u.into_f64(), point.v.into_f64()]
+                        })
+                        .collect();
+                    command(data, 'L', &points);
+                }
+            },
+            Segment::Polyline(points) => command(data, 'L', &points[1..]),
+        }
+    }
+
+    data.push_str("Z ");
+}
+
+fn command(data: &mut String, kind: char, points: &[[f64; 2]]) {
+    data.push(kind);
+    for &[x, y] in points {
+        let _ = write!(data, " {} {}", round(x), round(y));
+    }
+    data.push(' ');
+}
+
+fn arc_command(data: &mut String, arc: &Arc, sweep: f64, [x, y]: [f64; 2]) {
+    let large_arc = sweep.abs() > PI;
+
+    // The drawing is flipped, so positive angles are counter-clockwise, as in
+    // the drawing's coordinate system.
+    let positive = sweep > 0.;
+
+    let _ = write!(
+        data,
+        "A {} {} {} {} {} {} {} ",
+        round(arc.radius()),
+        round(arc.radius() * arc.ratio),
+        round(arc.rotation().to_degrees()),
+        u8::from(large_arc),
+        u8::from(positive),
+        round(x),
+        round(y)
+    );
+}
+
+/// Round a number to a precision far below anything that matters in practice
+///
+/// This removes the noise that computations leave in the last digits, so
+/// points that should be the same are written the same, and the file is
+/// easier to read.
+fn round(number: f64) -> f64 {
+    // Adding zero turns negative zero into zero.
+    (number * 1e9).round() / 1e9 + 0.
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::{import::svg_from_str, Shape2d, SketchSegment};
+
+    use crate::drawing::{tests::rounded_plate, Drawing};
+
+    #[test]
+    fn view_box() {
+        let svg = to_string();
+
+        // The y axis is flipped, so the view box starts at the negated top.
+        assert!(svg
+            .contains(r#"width="10mm" height="15mm" viewBox="-5 -10 10 15""#));
+    }
+
+    #[test]
+    fn round_trip() {
+        let shapes = svg_from_str(&to_string()).unwrap();
+        assert_eq!(shapes.len(), 1);
+
+        let difference = match &shapes[0] {
+            Shape2d::Difference(difference) => difference,
+            shape => panic!("Expected difference, got {:?}", shape),
+        };
+
+        // The coordinates survive the flip of the y axis.
+        let exterior = match difference.shape() {
+            Shape2d::Sketch(sketch) => sketch.to_points(),
+            shape => panic!("Expected sketch, got {:?}", shape),
+        };
+        for [x, y] in exterior {
+            assert!((-5. ..=5.).contains(&x), "{}", x);
+            assert!((-5. ..=10.).contains(&y), "{}", y);
+        }
+
+        let hole = match difference.holes() {
+            [Shape2d::Sketch(sketch)] => sketch.to_segments(),
+            holes => panic!("Expected one hole, got {:?}", holes),
+        };
+        assert!(!hole.is_empty());
+        for segment in hole {
+            match segment {
+                SketchSegment::Arc { center, end, .. } => {
+                    assert!(center[0].abs() < 1e-9 && center[1].abs() < 1e-9);
+                    let radius = (end[0] * end[0] + end[1] * end[1]).sqrt();
+                    assert!((radius - 2.).abs() < 1e-9);
+                }
+                segment => panic!("Expected arc, got {:?}", segment),
+            }
+        }
+    }
+
+    fn to_string() -> String {
+        let drawing = Drawing::from_shape(&rounded_plate()).unwrap();
+
+        let mut svg = Vec::new();
+        super::write(&drawing, &mut svg).unwrap();
+
+        String::from_utf8(svg).unwrap()
+    }
+}
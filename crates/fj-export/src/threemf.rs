@@ -0,0 +1,307 @@
+//! Export to 3MF files
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_interop::mesh::{Color, Index, Mesh};
+use fj_math::Point;
+use zip::{
+    write::{FileOptions, ZipWriter},
+    CompressionMethod,
+};
+
+use crate::Error;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Target="/3D/3dmodel.model" Id="rel0" Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel"/>
+</Relationships>
+"#;
+
+/// The ID of the base material group, which holds all colors of the mesh
+const MATERIALS: usize = 1;
+
+/// Export the mesh to a 3MF file
+///
+/// Every color of the mesh becomes a base material, which is assigned to the
+/// triangles of that color. Every body becomes a separate object. A body is a
+/// set of triangles that are connected through shared vertices, so bodies that
+/// touch in a vertex end up in the same object.
+///
+/// The coordinates are written in millimeters.
+pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+    let options =
+        FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut archive = ZipWriter::new(File::create(path)?);
+
+    archive.start_file("[Content_Types].xml", options)?;
+    archive.write_all(CONTENT_TYPES.as_bytes())?;
+
+    archive.start_file("_rels/.rels", options)?;
+    archive.write_all(RELATIONSHIPS.as_bytes())?;
+
+    archive.start_file("3D/3dmodel.model", options)?;
+    let mut model = BufWriter::new(&mut archive);
+    write_model(&mut model, mesh)?;
+    model.flush()?;
+    drop(model);
+
+    archive.finish()?;
+
+    Ok(())
+}
+
+fn write_model(file: &mut impl Write, mesh: &Mesh<Point<3>>) -> io::Result<()> {
+    let vertices: Vec<_> = mesh.vertices().collect();
+    let indices: Vec<_> = mesh.indices().collect();
+
+    // The mesh pushes the vertices of each triangle in order, so every chunk of
+    // three indices belongs to the triangle at the same position.
+    let triangles: Vec<_> = indices
+        .chunks(3)
+        .zip(mesh.triangles())
+        .map(|(indices, triangle)| {
+            ([indices[0], indices[1], indices[2]], triangle.color)
+        })
+        .collect();
+
+    let mut colors = Vec::new();
+    let mut materials_by_color = HashMap::new();
+    for &(_, color) in &triangles {
+        materials_by_color.entry(color).or_insert_with(|| {
+            colors.push(color);
+            colors.len() - 1
+        });
+    }
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        file,
+        r#"<model unit="millimeter" xml:lang="en-US" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">"#
+    )?;
+    writeln!(file, r#"  <metadata name="Application">Fornjot</metadata>"#)?;
+    writeln!(file, "  <resources>")?;
+
+    if !colors.is_empty() {
+        writeln!(file, r#"    <basematerials id="{}">"#, MATERIALS)?;
+        for (i, color) in colors.iter().enumerate() {
+            writeln!(
+                file,
+                r##"      <base name="Color {}" displaycolor="#{}"/>"##,
+                i + 1,
+                hex(*color)
+            )?;
+        }
+        writeln!(file, "    </basematerials>")?;
+    }
+
+    let bodies = bodies(&triangles, vertices.len());
+
+    for (i, body) in bodies.iter().enumerate() {
+        let material = |triangle: usize| {
+            let (_, color) = triangles[triangle];
+            materials_by_color[&color]
+        };
+
+        // Triangles that don't specify a material use that of their object.
+        let default_material = material(body[0]);
+
+        writeln!(
+            file,
+            r#"    <object id="{}" name="Body {}" type="model" pid="{}" pindex="{}">"#,
+            object_id(i),
+            i + 1,
+            MATERIALS,
+            default_material
+        )?;
+        writeln!(file, "      <mesh>")?;
+
+        // Each object has its own list of vertices, so the indices of the mesh
+        // have to be translated.
+        let mut local_indices: HashMap<Index, usize> = HashMap::new();
+
+        writeln!(file, "        <vertices>")?;
+        for &triangle in body {
+            let (indices, _) = triangles[triangle];
+
+            for index in indices {
+                if local_indices.contains_key(&index) {
+                    continue;
+                }
+                local_indices.insert(index, local_indices.len());
+
+                let vertex = vertices[index as usize];
+                writeln!(
+                    file,
+                    r#"          <vertex x="{}" y="{}" z="{}"/>"#,
+                    vertex.x, vertex.y, vertex.z
+                )?;
+            }
+        }
+        writeln!(file, "        </vertices>")?;
+
+        writeln!(file, "        <triangles>")?;
+        for &triangle in body {
+            let (indices, _) = triangles[triangle];
+            let [v1, v2, v3] = indices.map(|index| local_indices[&index]);
+
+            write!(
+                file,
+                r#"          <triangle v1="{}" v2="{}" v3="{}""#,
+                v1, v2, v3
+            )?;
+            if material(triangle) != default_material {
+                write!(
+                    file,
+                    r#" pid="{}" p1="{}""#,
+                    MATERIALS,
+                    material(triangle)
+                )?;
+            }
+            writeln!(file, "/>")?;
+        }
+        writeln!(file, "        </triangles>")?;
+
+        writeln!(file, "      </mesh>")?;
+        writeln!(file, "    </object>")?;
+    }
+
+    writeln!(file, "  </resources>")?;
+
+    writeln!(file, "  <build>")?;
+    for i in 0..bodies.len() {
+        writeln!(file, r#"    <item objectid="{}"/>"#, object_id(i))?;
+    }
+    writeln!(file, "  </build>")?;
+
+    writeln!(file, "</model>")?;
+
+    Ok(())
+}
+
+/// Group the triangles into bodies
+///
+/// Returns the indices of the triangles that make up each body. The bodies are
+/// ordered by their first triangle.
+fn bodies(
+    triangles: &[([Index; 3], Color)],
+    num_vertices: usize,
+) -> Vec<Vec<usize>> {
+    // Union-find over the vertices. Vertices that are connected by a triangle
+    // end up with the same root.
+    let mut parents: Vec<usize> = (0..num_vertices).collect();
+
+    fn root(parents: &mut [usize], mut vertex: usize) -> usize {
+        while parents[vertex] != vertex {
+            parents[vertex] = parents[parents[vertex]];
+            vertex = parents[vertex];
+        }
+        vertex
+    }
+
+    for ([a, b, c], _) in triangles {
+        let a = root(&mut parents, *a as usize);
+        for vertex in [b, c] {
+            let root = root(&mut parents, *vertex as usize);
+            parents[root] = a;
+        }
+    }
+
+    let mut bodies: Vec<Vec<usize>> = Vec::new();
+    let mut bodies_by_root = HashMap::new();
+
+    for (i, ([a, _, _], _)) in triangles.iter().enumerate() {
+        let root = root(&mut parents, *a as usize);
+        let body = *bodies_by_root.entry(root).or_insert_with(|| {
+            bodies.push(Vec::new());
+            bodies.len() - 1
+        });
+        bodies[body].push(i);
+    }
+
+    bodies
+}
+
+/// The ID of the object that represents the body with the given index
+///
+/// Object IDs share a namespace with the base material group, which comes
+/// first.
+fn object_id(body: usize) -> usize {
+    MATERIALS + 1 + body
+}
+
+fn hex([r, g, b, a]: Color) -> String {
+    format!("{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    #[test]
+    fn two_cubes() {
+        let mut mesh = Mesh::new();
+        cube(&mut mesh, 0., [255, 0, 0, 255]);
+        cube(&mut mesh, 2., [0, 0, 255, 255]);
+
+        let mut data = Vec::new();
+        super::write_model(&mut data, &mesh).unwrap();
+        let model = String::from_utf8(data).unwrap();
+
+        let count = |pattern: &str| model.matches(pattern).count();
+
+        assert_eq!(count(r#"<model unit="millimeter""#), 1);
+        assert_eq!(count("<basematerials "), 1);
+        assert_eq!(count("<base "), 2);
+        assert_eq!(count("<object "), 2);
+        assert_eq!(count("<item "), 2);
+        assert_eq!(count("<vertex "), 2 * 8);
+        assert_eq!(count("<triangle "), 2 * 12);
+
+        // Each cube has a single color, which is assigned to its object.
+        assert!(model.contains(
+            r#"<object id="2" name="Body 1" type="model" pid="1" pindex="0""#
+        ));
+        assert!(model.contains(
+            r#"<object id="3" name="Body 2" type="model" pid="1" pindex="1""#
+        ));
+        assert_eq!(count(" p1="), 0);
+    }
+
+    /// Add a unit cube, offset along the x axis
+    fn cube(mesh: &mut Mesh<Point<3>>, offset: f64, color: [u8; 4]) {
+        let corner = |i: usize| {
+            Point::from([
+                offset + (i & 1) as f64,
+                ((i >> 1) & 1) as f64,
+                ((i >> 2) & 1) as f64,
+            ])
+        };
+
+        let sides = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+        for [a, b, c, d] in sides {
+            mesh.push_triangle([a, b, c].map(corner), color);
+            mesh.push_triangle([a, c, d].map(corner), color);
+        }
+    }
+}
@@ -1,10 +1,11 @@
 //! A triangle mesh
 
-use std::{collections::HashMap, hash::Hash};
+use std::{collections::HashMap, error, fmt, hash::Hash};
 
 use fj_math::Point;
 
 /// A triangle mesh
+#[derive(Clone)]
 pub struct Mesh<V> {
     vertices: Vec<V>,
     indices: Vec<Index>,
@@ -23,6 +24,10 @@ where
     }
 
     /// Add a vertex to the mesh
+    ///
+    /// A vertex that is pushed for the first time is assigned the next free
+    /// index. The indices, and the order of the vertices, only depend on the
+    /// order in which the vertices are pushed.
     pub fn push_vertex(&mut self, vertex: V) {
         let index =
             *self.indices_by_vertex.entry(vertex).or_insert_with(|| {
@@ -52,6 +57,8 @@ where
     }
 
     /// Access the vertices of the mesh
+    ///
+    /// The vertices are returned in the order in which they were first pushed.
     pub fn vertices(&self) -> impl Iterator<Item = V> + '_ {
         self.vertices.iter().copied()
     }
@@ -65,6 +72,54 @@ where
     pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
         self.triangles.iter().copied()
     }
+
+    /// Validate that the mesh is a closed manifold
+    ///
+    /// Checks that every edge is shared by exactly two triangles, and that
+    /// those triangles are oriented consistently, meaning they traverse their
+    /// shared edge in opposite directions.
+    pub fn validate_manifold(&self) -> Result<(), ManifoldIssues> {
+        // The uses of each edge, as the triangle that uses it, and whether the
+        // triangle traverses it from the lower to the higher index.
+        let mut uses: HashMap<[Index; 2], Vec<(usize, bool)>> = HashMap::new();
+
+        for (i, triangle) in self.indices.chunks_exact(3).enumerate() {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+
+            for [from, to] in [[a, b], [b, c], [c, a]] {
+                if from == to {
+                    continue;
+                }
+
+                let edge = [from.min(to), from.max(to)];
+                uses.entry(edge).or_default().push((i, from < to));
+            }
+        }
+
+        let mut issues = ManifoldIssues::default();
+        for (edge, uses) in uses {
+            match uses.as_slice() {
+                [_] => issues.open_edges.push(edge),
+                [(a, a_forward), (b, b_forward)] => {
+                    if a_forward == b_forward {
+                        issues.inconsistent_orientations.push([*a, *b]);
+                    }
+                }
+                _ => issues.non_manifold_edges.push(edge),
+            }
+        }
+
+        if issues.is_empty() {
+            return Ok(());
+        }
+
+        issues.open_edges.sort();
+        issues.non_manifold_edges.sort();
+        issues.inconsistent_orientations.sort();
+        issues.inconsistent_orientations.dedup();
+
+        Err(issues)
+    }
 }
 
 impl Mesh<Point<3>> {
@@ -91,6 +146,90 @@ impl<V> Default for Mesh<V> {
     }
 }
 
+/// Manifold issues found during validation
+///
+/// Returned by [`Mesh::validate_manifold`], where edges are identified by the
+/// sorted indices of their vertices, and faces by the position of their
+/// triangle in [`Mesh::triangles`]. The kernel reports issues with the shells
+/// of a shape using the same type, with its own identifiers for edges and
+/// faces.
+#[derive(Debug)]
+pub struct ManifoldIssues<E = [Index; 2], F = usize> {
+    /// Edges that are used by only one face
+    pub open_edges: Vec<E>,
+
+    /// Edges that are used by more than two faces
+    pub non_manifold_edges: Vec<E>,
+
+    /// Pairs of neighboring faces that are not oriented consistently
+    ///
+    /// Each pair is sorted, and only reported once.
+    pub inconsistent_orientations: Vec<[F; 2]>,
+}
+
+impl<E, F> ManifoldIssues<E, F> {
+    /// Indicate whether no issues were found
+    pub fn is_empty(&self) -> bool {
+        self.open_edges.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.inconsistent_orientations.is_empty()
+    }
+}
+
+// This needs to be a manual implementation. Deriving `Default` would require
+// `E` and `F` to be `Default` as well, even though that is not necessary.
+impl<E, F> Default for ManifoldIssues<E, F> {
+    fn default() -> Self {
+        Self {
+            open_edges: Vec::new(),
+            non_manifold_edges: Vec::new(),
+            inconsistent_orientations: Vec::new(),
+        }
+    }
+}
+
+impl<E, F> fmt::Display for ManifoldIssues<E, F>
+where
+    E: fmt::Debug,
+    F: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Manifold issues found:")?;
+
+        if !self.open_edges.is_empty() {
+            writeln!(f, "- Open edges:")?;
+
+            for edge in &self.open_edges {
+                writeln!(f, "  - {:?}", edge)?;
+            }
+        }
+        if !self.non_manifold_edges.is_empty() {
+            writeln!(f, "- Non-manifold edges:")?;
+
+            for edge in &self.non_manifold_edges {
+                writeln!(f, "  - {:?}", edge)?;
+            }
+        }
+        if !self.inconsistent_orientations.is_empty() {
+            writeln!(f, "- Inconsistently oriented faces:")?;
+
+            for [a, b] in &self.inconsistent_orientations {
+                writeln!(f, "  - {:?}", a)?;
+                writeln!(f, "    {:?}", b)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<E, F> error::Error for ManifoldIssues<E, F>
+where
+    E: fmt::Debug,
+    F: fmt::Debug,
+{
+}
+
 /// An index that refers to a vertex in a mesh
 pub type Index = u32;
 
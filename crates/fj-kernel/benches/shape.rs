@@ -0,0 +1,101 @@
+use criterion::{
+    criterion_group, criterion_main, BenchmarkId, Criterion, Throughput,
+};
+use fj_kernel::{
+    algorithms::transform_shape,
+    shape::Shape,
+    topology::{Solid, Vertex},
+};
+use fj_math::{Scalar, Transform};
+
+/// Insert a grid of vertices
+///
+/// Every insertion validates that the new vertex is unique, and looks for an
+/// identical point or vertex to re-use. That requires a lookup of nearby
+/// vertices.
+fn insert_vertices(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert_vertices");
+
+    for n in [8, 16, 32] {
+        group.throughput(Throughput::Elements(n * n * n));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| {
+                let mut shape = Shape::new();
+
+                for x in 0..n {
+                    for y in 0..n {
+                        for z in 0..n {
+                            let point = [x as f64, y as f64, z as f64];
+                            Vertex::builder(&mut shape)
+                                .build_from_point(point)
+                                .unwrap();
+                        }
+                    }
+                }
+
+                shape
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Merge a pattern of cubes into a single shape
+///
+/// Neighboring cubes touch, so many of their points and vertices are shared.
+fn pattern_cubes(c: &mut Criterion) {
+    let mut cube = Shape::new();
+    Solid::builder(&mut cube)
+        .build_cube_from_edge_size(Scalar::ONE)
+        .unwrap();
+
+    let mut group = c.benchmark_group("pattern_cubes");
+    group.sample_size(10);
+
+    for n in [2, 4, 8] {
+        group.throughput(Throughput::Elements(n * n));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| {
+                let mut shape = Shape::new();
+
+                for x in 0..n {
+                    for y in 0..n {
+                        let (mut copy, _) = cube.clone_shape();
+                        let translation =
+                            Transform::translation([x as f64, y as f64, 0.]);
+                        transform_shape(&mut copy, &translation).unwrap();
+
+                        shape.merge_shape(&copy).unwrap();
+                    }
+                }
+
+                shape
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Find the nearest vertex in a grid of vertices
+fn nearest_vertex(c: &mut Criterion) {
+    let n = 32;
+
+    let mut shape = Shape::new();
+    for x in 0..n {
+        for y in 0..n {
+            for z in 0..n {
+                let point = [x as f64, y as f64, z as f64];
+                Vertex::builder(&mut shape).build_from_point(point).unwrap();
+            }
+        }
+    }
+
+    c.bench_function("nearest_vertex", |b| {
+        b.iter(|| shape.nearest_vertex([12.3, 4.5, 6.7]).unwrap());
+    });
+}
+
+criterion_group!(benches, insert_vertices, pattern_cubes, nearest_vertex);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 use std::cmp::max;
 
-use fj_math::{Circle, Scalar};
+use fj_math::{Circle, Ellipse, NurbsCurve, Point, Scalar};
 
 use crate::geometry::{self, Curve};
 
@@ -11,23 +11,36 @@ use super::Tolerance;
 /// `tolerance` defines how far the approximation is allowed to deviate from the
 /// actual edge.
 ///
-/// # Implementation Note
+/// `range` is the section of the curve that the edge inhabits, as defined by
+/// the local coordinates of its vertices. Only points in between those are
+/// returned, not the points at the ends of the range. Those are expected to be
+/// provided by the vertices of the edge. If no range is provided, the whole
+/// curve is approximated.
 ///
-/// This only works as it is, because edges are severely limited and don't
-/// define which section of the curve they inhabit. Once they do that, we need
-/// an `approximate_between(a, b)` method instead, where `a` and `b` are the
-/// vertices that bound the edge on the curve.
+/// # Implementation Note
 ///
-/// The `approximate_between` methods of the curves then need to make sure to
-/// only return points in between those vertices, not the vertices themselves.
+/// Lines don't need any points in between their vertices, and NURBS curves are
+/// always approximated across their whole domain. Edges on NURBS curves are
+/// expected to be bounded by the ends of their curve's domain.
 pub fn approx_curve<const D: usize>(
     curve: &Curve<D>,
+    range: Option<[Scalar; 2]>,
     tolerance: Tolerance,
     out: &mut Vec<geometry::Point<1, D>>,
 ) {
+    let reversed = matches!(range, Some([a, b]) if a > b);
+    let range = range.map(|[a, b]| if reversed { [b, a] } else { [a, b] });
+    let len = out.len();
+
     match curve {
-        Curve::Circle(curve) => approx_circle(curve, tolerance, out),
+        Curve::Circle(curve) => approx_circle(curve, range, tolerance, out),
+        Curve::Ellipse(curve) => approx_ellipse(curve, range, tolerance, out),
         Curve::Line(_) => {}
+        Curve::Nurbs(curve) => approx_nurbs_curve(curve, tolerance, out),
+    }
+
+    if reversed {
+        out[len..].reverse();
     }
 }
 
@@ -35,8 +48,12 @@ pub fn approx_curve<const D: usize>(
 ///
 /// `tolerance` specifies how much the approximation is allowed to deviate
 /// from the circle.
+///
+/// If `range` is provided, only the points between its start and end are
+/// returned. Otherwise, the approximation covers the whole circle.
 pub fn approx_circle<const D: usize>(
     circle: &Circle<D>,
+    range: Option<[Scalar; 2]>,
     tolerance: Tolerance,
     out: &mut Vec<geometry::Point<1, D>>,
 ) {
@@ -50,13 +67,214 @@ pub fn approx_circle<const D: usize>(
 
     let n = number_of_vertices_for_circle(tolerance, radius);
 
-    for i in 0..n {
-        let angle = Scalar::PI * 2. / n as f64 * i as f64;
+    let angles: Vec<_> = match range {
+        Some([start, end]) => {
+            // An arc needs no more than its share of the polygon's vertices.
+            let n = ((end - start) / (Scalar::PI * 2.) * n as f64)
+                .ceil()
+                .into_u64()
+                .max(1);
+
+            (1..n)
+                .map(|i| start + (end - start) / n as f64 * i as f64)
+                .collect()
+        }
+        None => (0..n)
+            .map(|i| Scalar::PI * 2. / n as f64 * i as f64)
+            .collect(),
+    };
+
+    for angle in angles {
         let point = circle.point_from_circle_coords([angle]);
         out.push(geometry::Point::new([angle], point));
     }
 }
 
+/// Approximate the ellipse
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the ellipse.
+///
+/// If `range` is provided, only the points between its start and end are
+/// returned. Otherwise, the approximation covers the whole ellipse.
+pub fn approx_ellipse<const D: usize>(
+    ellipse: &Ellipse<D>,
+    range: Option<[Scalar; 2]>,
+    tolerance: Tolerance,
+    out: &mut Vec<geometry::Point<1, D>>,
+) {
+    // An ellipse is the image of a circle under a linear map. If we step along
+    // the ellipse in steps of `angle` (in ellipse coordinates), the distance
+    // between the approximation and the ellipse is about
+    // `r * (1 - cos(angle / 2))`, with `r = major * minor / |P'(t)|` being the
+    // radius of an equivalent circle at ellipse coordinate `t`.
+    //
+    // `r` varies between the semi-minor and the semi-major axis, so this
+    // approximation can use larger steps where the ellipse is flat, instead of
+    // using the worst-case step size everywhere.
+    let [major, minor] = ellipse.semi_axes();
+    let radius = |t: Scalar| {
+        // The derivative of the ellipse at `t` is the same as the vector at
+        // `t + PI / 2`.
+        let derivative =
+            ellipse.vector_from_ellipse_coords([t + Scalar::PI / 2.]);
+        major * minor / derivative.magnitude()
+    };
+    let step = |radius: Scalar| {
+        let ratio = (tolerance.inner() / radius).min(Scalar::ONE);
+        let step = (Scalar::ONE - ratio).acos() * 2.;
+
+        // Make sure we end up with at least 3 points, same as with circles.
+        step.min(Scalar::PI * 2. / 3.)
+    };
+
+    // The radius has its extrema at the ends of the principal axes of the
+    // ellipse. These are the ellipse coordinates of the first one.
+    let [aa, ab, bb] = [
+        ellipse.a.dot(&ellipse.a),
+        ellipse.a.dot(&ellipse.b),
+        ellipse.b.dot(&ellipse.b),
+    ];
+    let principal = Scalar::atan2(ab * 2., aa - bb) / 2.;
+
+    let [start, end] = range.unwrap_or([Scalar::ZERO, Scalar::PI * 2.]);
+
+    let mut angle = start;
+    while angle < end {
+        if range.is_none() || angle > start {
+            let point = ellipse.point_from_ellipse_coords([angle]);
+            out.push(geometry::Point::new([angle], point));
+        }
+
+        // The radius can change over the course of the step. Sample it at the
+        // end of the step and at any extremum within the step, to make sure we
+        // don't exceed the tolerance.
+        let estimate = step(radius(angle));
+        let extrema = (0..8)
+            .map(|i| principal + Scalar::PI / 2. * (i as f64 - 2.))
+            .filter(|&t| t > angle && t < angle + estimate);
+        let radius = [angle, angle + estimate]
+            .into_iter()
+            .chain(extrema)
+            .map(radius)
+            .fold(Scalar::ZERO, Scalar::max);
+
+        angle += step(radius);
+    }
+}
+
+/// Approximate the NURBS curve
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the curve.
+///
+/// If the curve is closed, the approximation includes the point at the start
+/// of the curve's domain, same as the approximation of a circle. Otherwise,
+/// it only includes points between the start and end of the domain. Those are
+/// expected to be provided by the vertices of the edge.
+pub fn approx_nurbs_curve<const D: usize>(
+    curve: &NurbsCurve<D>,
+    tolerance: Tolerance,
+    out: &mut Vec<geometry::Point<1, D>>,
+) {
+    let mut params = nurbs_curve_params(curve, tolerance);
+
+    // The end of the domain is never included, same as with circles.
+    params.pop();
+
+    if !curve.is_closed() {
+        params.remove(0);
+    }
+
+    for t in params {
+        let point = curve.point_from_curve_coords([t]);
+        out.push(geometry::Point::new([t], point));
+    }
+}
+
+/// Compute the curve coordinates that approximate a NURBS curve
+///
+/// Includes the start and end of the curve's domain.
+pub fn nurbs_curve_params<const D: usize>(
+    curve: &NurbsCurve<D>,
+    tolerance: Tolerance,
+) -> Vec<Scalar> {
+    let [start, end] = curve.domain();
+
+    // The curve is only guaranteed to be smooth within a knot span, so each
+    // span is approximated separately.
+    let mut knots: Vec<_> = curve
+        .knots
+        .iter()
+        .copied()
+        .filter(|&knot| knot > start && knot < end)
+        .collect();
+    knots.insert(0, start);
+    knots.push(end);
+    knots.dedup();
+
+    let mut params = Vec::new();
+    for span in knots.windows(2) {
+        params.push(span[0]);
+        subdivide(curve, [span[0], span[1]], tolerance, 0, &mut params);
+    }
+    params.push(end);
+
+    params
+}
+
+/// Recursively subdivide a section of a NURBS curve
+///
+/// Adds all curve coordinates between `a` and `b` (exclusive) that are
+/// required to approximate the section within `tolerance`.
+fn subdivide<const D: usize>(
+    curve: &NurbsCurve<D>,
+    [a, b]: [Scalar; 2],
+    tolerance: Tolerance,
+    depth: usize,
+    out: &mut Vec<Scalar>,
+) {
+    // Limit the recursion, in case the tolerance can't be reached due to
+    // floating point accuracy issues.
+    const MAX_DEPTH: usize = 16;
+
+    let segment = [a, b].map(|t| curve.point_from_curve_coords([t]));
+
+    // Checking a single point in the middle could miss deviations, if the
+    // section has an inflection point. Checking a few points is more robust.
+    let within_tolerance = [0.25, 0.5, 0.75].into_iter().all(|s| {
+        let point = curve.point_from_curve_coords([a + (b - a) * s]);
+        distance_to_segment(point, segment) <= tolerance.inner()
+    });
+
+    if within_tolerance || depth >= MAX_DEPTH {
+        return;
+    }
+
+    let middle = a + (b - a) / 2.;
+    subdivide(curve, [a, middle], tolerance, depth + 1, out);
+    out.push(middle);
+    subdivide(curve, [middle, b], tolerance, depth + 1, out);
+}
+
+pub fn distance_to_segment<const D: usize>(
+    point: Point<D>,
+    [a, b]: [Point<D>; 2],
+) -> Scalar {
+    let ab = b - a;
+
+    let length = ab.dot(&ab);
+    if length == Scalar::ZERO {
+        return (point - a).magnitude();
+    }
+
+    let s = ((point - a).dot(&ab) / length)
+        .max(Scalar::ZERO)
+        .min(Scalar::ONE);
+
+    (point - (a + ab * s)).magnitude()
+}
+
 fn number_of_vertices_for_circle(tolerance: Tolerance, radius: Scalar) -> u64 {
     let n = (Scalar::PI / (Scalar::ONE - (tolerance.inner() / radius)).acos())
         .ceil()
@@ -67,9 +285,9 @@ fn number_of_vertices_for_circle(tolerance: Tolerance, radius: Scalar) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use fj_math::Scalar;
+    use fj_math::{Circle, Ellipse, NurbsCurve, Point, Scalar, Vector};
 
-    use crate::algorithms::Tolerance;
+    use crate::{algorithms::Tolerance, geometry::Curve};
 
     #[test]
     fn number_of_vertices_for_circle() {
@@ -100,4 +318,143 @@ mod tests {
             radius - radius * (Scalar::PI / Scalar::from_u64(n)).cos()
         }
     }
+
+    #[test]
+    fn approx_circle_between_vertices() {
+        let circle = Circle {
+            center: Point::from([0., 0.]),
+            a: Vector::from([1., 0.]),
+            b: Vector::from([0., 1.]),
+        };
+        let tolerance = Tolerance::from_scalar(0.01).unwrap();
+
+        // A quarter circle that crosses the point where circle coordinates
+        // wrap around.
+        let range = [Scalar::PI * 1.75, Scalar::PI * 2.25];
+
+        let mut points = Vec::new();
+        super::approx_circle(&circle, Some(range), tolerance, &mut points);
+
+        let n = super::number_of_vertices_for_circle(tolerance, Scalar::ONE);
+        let segments = (n as f64 / 4.).ceil() as usize;
+        assert_eq!(points.len(), segments - 1);
+
+        let mut params: Vec<_> =
+            points.iter().map(|point| point.local().t).collect();
+        assert!(params.iter().all(|&t| t > range[0] && t < range[1]));
+
+        params.insert(0, range[0]);
+        params.push(range[1]);
+        for segment in params.windows(2) {
+            // The arc is approximated with steps of the same size as the full
+            // circle.
+            assert!(segment[1] - segment[0] <= Scalar::PI * 2. / n as f64);
+        }
+
+        // Approximating in the other direction yields the same points, in
+        // reverse order.
+        let mut reversed = Vec::new();
+        super::approx_curve(
+            &Curve::Circle(circle),
+            Some([range[1], range[0]]),
+            tolerance,
+            &mut reversed,
+        );
+        reversed.reverse();
+        assert_eq!(points, reversed);
+    }
+
+    #[test]
+    fn approx_ellipse() {
+        let ellipses = [
+            Ellipse {
+                center: Point::from([1., 2., 3.]),
+                a: Vector::from([10., 0., 0.]),
+                b: Vector::from([0., 1., 1.]),
+            },
+            Ellipse {
+                center: Point::from([0., 0., 0.]),
+                a: Vector::from([3., 1., 0.]),
+                b: Vector::from([2., 2., 0.]),
+            },
+            Ellipse {
+                center: Point::from([0., 0., 0.]),
+                a: Vector::from([1., 0., 0.]),
+                b: Vector::from([0., 1., 0.]),
+            },
+        ];
+        let tolerance = Tolerance::from_scalar(0.01).unwrap();
+
+        for ellipse in ellipses {
+            verify(ellipse, tolerance);
+        }
+
+        fn verify(ellipse: Ellipse<3>, tolerance: Tolerance) {
+            let mut points = Vec::new();
+            super::approx_ellipse(&ellipse, None, tolerance, &mut points);
+
+            // The adaptive approximation should not need more points than a
+            // uniform one, which must use the worst-case step size everywhere.
+            let [major, _] = ellipse.semi_axes();
+            let uniform =
+                super::number_of_vertices_for_circle(tolerance, major);
+            assert!(points.len() as u64 <= uniform);
+
+            let mut angles: Vec<_> =
+                points.iter().map(|point| point.local().t).collect();
+            angles.push(Scalar::PI * 2.);
+
+            for segment in angles.windows(2) {
+                let [start, end] = [segment[0], segment[1]];
+                let [a, b] = [start, end]
+                    .map(|t| ellipse.point_from_ellipse_coords([t]));
+
+                for i in 1..10 {
+                    let t = start + (end - start) * (i as f64 / 10.);
+                    let point = ellipse.point_from_ellipse_coords([t]);
+
+                    let distance = super::distance_to_segment(point, [a, b]);
+
+                    // Allow for floating point inaccuracy. The distance
+                    // can be equal to the tolerance, for circles.
+                    assert!(distance <= tolerance.inner() * (1. + 1e-12));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn approx_nurbs_curve() {
+        let curve = NurbsCurve::from_control_points(
+            3,
+            [[0., 0.], [1., 2.], [2., -1.], [3., 1.], [4., 0.]],
+        );
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        let mut points = Vec::new();
+        super::approx_nurbs_curve(&curve, tolerance, &mut points);
+
+        // The curve is open, so its start and end are not included.
+        let mut params: Vec<_> =
+            points.iter().map(|point| point.local().t).collect();
+        assert!(params.iter().all(|&t| t > Scalar::ZERO && t < Scalar::ONE));
+        assert!(params.windows(2).all(|params| params[0] < params[1]));
+
+        params.insert(0, Scalar::ZERO);
+        params.push(Scalar::ONE);
+
+        for segment in params.windows(2) {
+            let [start, end] = [segment[0], segment[1]];
+            let [a, b] =
+                [start, end].map(|t| curve.point_from_curve_coords([t]));
+
+            for i in 1..10 {
+                let t = start + (end - start) * (i as f64 / 10.);
+                let point = curve.point_from_curve_coords([t]);
+
+                let distance = super::distance_to_segment(point, [a, b]);
+                assert!(distance <= tolerance.inner());
+            }
+        }
+    }
 }
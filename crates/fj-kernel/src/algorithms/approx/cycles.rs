@@ -5,7 +5,7 @@ use crate::{geometry, topology::Cycle};
 use super::{curves::approx_curve, edges::approximate_edge, Tolerance};
 
 /// An approximation of a [`Cycle`]
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct CycleApprox {
     /// The points that approximate the cycle
     pub points: Vec<geometry::Point<3, 3>>,
@@ -17,16 +17,22 @@ impl CycleApprox {
     /// `tolerance` defines how far the approximation is allowed to deviate from
     /// the actual face.
     pub fn new(cycle: &Cycle<3>, tolerance: Tolerance) -> Self {
-        let mut points = Vec::new();
+        let mut edges = Vec::new();
 
         for edge in cycle.edges() {
+            let range = edge.vertices.as_ref().map(|vertices| {
+                vertices.clone().map(|vertex| vertex.local().t)
+            });
+
             let mut edge_points = Vec::new();
-            approx_curve(&edge.curve(), tolerance, &mut edge_points);
+            approx_curve(&edge.curve(), range, tolerance, &mut edge_points);
             approximate_edge(edge.vertices, &mut edge_points);
 
-            points.extend(edge_points);
+            edges.push(edge_points);
         }
 
+        let points = chain(edges);
+
         let mut points: Vec<_> = points
             .into_iter()
             .map(|point| {
@@ -55,3 +61,48 @@ impl CycleApprox {
         segments
     }
 }
+
+/// Chain the approximated edges of a cycle, so each continues the previous one
+///
+/// The edges of a cycle are not guaranteed to be ordered or oriented, such that
+/// each edge starts where the previous one ended. The side faces created by
+/// sweeping share their edges with other faces, for example, and don't follow
+/// that rule.
+///
+/// Edges that don't connect to any other edge, like continuous edges, are
+/// taken in the order in which they appear in the cycle.
+fn chain(
+    mut edges: Vec<Vec<geometry::Point<1, 3>>>,
+) -> Vec<geometry::Point<1, 3>> {
+    let mut points: Vec<geometry::Point<1, 3>> = Vec::new();
+
+    while !edges.is_empty() {
+        let end = points.last().map(|point| point.canonical());
+
+        // Edges that are already in the right order are found right away.
+        let next = end.and_then(|end| {
+            edges.iter().enumerate().find_map(|(i, edge)| {
+                let first = edge.first()?.canonical();
+                let last = edge.last()?.canonical();
+
+                if first == end {
+                    Some((i, false))
+                } else if last == end {
+                    Some((i, true))
+                } else {
+                    None
+                }
+            })
+        });
+        let (i, reverse) = next.unwrap_or((0, false));
+
+        let mut edge = edges.remove(i);
+        if reverse {
+            edge.reverse();
+        }
+
+        points.extend(edge);
+    }
+
+    points
+}
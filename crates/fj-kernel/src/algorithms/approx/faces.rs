@@ -1,23 +1,35 @@
-use std::collections::HashSet;
+use std::{collections::BTreeSet, iter};
 
-use crate::{geometry, topology::Face};
+use fj_math::{NurbsSurface, Point, Scalar};
 
-use super::{CycleApprox, Tolerance};
+use crate::{
+    geometry::{self, Surface},
+    topology::Face,
+};
+
+use super::{
+    curves::distance_to_segment, surfaces::approx_nurbs_surface, CycleApprox,
+    Tolerance,
+};
 
 /// An approximation of a [`Face`]
+///
+/// The points and interior cycles are kept in sorted sets. Their order only
+/// depends on their values, which makes everything that is computed from the
+/// approximation, like the triangulation, deterministic.
 #[derive(Debug, PartialEq)]
 pub struct FaceApprox {
     /// All points that make up the approximation
     ///
     /// These could be actual vertices from the model, points that approximate
     /// an edge, or points that approximate a face.
-    pub points: HashSet<geometry::Point<3, 3>>,
+    pub points: BTreeSet<geometry::Point<3, 3>>,
 
     /// Approximation of the exterior cycle
     pub exterior: CycleApprox,
 
     /// Approximations of the interior cycles
-    pub interiors: HashSet<CycleApprox>,
+    pub interiors: BTreeSet<CycleApprox>,
 }
 
 impl FaceApprox {
@@ -26,22 +38,18 @@ impl FaceApprox {
     /// `tolerance` defines how far the approximation is allowed to deviate from
     /// the actual face.
     pub fn new(face: &Face, tolerance: Tolerance) -> Self {
-        // Curved faces whose curvature is not fully defined by their edges
-        // are not supported yet. For that reason, we can fully ignore `face`'s
-        // `surface` field and just pass the edges to `Self::for_edges`.
-        //
-        // An example of a curved face that is supported, is the cylinder. Its
-        // curvature is fully defined be the edges (circles) that border it. The
-        // circle approximations are sufficient to triangulate the surface.
+        // The curvature of some faces is fully defined by their edges. An
+        // example of this is the cylinder, whose curvature is fully defined by
+        // the edges (circles) that border it. The circle approximations are
+        // sufficient to triangulate the surface.
         //
-        // An example of a curved face that is currently not supported, and thus
-        // doesn't need to be handled here, is a sphere. A spherical face would
-        // would need to provide its own approximation, as the edges that bound
-        // it have nothing to do with its curvature.
+        // The curvature of NURBS surfaces has nothing to do with the edges that
+        // bound them, so those need to provide additional points within the
+        // face.
 
-        let mut points = HashSet::new();
+        let mut points = BTreeSet::new();
         let mut exteriors = Vec::new();
-        let mut interiors = HashSet::new();
+        let mut interiors = BTreeSet::new();
 
         for cycle in face.exteriors() {
             let cycle = CycleApprox::new(&cycle, tolerance);
@@ -68,6 +76,13 @@ impl FaceApprox {
             "Approximation only supports faces with one exterior cycle",
         );
 
+        if let Surface::Nurbs(surface) = face.surface() {
+            let cycles = iter::once(&exterior).chain(&interiors);
+            points.extend(approx_surface_within_cycles(
+                &surface, cycles, tolerance,
+            ));
+        }
+
         Self {
             points,
             exterior,
@@ -76,10 +91,79 @@ impl FaceApprox {
     }
 }
 
+/// Approximate a NURBS surface within the bounds of the given cycles
+///
+/// Only returns points that are well within the face, so the triangulation
+/// doesn't produce slivers along its boundary.
+fn approx_surface_within_cycles<'r>(
+    surface: &NurbsSurface,
+    cycles: impl Iterator<Item = &'r CycleApprox>,
+    tolerance: Tolerance,
+) -> Vec<geometry::Point<3, 3>> {
+    let cycles: Vec<Vec<Point<2>>> = cycles
+        .map(|cycle| {
+            cycle
+                .points
+                .iter()
+                .map(|point| surface.point_to_surface_coords(point.canonical()))
+                .collect()
+        })
+        .collect();
+
+    let [us, vs] = approx_nurbs_surface(surface, tolerance);
+
+    // Points that are closer to the boundary than half of the grid spacing are
+    // left out. The boundary provides enough points there.
+    let spacing = us
+        .windows(2)
+        .chain(vs.windows(2))
+        .map(|params| params[1] - params[0])
+        .min()
+        .unwrap_or(Scalar::ZERO);
+    let margin = spacing / 2.;
+
+    let mut points = Vec::new();
+    for &u in &us {
+        for &v in &vs {
+            let point = Point::from([u, v]);
+
+            let segments = cycles.iter().flat_map(|cycle| {
+                cycle.windows(2).map(|segment| [segment[0], segment[1]])
+            });
+
+            // Count the boundary crossings of a ray in the positive u
+            // direction, to find out whether the point is inside the face.
+            let mut inside = false;
+            let mut near_boundary = false;
+            for [a, b] in segments {
+                if distance_to_segment(point, [a, b]) < margin {
+                    near_boundary = true;
+                    break;
+                }
+
+                if (a.v > point.v) != (b.v > point.v) {
+                    let s = (point.v - a.v) / (b.v - a.v);
+                    if a.u + (b.u - a.u) * s > point.u {
+                        inside = !inside;
+                    }
+                }
+            }
+
+            if inside && !near_boundary {
+                let point = surface.point_from_surface_coords(point);
+                points.push(geometry::Point::new(point, point));
+            }
+        }
+    }
+
+    points
+}
+
 #[cfg(test)]
 mod tests {
-    use fj_math::{Point, Scalar};
-    use map_macro::set;
+    use std::collections::BTreeSet;
+
+    use fj_math::{NurbsSurface, Point, Scalar};
 
     use crate::{
         geometry::{self, Surface},
@@ -123,17 +207,69 @@ mod tests {
 
         let approx = FaceApprox::new(&face.get(), tolerance);
         let expected = FaceApprox {
-            points: set![a, b, c, d, e, f, g, h],
+            points: BTreeSet::from([a, b, c, d, e, f, g, h]),
             exterior: CycleApprox {
                 points: vec![a, b, c, d, a],
             },
-            interiors: set![CycleApprox {
+            interiors: BTreeSet::from([CycleApprox {
                 points: vec![e, f, g, h, e],
-            }],
+            }]),
         };
 
         assert_eq!(approx, expected);
 
         Ok(())
     }
+
+    #[test]
+    fn for_face_on_nurbs_surface() -> anyhow::Result<()> {
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let mut shape = Shape::new();
+
+        // A surface that bulges upwards in its middle.
+        let nurbs = NurbsSurface::from_control_points(
+            [2, 2],
+            vec![
+                vec![[0., 0., 0.], [0., 1., 0.], [0., 2., 0.]],
+                vec![[1., 0., 0.], [1., 1., 2.], [1., 2., 0.]],
+                vec![[2., 0., 0.], [2., 1., 0.], [2., 2., 0.]],
+            ]
+            .into_iter()
+            .map(|row| row.into_iter().map(Point::from).collect())
+            .collect(),
+        );
+
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([2., 0., 0.]);
+        let c = Point::from([2., 2., 0.]);
+        let d = Point::from([0., 2., 0.]);
+
+        let face = Face::builder(Surface::Nurbs(nurbs), &mut shape)
+            .with_exterior_polygon([a, b, c, d])
+            .build()?;
+
+        let approx = FaceApprox::new(&face.get(), tolerance);
+
+        // The boundary doesn't say anything about the curvature of the
+        // surface, so the approximation needs to provide points within it.
+        let interior_points: Vec<_> = approx
+            .points
+            .iter()
+            .filter(|point| !approx.exterior.points.contains(point))
+            .collect();
+        assert!(!interior_points.is_empty());
+
+        // The x and y coordinates of the surface are linear in u and v, so
+        // they can be checked directly.
+        for point in interior_points {
+            let point = point.canonical();
+
+            assert!(point.x > Scalar::ZERO && point.x < Scalar::from(2.));
+            assert!(point.y > Scalar::ZERO && point.y < Scalar::from(2.));
+            assert!(point.z > Scalar::ZERO);
+        }
+
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@ mod curves;
 mod cycles;
 mod edges;
 mod faces;
+mod surfaces;
 mod tolerance;
 
 pub use self::{
@@ -0,0 +1,119 @@
+use fj_math::{NurbsCurve, NurbsSurface, Scalar};
+
+use super::{curves::nurbs_curve_params, Tolerance};
+
+/// Compute a grid of surface coordinates that approximates a NURBS surface
+///
+/// `tolerance` defines how far the triangulated grid is allowed to deviate from
+/// the surface.
+///
+/// Returns the u and v coordinates of the grid lines, in that order. The grid
+/// covers the whole domain of the surface. It's up to the caller to remove any
+/// points that are outside of the face being approximated.
+pub fn approx_nurbs_surface(
+    surface: &NurbsSurface,
+    tolerance: Tolerance,
+) -> [Vec<Scalar>; 2] {
+    // The error of the triangulated grid is bounded by the errors in both
+    // directions, so each direction gets half of the tolerance.
+    let tolerance = Tolerance::from_scalar(tolerance.inner() / 2.)
+        .expect("Half of a valid tolerance is a valid tolerance");
+
+    let [domain_u, domain_v] = surface.domain();
+
+    let us = params(
+        sample_params(domain_v, &surface.knots[1])
+            .map(|v| surface.curve_at_v(v)),
+        tolerance,
+    );
+    let vs = params(
+        sample_params(domain_u, &surface.knots[0])
+            .map(|u| surface.curve_at_u(u)),
+        tolerance,
+    );
+
+    [us, vs]
+}
+
+/// Compute the parameters of the isoparametric curves that are checked
+///
+/// Includes the knots within the domain, and the points halfway between them.
+fn sample_params(
+    [start, end]: [Scalar; 2],
+    knots: &[Scalar],
+) -> impl Iterator<Item = Scalar> {
+    let mut knots: Vec<_> = knots
+        .iter()
+        .copied()
+        .filter(|&knot| knot > start && knot < end)
+        .collect();
+    knots.insert(0, start);
+    knots.push(end);
+    knots.dedup();
+
+    let middles: Vec<_> = knots
+        .windows(2)
+        .map(|span| span[0] + (span[1] - span[0]) / 2.)
+        .collect();
+
+    knots.into_iter().chain(middles)
+}
+
+/// Merge the approximation parameters of multiple curves
+fn params(
+    curves: impl Iterator<Item = NurbsCurve<3>>,
+    tolerance: Tolerance,
+) -> Vec<Scalar> {
+    let mut params: Vec<_> = curves
+        .flat_map(|curve| nurbs_curve_params(&curve, tolerance))
+        .collect();
+
+    params.sort();
+    params.dedup();
+
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{NurbsSurface, Point, Scalar};
+
+    use crate::algorithms::Tolerance;
+
+    use super::approx_nurbs_surface;
+
+    #[test]
+    fn approx_flat_surface() {
+        let surface = NurbsSurface::from_control_points(
+            [1, 1],
+            vec![
+                vec![Point::from([0., 0., 0.]), Point::from([0., 1., 0.])],
+                vec![Point::from([1., 0., 0.]), Point::from([1., 1., 0.])],
+            ],
+        );
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        // A flat surface doesn't need any grid lines except the boundaries.
+        let [us, vs] = approx_nurbs_surface(&surface, tolerance);
+        assert_eq!(us, vec![Scalar::ZERO, Scalar::ONE]);
+        assert_eq!(vs, vec![Scalar::ZERO, Scalar::ONE]);
+    }
+
+    #[test]
+    fn approx_curved_surface() {
+        let surface = NurbsSurface::from_control_points(
+            [2, 1],
+            vec![
+                vec![Point::from([0., 0., 0.]), Point::from([0., 1., 0.])],
+                vec![Point::from([1., 0., 1.]), Point::from([1., 1., 1.])],
+                vec![Point::from([2., 0., 0.]), Point::from([2., 1., 0.])],
+            ],
+        );
+        let tolerance = Tolerance::from_scalar(0.01).unwrap();
+
+        // The surface is only curved in u, so v doesn't need subdividing.
+        let [us, vs] = approx_nurbs_surface(&surface, tolerance);
+        assert!(us.len() > 2);
+        assert_eq!(vs, vec![Scalar::ZERO, Scalar::ONE]);
+    }
+}
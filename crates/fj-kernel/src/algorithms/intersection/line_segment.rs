@@ -1,4 +1,4 @@
-use fj_math::{Aabb, Line, Scalar, Segment, Vector};
+use fj_math::{Line, Scalar, Segment, Vector};
 
 /// Determine the intersection between a [`Line`] and a [`Segment`]
 pub fn line_segment(
@@ -11,10 +11,8 @@ pub fn line_segment(
     let [a, b] = segment.points();
 
     // Find vector that is orthogonal to `segment`.
-    let n = {
-        let ab = b - a;
-        Vector::from([ab.v, ab.u])
-    };
+    let ab = b - a;
+    let n = Vector::from([-ab.v, ab.u]);
 
     let n_dot_origin = n.dot(&(b - line.origin));
     let n_dot_direction = n.dot(&line.direction);
@@ -33,9 +31,17 @@ pub fn line_segment(
     // defined by `segment`'s points.
     let t = n_dot_origin / n_dot_direction;
 
-    let point_is_on_segment = Aabb::<2>::from_points(segment.points())
-        .contains(line.point_from_line_coords([t]));
-    if !point_is_on_segment {
+    // Check whether that point is actually on the segment, by computing its
+    // coordinate on the segment. This is more robust than checking the
+    // computed point against the segment's bounding box, which is degenerate
+    // for axis-aligned segments.
+    let s = {
+        let d = line.direction;
+        let ao = line.origin - a;
+
+        (ao.u * d.v - ao.v * d.u) / (ab.u * d.v - ab.v * d.u)
+    };
+    if s < Scalar::ZERO || s > Scalar::ONE {
         return None;
     }
 
@@ -103,6 +109,24 @@ mod tests {
             None,
         );
 
+        // hit on a diagonal segment
+        assert_eq!(
+            super::line_segment(
+                &line,
+                &Segment::from_points([[1., -1.], [3., 1.]]),
+            ),
+            Some(LineSegmentIntersection::PointOnLine(Scalar::from_f64(2.))),
+        );
+
+        // diagonal segment that misses the line
+        assert_eq!(
+            super::line_segment(
+                &line,
+                &Segment::from_points([[1., 1.], [3., 3.]]),
+            ),
+            None,
+        );
+
         // segment parallel to line
         assert_eq!(
             super::line_segment(
@@ -7,3 +7,5 @@ pub use self::{
     line_segment::{line_segment, LineSegmentIntersection},
     surface_surface::surface_surface,
 };
+
+pub(crate) use self::surface_surface::extract_plane;
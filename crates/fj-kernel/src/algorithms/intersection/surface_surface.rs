@@ -1,15 +1,29 @@
-use fj_math::{Line, Point, Scalar, Vector};
+use fj_math::{Line, Point, Scalar, Transform, Vector};
 
-use crate::geometry::{Curve, Surface};
+use crate::geometry::{Curve, Surface, SweptCurve};
 
 /// Test intersection between two surfaces
+///
+/// Supports the intersection of two planes, and the intersection of a plane
+/// with a surface that was swept along the normal of the plane. Returns `None`,
+/// if the surfaces don't intersect, or if the intersection of the given kinds
+/// of surfaces is not supported.
 pub fn surface_surface(a: &Surface, b: &Surface) -> Option<Curve<3>> {
+    match (extract_plane(a), extract_plane(b)) {
+        (Some(a), Some(b)) => plane_plane(a, b),
+        (Some(plane), None) => plane_swept_curve(plane, b),
+        (None, Some(plane)) => plane_swept_curve(plane, a),
+        (None, None) => None,
+    }
+}
+
+fn plane_plane(
+    (a_normal, a_distance): (Vector<3>, Scalar),
+    (b_normal, b_distance): (Vector<3>, Scalar),
+) -> Option<Curve<3>> {
     // Algorithm from Real-Time Collision Detection by Christer Ericson. See
     // section 5.4.4, Intersection of Two Planes.
 
-    let (a_normal, a_distance) = extract_plane(a);
-    let (b_normal, b_distance) = extract_plane(b);
-
     let direction = a_normal.cross(&b_normal);
 
     let denom = direction.dot(&direction);
@@ -32,20 +46,86 @@ pub fn surface_surface(a: &Surface, b: &Surface) -> Option<Curve<3>> {
     Some(Curve::Line(Line { origin, direction }))
 }
 
+/// Intersect a plane with a surface that was swept along the plane's normal
+///
+/// The intersection is the curve that the surface was swept from, moved into
+/// the plane. That only works, if that curve lies in a plane that is parallel
+/// to the given one.
+fn plane_swept_curve(
+    (normal, distance): (Vector<3>, Scalar),
+    surface: &Surface,
+) -> Option<Curve<3>> {
+    let SweptCurve { curve, path } = match surface {
+        Surface::SweptCurve(surface) => surface,
+        Surface::Nurbs(_) => return None,
+    };
+
+    // The surface comes out of a sweep, and so does the plane, most of the
+    // time. Their directions are either exactly the same, or not meant to be.
+    // The epsilon value only has to cover inaccuracies in the computation of
+    // the plane's normal.
+    let epsilon = Scalar::from_f64(1e-12);
+
+    if normal.cross(path).magnitude() > path.magnitude() * epsilon {
+        return None;
+    }
+
+    let heights: Vec<_> = match curve {
+        Curve::Circle(circle) => vec![
+            circle.center.coords,
+            circle.center.coords + circle.a,
+            circle.center.coords + circle.b,
+        ],
+        Curve::Ellipse(ellipse) => vec![
+            ellipse.center.coords,
+            ellipse.center.coords + ellipse.a,
+            ellipse.center.coords + ellipse.b,
+        ],
+        Curve::Nurbs(nurbs) => nurbs
+            .control_points
+            .iter()
+            .map(|point| point.coords)
+            .collect(),
+        Curve::Line(_) => {
+            // A line that is swept along the normal of a plane results in a
+            // plane that is perpendicular to it. That case is covered by the
+            // plane-plane intersection.
+            return None;
+        }
+    }
+    .into_iter()
+    .map(|point| normal.dot(&point))
+    .collect();
+
+    let height = heights[0];
+    let scale = Scalar::ONE + height.abs();
+    if heights
+        .iter()
+        .any(|other| (*other - height).abs() > scale * epsilon)
+    {
+        return None;
+    }
+
+    let offset = *path * ((distance - height) / normal.dot(path));
+    Some(curve.clone().transform(&Transform::translation(offset)))
+}
+
 /// Extract a plane in constant-normal form from a `Surface`
 ///
-/// Panics, if the given `Surface` is not a plane.
-fn extract_plane(surface: &Surface) -> (Vector<3>, Scalar) {
-    let Surface::SweptCurve(surface) = surface;
-    let line = match surface.curve {
-        Curve::Line(line) => line,
-        _ => todo!("Only plane-plane intersection is currently supported."),
+/// Returns `None`, if the given `Surface` is not a plane.
+pub(crate) fn extract_plane(surface: &Surface) -> Option<(Vector<3>, Scalar)> {
+    let (line, path) = match surface {
+        Surface::SweptCurve(SweptCurve {
+            curve: Curve::Line(line),
+            path,
+        }) => (line, path),
+        _ => return None,
     };
 
     // Convert plane from parametric form to three-point form.
     let a = line.origin;
     let b = line.origin + line.direction;
-    let c = line.origin + surface.path;
+    let c = line.origin + *path;
 
     // Convert plane from three-point form to constant-normal form. See
     // Real-Time Collision Detection by Christer Ericson, section 3.6, Planes
@@ -53,14 +133,14 @@ fn extract_plane(surface: &Surface) -> (Vector<3>, Scalar) {
     let normal = (b - a).cross(&(c - a)).normalize();
     let distance = normal.dot(&a.coords);
 
-    (normal, distance)
+    Some((normal, distance))
 }
 
 #[cfg(test)]
 mod tests {
-    use fj_math::Transform;
+    use fj_math::{Circle, Point, Transform, Vector};
 
-    use crate::geometry::{Curve, Surface};
+    use crate::geometry::{Curve, Surface, SweptCurve};
 
     use super::surface_surface;
 
@@ -73,10 +153,39 @@ mod tests {
         assert_eq!(
             surface_surface(
                 &xy,
-                &xy.transform(&Transform::translation([0., 0., 1.]))
+                &xy.clone().transform(&Transform::translation([0., 0., 1.]))
             ),
             None,
         );
         assert_eq!(surface_surface(&xy, &xz), Some(Curve::x_axis()));
     }
+
+    #[test]
+    fn plane_swept_curve() {
+        let circle = |z| {
+            Curve::Circle(Circle {
+                center: Point::from([0., 0., z]),
+                a: Vector::from([1., 0., 0.]),
+                b: Vector::from([0., 1., 0.]),
+            })
+        };
+
+        let plane = Surface::xy_plane()
+            .transform(&Transform::translation([0., 0., 1.]));
+        let cylinder = Surface::SweptCurve(SweptCurve {
+            curve: circle(0.),
+            path: Vector::from([0., 0., 2.]),
+        });
+
+        assert_eq!(surface_surface(&plane, &cylinder), Some(circle(1.)));
+        assert_eq!(surface_surface(&cylinder, &plane), Some(circle(1.)));
+
+        // The intersection of a plane with a surface that wasn't swept along
+        // its normal is not supported.
+        let slanted = Surface::SweptCurve(SweptCurve {
+            curve: circle(0.),
+            path: Vector::from([0., 1., 2.]),
+        });
+        assert_eq!(surface_surface(&plane, &slanted), None);
+    }
 }
@@ -4,6 +4,7 @@
 //! on their respective purpose.
 
 mod approx;
+mod section;
 mod sweep;
 mod transform;
 mod triangulation;
@@ -12,7 +13,10 @@ pub mod intersection;
 
 pub use self::{
     approx::{CycleApprox, FaceApprox, InvalidTolerance, Tolerance},
+    section::{section, SectionError},
     sweep::sweep_shape,
     transform::transform_shape,
     triangulation::triangulate,
 };
+
+pub(crate) use self::triangulation::triangulate_face;
@@ -0,0 +1,607 @@
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_math::{Point, PolyChain, Scalar};
+
+use crate::{
+    geometry::{Curve, Surface},
+    shape::{Handle, LocalForm, Shape, ValidationError},
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::{
+    intersection::{extract_plane, surface_surface},
+    triangulation::{triangulate_face, Polygon},
+    CycleApprox, Tolerance,
+};
+
+/// Compute the section of a shape with a plane
+///
+/// Returns a new shape that contains the faces where `plane` cuts through
+/// `shape`. Those faces lie in `plane` and are bounded by closed cycles. A cycle
+/// that lies within another cycle bounds a hole in that cycle's face.
+///
+/// Where [`surface_surface`] supports it, the intersection of `plane` with a
+/// face is exact, and only trimmed against the approximated boundary of the
+/// face. Where it doesn't, the intersection is computed from the triangulation
+/// of the face. In both cases, `tolerance` defines the approximation.
+///
+/// `shape` is expected to be closed, as any shape is that results from a
+/// sweep. Faces of `shape` that lie in `plane` are not part of the section.
+///
+/// # Errors
+///
+/// Returns an error, if the boundary of a face crosses `plane` an odd number
+/// of times, or if the section can't be inserted into a valid shape.
+///
+/// # Panics
+///
+/// Panics, if `plane` is not a plane.
+pub fn section(
+    shape: &Shape,
+    plane: &Surface,
+    tolerance: Tolerance,
+    color: [u8; 4],
+) -> Result<Shape, SectionError> {
+    // Can't panic, unless the caller passed a surface that is not a plane,
+    // which is a bug in the caller.
+    let (normal, distance) =
+        extract_plane(plane).expect("Section must be computed with a plane");
+    let plane_distance = |point: Point<3>| normal.dot(&point.coords) - distance;
+
+    let mut pieces = Vec::new();
+    for face in shape.faces() {
+        face_pieces(&face, plane, &plane_distance, tolerance, &mut pieces)?;
+    }
+
+    let mut target =
+        Shape::new().with_validation_config(shape.validation_config());
+
+    // Pieces that are too short to be bounded by distinct vertices are left
+    // out. Their neighbors are connected directly instead.
+    let min_distance = target.distinct_min_distance();
+    pieces.retain(|piece| {
+        let [a, b] = piece.points();
+        (b - a).magnitude() >= min_distance
+    });
+
+    let cycles = chain_pieces(pieces, &mut target)?;
+
+    // Figure out which cycles bound holes in the faces that other cycles
+    // bound. Each cycle is approximated as a polygon in surface coordinates,
+    // and a cycle is within another, if one of its points is.
+    let polygons: Vec<PolyChain<2>> = cycles
+        .iter()
+        .map(|cycle| {
+            CycleApprox::new(&cycle.get(), tolerance)
+                .points
+                .into_iter()
+                .map(|point| {
+                    plane.point_to_surface_coords(point.canonical()).local()
+                })
+                .collect::<Vec<_>>()
+                .into()
+        })
+        .collect();
+
+    let mut debug_info = DebugInfo::new();
+    let containing: Vec<Vec<usize>> = polygons
+        .iter()
+        .enumerate()
+        .map(|(i, polygon)| {
+            let point = polygon.segments()[0].points()[0];
+
+            (0..polygons.len())
+                .filter(|&j| j != i)
+                .filter(|&j| {
+                    Polygon::new(plane.clone())
+                        .with_exterior(polygons[j].clone())
+                        .contains_point(point, &mut debug_info)
+                })
+                .collect()
+        })
+        .collect();
+
+    // A cycle that lies within an even number of other cycles bounds a face.
+    // Any other cycle bounds a hole in the innermost of the cycles it lies
+    // within.
+    let depth = |i: usize| containing[i].len();
+    let is_exterior = |i: usize| depth(i) % 2 == 0;
+
+    let surface = target.insert(plane.clone())?;
+
+    for exterior in (0..cycles.len()).filter(|&i| is_exterior(i)) {
+        let interiors = (0..cycles.len())
+            .filter(|&i| {
+                !is_exterior(i)
+                    && containing[i].contains(&exterior)
+                    && depth(i) == depth(exterior) + 1
+            })
+            .map(|i| cycles[i].clone());
+
+        target.insert(Face::new(
+            surface.clone(),
+            vec![cycles[exterior].clone()],
+            interiors,
+            color,
+        ))?;
+    }
+
+    Ok(target)
+}
+
+/// Compute the pieces of the section that lie within a face
+fn face_pieces(
+    handle: &Handle<Face>,
+    plane: &Surface,
+    plane_distance: &impl Fn(Point<3>) -> Scalar,
+    tolerance: Tolerance,
+    pieces: &mut Vec<Piece>,
+) -> Result<(), SectionError> {
+    let face = handle.get();
+
+    if let Face::Face(brep) = &face {
+        let surface = brep.surface();
+
+        match surface_surface(plane, &surface) {
+            Some(curve @ (Curve::Line(_) | Curve::Nurbs(_))) => {
+                return trim(handle, curve, plane_distance, tolerance, pieces);
+            }
+            Some(Curve::Circle(_) | Curve::Ellipse(_)) => {
+                // These curves are periodic, which the trimming doesn't
+                // support. Fall through to the approximation.
+            }
+            None => {
+                if extract_plane(&surface).is_some() {
+                    // The face lies in a plane that is parallel to the section
+                    // plane. It doesn't contribute to the section.
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    let mut mesh = Mesh::new();
+    triangulate_face(&face, tolerance, &mut DebugInfo::new(), &mut mesh);
+
+    for triangle in mesh.triangles() {
+        let [a, b, c] = triangle.points;
+
+        let points: Vec<_> = [[a, b], [b, c], [c, a]]
+            .into_iter()
+            .filter_map(|segment| crossing(segment, plane_distance))
+            .collect();
+
+        if let [a, b] = points[..] {
+            pieces.push(Piece::Line([a, b]));
+        }
+    }
+
+    Ok(())
+}
+
+/// Trim the intersection curve of a face against the face's boundary
+///
+/// The curve must not be periodic.
+fn trim(
+    handle: &Handle<Face>,
+    curve: Curve<3>,
+    plane_distance: &impl Fn(Point<3>) -> Scalar,
+    tolerance: Tolerance,
+    pieces: &mut Vec<Piece>,
+) -> Result<(), SectionError> {
+    let face = handle.get();
+    let mut crossings = Vec::new();
+
+    // The edges of a cycle are not necessarily in order, so each edge is
+    // approximated on its own.
+    let edges = face.all_cycles().flat_map(|cycle| cycle.edges);
+    for edge in edges {
+        let approx = CycleApprox::new(&Cycle { edges: vec![edge] }, tolerance);
+
+        for segment in approx.segments() {
+            if let Some(point) = crossing(segment.points(), plane_distance) {
+                let t = curve.point_to_curve_coords(point).local().t;
+                crossings.push((t, point));
+            }
+        }
+    }
+
+    // Going along the curve, it enters and leaves the face alternately. That
+    // only works out, if the boundary of the face is closed.
+    if crossings.len() % 2 != 0 {
+        return Err(SectionError::OddCrossings {
+            face: handle.clone(),
+            crossings: crossings.len(),
+        });
+    }
+    crossings.sort_by_key(|&(t, _)| t);
+
+    for crossings in crossings.chunks_exact(2) {
+        let [(a_t, a), (b_t, b)] = [crossings[0], crossings[1]];
+
+        let piece = match &curve {
+            Curve::Nurbs(nurbs) => {
+                // Where the face's boundary crosses the plane at the start or
+                // end of the curve, use the exact ends of the curve. That's
+                // where the vertices of neighboring pieces are going to be.
+                let [start, end] = nurbs.domain();
+                let epsilon = (end - start) * Scalar::from_f64(1e-9);
+                let snap = |t: Scalar| {
+                    if (t - start).abs() <= epsilon {
+                        start
+                    } else if (t - end).abs() <= epsilon {
+                        end
+                    } else {
+                        t
+                    }
+                };
+
+                Piece::Curve {
+                    curve: curve.clone(),
+                    range: [snap(a_t), snap(b_t)],
+                }
+            }
+            _ => Piece::Line([a, b]),
+        };
+
+        pieces.push(piece);
+    }
+
+    Ok(())
+}
+
+/// Compute where a line segment crosses the plane
+///
+/// Points that lie in the plane are treated as if they were in front of it.
+/// That way, a boundary that touches the plane in a single point crosses it
+/// either twice there, or not at all.
+fn crossing(
+    [a, b]: [Point<3>; 2],
+    plane_distance: &impl Fn(Point<3>) -> Scalar,
+) -> Option<Point<3>> {
+    let [a_distance, b_distance] = [a, b].map(plane_distance);
+
+    if (a_distance >= Scalar::ZERO) == (b_distance >= Scalar::ZERO) {
+        return None;
+    }
+
+    Some(a + (b - a) * (a_distance / (a_distance - b_distance)))
+}
+
+/// Chain the pieces of the section into cycles, and insert them into the shape
+fn chain_pieces(
+    mut pieces: Vec<Piece>,
+    shape: &mut Shape,
+) -> Result<Vec<Handle<Cycle<3>>>, ValidationError> {
+    let mut cycles = Vec::new();
+
+    while let Some(first) = pieces.pop() {
+        let mut chain = vec![first];
+
+        // Add the piece that starts closest to the end of the chain, until the
+        // start of the chain is closer than any piece.
+        loop {
+            let [start, _] = chain[0].points();
+            let [_, end] = chain[chain.len() - 1].points();
+
+            let next = pieces
+                .iter()
+                .enumerate()
+                .flat_map(|(i, piece)| {
+                    let [a, b] = piece.points();
+                    [(i, false, a), (i, true, b)]
+                })
+                .map(|(i, reverse, point)| {
+                    (i, reverse, (point - end).magnitude())
+                })
+                .min_by_key(|&(_, _, distance)| distance);
+
+            match next {
+                Some((i, reverse, distance))
+                    if distance < (start - end).magnitude() =>
+                {
+                    let piece = pieces.swap_remove(i);
+                    chain.push(if reverse { piece.reverse() } else { piece });
+                }
+                _ => break,
+            }
+        }
+
+        // Two lines can't bound anything.
+        let is_degenerate = chain.len() < 2
+            || chain.len() == 2
+                && chain.iter().all(|piece| matches!(piece, Piece::Line(_)));
+        if is_degenerate {
+            continue;
+        }
+
+        cycles.push(insert_cycle(&chain, shape)?);
+    }
+
+    Ok(cycles)
+}
+
+/// Insert a chain of pieces into the shape, as a cycle
+fn insert_cycle(
+    chain: &[Piece],
+    shape: &mut Shape,
+) -> Result<Handle<Cycle<3>>, ValidationError> {
+    let n = chain.len();
+
+    // Neighboring pieces end and start in approximately the same point, and
+    // share a vertex there. The ends of curves are exact, so they take
+    // precedence. Lines are constructed from their vertices, so they always
+    // fit.
+    let mut vertices = Vec::new();
+    for (i, piece) in chain.iter().enumerate() {
+        let previous = &chain[(i + n - 1) % n];
+
+        let point = match (previous, piece) {
+            (_, Piece::Curve { .. }) | (Piece::Line(_), Piece::Line(_)) => {
+                piece.points()[0]
+            }
+            (Piece::Curve { .. }, Piece::Line(_)) => previous.points()[1],
+        };
+
+        vertices.push(Vertex::builder(shape).build_from_point(point)?);
+    }
+
+    let mut edges = Vec::new();
+    for (i, piece) in chain.iter().enumerate() {
+        let [a, b] = [vertices[i].clone(), vertices[(i + 1) % n].clone()];
+
+        let edge = match piece {
+            Piece::Line(_) => {
+                Edge::builder(shape).build_line_segment_from_vertices([a, b])?
+            }
+            Piece::Curve { curve, range } => {
+                let [a_t, b_t] = *range;
+
+                let curve = shape.get_handle_or_insert(curve.clone())?;
+                shape.insert(Edge::new(
+                    curve,
+                    Some([
+                        LocalForm::new(Point::from([a_t]), a),
+                        LocalForm::new(Point::from([b_t]), b),
+                    ]),
+                ))?
+            }
+        };
+
+        edges.push(edge);
+    }
+
+    shape.insert(Cycle::new(edges))
+}
+
+/// An error that can occur when computing a section
+#[derive(Debug, thiserror::Error)]
+pub enum SectionError {
+    /// The boundary of a face crosses the section plane an odd number of times
+    ///
+    /// A closed boundary crosses the plane as often in one direction, as it
+    /// does in the other. Which parts of the intersection lie within the face
+    /// can't be determined otherwise.
+    #[error("Face boundary crosses section plane {crossings} times")]
+    OddCrossings {
+        /// The face whose boundary crosses the plane
+        face: Handle<Face>,
+
+        /// The number of crossings
+        crossings: usize,
+    },
+
+    /// The section is not a valid shape
+    #[error("Section is not a valid shape")]
+    Validation(#[from] ValidationError),
+}
+
+/// A piece of a section, where the section plane cuts through a face
+enum Piece {
+    /// A line segment between two points
+    Line([Point<3>; 2]),
+
+    /// A segment of a curve, between two points in curve coordinates
+    Curve { curve: Curve<3>, range: [Scalar; 2] },
+}
+
+impl Piece {
+    fn points(&self) -> [Point<3>; 2] {
+        match self {
+            Self::Line(points) => *points,
+            Self::Curve { curve, range } => {
+                range.map(|t| curve.point_from_curve_coords([t]))
+            }
+        }
+    }
+
+    fn reverse(self) -> Self {
+        match self {
+            Self::Line([a, b]) => Self::Line([b, a]),
+            Self::Curve {
+                curve,
+                range: [a, b],
+            } => Self::Curve {
+                curve,
+                range: [b, a],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{NurbsCurve, Point, Scalar, Transform, Vector};
+
+    use crate::{
+        algorithms::{sweep_shape, Tolerance},
+        geometry::{Curve, Surface},
+        shape::Shape,
+        topology::{Cycle, Edge, Face},
+    };
+
+    use super::{section, SectionError};
+
+    #[test]
+    fn section_polygon() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([
+                [0., 0., 0.],
+                [4., 0., 0.],
+                [4., 4., 0.],
+                [0., 4., 0.],
+            ])
+            .with_interior_polygon([
+                [1., 1., 0.],
+                [1., 3., 0.],
+                [3., 3., 0.],
+                [3., 1., 0.],
+            ])
+            .build()?;
+
+        let section = section_at(shape, 0.5)?;
+
+        let faces: Vec<_> = section.faces().map(|face| face.get()).collect();
+        assert_eq!(faces.len(), 1);
+
+        let exteriors: Vec<_> = faces[0].exteriors().collect();
+        let interiors: Vec<_> = faces[0].interiors().collect();
+        assert_eq!(exteriors.len(), 1);
+        assert_eq!(interiors.len(), 1);
+
+        assert_eq!(
+            corners(&exteriors[0]),
+            [[0., 0.], [0., 4.], [4., 0.], [4., 4.]],
+        );
+        assert_eq!(
+            corners(&interiors[0]),
+            [[1., 1.], [1., 3.], [3., 1.], [3., 3.]],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn section_outside() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .build()?;
+
+        let section = section_at(shape, 2.)?;
+        assert_eq!(section.faces().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn section_curve() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let curve = NurbsCurve::from_control_points(
+            2,
+            [[2., 0., 0.], [1., 2., 0.], [0., 0., 0.]],
+        );
+        let arc = Edge::builder(&mut shape).build_nurbs(curve.clone())?;
+        let line = Edge::builder(&mut shape)
+            .build_line_segment_from_points([[0., 0., 0.], [2., 0., 0.]])?;
+        let cycle = shape.insert(Cycle::new([arc, line]))?;
+        let surface = shape.insert(Surface::xy_plane())?;
+        shape.insert(Face::new(surface, [cycle], [], [255, 0, 0, 255]))?;
+
+        let section = section_at(shape, 0.5)?;
+
+        // The section of the curved side face is exact.
+        let expected = Curve::Nurbs(
+            Transform::translation([0., 0., 0.5]).transform_nurbs_curve(&curve),
+        );
+        let edges: Vec<_> = section.edges().map(|edge| edge.get()).collect();
+        assert_eq!(edges.len(), 2);
+        assert!(edges.iter().any(|edge| edge.curve() == expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn section_approximated() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        // Sweeping a circle results in a side face that is made of triangles.
+        let circle = Edge::builder(&mut shape).build_circle(Scalar::ONE)?;
+        let cycle = shape.insert(Cycle::new([circle]))?;
+        let surface = shape.insert(Surface::xy_plane())?;
+        shape.insert(Face::new(surface, [cycle], [], [255, 0, 0, 255]))?;
+
+        let section = section_at(shape, 0.5)?;
+        assert_eq!(section.faces().count(), 1);
+
+        for vertex in section.vertices() {
+            let point = vertex.get().point();
+            let [x, y, z] = point.coords.components.map(Scalar::into_f64);
+
+            // The points lie on the triangles that approximate the side face,
+            // which are within the tolerance of the actual circle.
+            assert_eq!(z, 0.5);
+            assert!((x.hypot(y) - 1.).abs() <= 0.1);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn section_open_boundary() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        // The boundary of the face is not closed, so the plane only crosses it
+        // once.
+        let a = Edge::builder(&mut shape)
+            .build_line_segment_from_points([[0., 0., 0.], [1., 0., 0.]])?;
+        let b = Edge::builder(&mut shape)
+            .build_line_segment_from_points([[1., 0., 0.], [1., 0., 1.]])?;
+        let cycle = shape.insert(Cycle::new([a, b]))?;
+        let surface = shape.insert(Surface::xz_plane())?;
+        let face =
+            shape.insert(Face::new(surface, [cycle], [], [255, 0, 0, 255]))?;
+
+        let tolerance = Tolerance::from_scalar(0.1)?;
+        let plane = Surface::xy_plane()
+            .transform(&Transform::translation([0., 0., 0.5]));
+
+        let result = section(&shape, &plane, tolerance, [255, 0, 0, 255]);
+        assert!(matches!(
+            result,
+            Err(SectionError::OddCrossings { face: f, crossings: 1 })
+                if f == face
+        ));
+
+        Ok(())
+    }
+
+    fn section_at(shape: Shape, z: f64) -> anyhow::Result<Shape> {
+        let tolerance = Tolerance::from_scalar(0.1)?;
+
+        let swept = sweep_shape(
+            shape,
+            Vector::from([0., 0., 1.]),
+            tolerance,
+            [255, 0, 0, 255],
+        )?;
+        let plane =
+            Surface::xy_plane().transform(&Transform::translation([0., 0., z]));
+
+        Ok(section(&swept, &plane, tolerance, [255, 0, 0, 255])?)
+    }
+
+    /// The corners of a polygon, sorted and projected into the xy plane
+    fn corners(cycle: &Cycle<3>) -> Vec<[f64; 2]> {
+        let mut corners: Vec<_> = cycle
+            .edges()
+            .flat_map(|edge| edge.vertices())
+            .map(|[vertex, _]| vertex.point())
+            .map(|point: Point<3>| [point.x.into_f64(), point.y.into_f64()])
+            .collect();
+
+        corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        corners
+    }
+}
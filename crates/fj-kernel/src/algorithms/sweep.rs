@@ -1,11 +1,11 @@
 use std::collections::HashMap;
 
-use fj_math::{Transform, Triangle, Vector};
+use fj_math::{Circle, Ellipse, Scalar, Transform, Triangle, Vector};
 
 use crate::{
-    geometry::{Surface, SweptCurve},
+    geometry::{Curve, Surface, SweptCurve},
     shape::{Shape, ValidationError},
-    topology::{Cycle, Edge, Face},
+    topology::{Cycle, Edge, Face, Shell, Solid},
 };
 
 use super::{transform_shape, CycleApprox, Tolerance};
@@ -22,26 +22,37 @@ pub fn sweep_shape(
     let (mut bottom, source_to_bottom) = source.clone_shape();
     let (mut top, source_to_top) = source.clone_shape();
     let sweep_along_negative_direction =
-        path.dot(&Vector::from([0., 0., 1.])) < fj_math::Scalar::ZERO;
+        path.dot(&Vector::from([0., 0., 1.])) < Scalar::ZERO;
 
     if sweep_along_negative_direction {
         top.update()
-            .update_all(|surface: &mut Surface| *surface = surface.reverse())
+            .update_all(|surface: &mut Surface| {
+                *surface = surface.clone().reverse()
+            })
             .validate()?;
     } else {
         bottom
             .update()
-            .update_all(|surface: &mut Surface| *surface = surface.reverse())
+            .update_all(|surface: &mut Surface| {
+                *surface = surface.clone().reverse()
+            })
             .validate()?;
     }
     transform_shape(&mut top, &translation)?;
 
-    let mut target = Shape::new();
-    target.merge_shape(&bottom)?;
-    target.merge_shape(&top)?;
+    let mut target =
+        Shape::new().with_validation_config(source.validation_config());
+    let bottom_to_target = target.merge_shape(&bottom)?;
+    let top_to_target = target.merge_shape(&top)?;
 
-    // Create the side faces.
+    // Create the side faces. They are collected per source cycle, so we can
+    // assemble the shells later.
+    let mut side_faces = HashMap::new();
     for cycle_source in source.cycles() {
+        let faces_of_cycle = side_faces
+            .entry(cycle_source.clone())
+            .or_insert_with(Vec::new);
+
         if cycle_source.get().edges.len() == 1 {
             // If there's only one edge in the cycle, it must be a continuous
             // edge that connects to itself. By sweeping that, we create a
@@ -68,13 +79,23 @@ pub fn sweep_shape(
                 quads.push([v0, v1, v2, v3]);
             }
 
+            // Sweeping along the negative direction turns the side face
+            // inside out, same as the surfaces of the other side faces.
             let mut side_face: Vec<(Triangle<3>, _)> = Vec::new();
             for [v0, v1, v2, v3] in quads {
-                side_face.push(([v0, v1, v2].into(), color));
-                side_face.push(([v0, v2, v3].into(), color));
+                let triangles = if sweep_along_negative_direction {
+                    [[v0, v2, v1], [v0, v3, v2]]
+                } else {
+                    [[v0, v1, v2], [v0, v2, v3]]
+                };
+
+                for triangle in triangles {
+                    side_face.push((triangle.into(), color));
+                }
             }
 
-            target.insert(Face::Triangles(side_face))?;
+            let face = target.insert(Face::Triangles(side_face))?;
+            faces_of_cycle.push(face);
         } else {
             // If there's no continuous edge, we can create the non-
             // continuous faces using boundary representation.
@@ -152,7 +173,7 @@ pub fn sweep_shape(
                     .clone();
 
                 let mut surface = Surface::SweptCurve(SweptCurve {
-                    curve: bottom_edge.get().curve(),
+                    curve: side_face_curve(&bottom_edge.get()),
                     path,
                 });
                 if sweep_along_negative_direction {
@@ -167,22 +188,102 @@ pub fn sweep_shape(
                     side_edge_b,
                 ]))?;
 
-                target.insert(Face::new(
+                let face = target.insert(Face::new(
                     surface,
                     vec![cycle],
                     Vec::new(),
                     color,
                 ))?;
+                faces_of_cycle.push(face);
             }
         }
     }
 
+    // Every source face becomes a solid, bounded by a single shell. That shell
+    // consists of the bottom and top faces, and the side faces created from
+    // the cycles of the source face.
+    for face_source in source.faces() {
+        // Can't panic, unless this isn't actually a face from `source`, we're
+        // using the wrong mappings, or the mappings don't contain this face.
+        //
+        // All of these would be a bug.
+        let bottom_face = source_to_bottom
+            .faces()
+            .get(&face_source)
+            .and_then(|face| bottom_to_target.faces().get(face))
+            .expect("Couldn't find face in mapping")
+            .clone();
+        let top_face = source_to_top
+            .faces()
+            .get(&face_source)
+            .and_then(|face| top_to_target.faces().get(face))
+            .expect("Couldn't find face in mapping")
+            .clone();
+
+        let mut faces = vec![bottom_face, top_face];
+        if let Face::Face(face_source) = face_source.get() {
+            for cycle_source in face_source
+                .exteriors
+                .as_handle()
+                .chain(face_source.interiors.as_handle())
+            {
+                if let Some(side_faces) = side_faces.get(&cycle_source) {
+                    faces.extend(side_faces.iter().cloned());
+                }
+            }
+        }
+
+        let shell = target.insert(Shell::new(faces))?;
+        target.insert(Solid::new(shell, Vec::new()))?;
+    }
+
     Ok(target)
 }
 
+/// Compute the curve that the side face of an edge is swept from
+///
+/// Points on circles and ellipses are converted into curve coordinates between
+/// `0` and `2π`. If the edge crossed the point where those wrap around, so would
+/// the surface coordinates of the side face. To prevent that, the curve is
+/// rotated, such that the middle of the edge ends up at `π`.
+fn side_face_curve(edge: &Edge<3>) -> Curve<3> {
+    let curve = edge.curve();
+
+    let middle = match &edge.vertices {
+        Some(vertices) => {
+            let [a, b] = vertices.clone().map(|vertex| vertex.local().t);
+            (a + b) / 2.
+        }
+        None => return curve,
+    };
+
+    match curve {
+        Curve::Circle(circle) => {
+            let [a, b] = [middle, middle + Scalar::PI / 2.]
+                .map(|t| circle.center - circle.point_from_circle_coords([t]));
+            Curve::Circle(Circle {
+                center: circle.center,
+                a,
+                b,
+            })
+        }
+        Curve::Ellipse(ellipse) => {
+            let [a, b] = [middle, middle + Scalar::PI / 2.].map(|t| {
+                ellipse.center - ellipse.point_from_ellipse_coords([t])
+            });
+            Curve::Ellipse(Ellipse {
+                center: ellipse.center,
+                a,
+                b,
+            })
+        }
+        curve => curve,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use fj_math::{Point, Scalar, Vector};
+    use fj_math::{Circle, Point, Scalar, Vector};
 
     use crate::{
         algorithms::Tolerance,
@@ -191,7 +292,7 @@ mod tests {
         topology::{Cycle, Edge, Face},
     };
 
-    use super::sweep_shape;
+    use super::{side_face_curve, sweep_shape};
 
     #[test]
     fn sweep() -> anyhow::Result<()> {
@@ -234,6 +335,43 @@ mod tests {
         // Side faces are not tested, as those use triangle representation. The
         // plan is to start testing them, as they are transitioned to b-rep.
 
+        // The swept face becomes a solid, bounded by the bottom face, the top
+        // face, and a side face for each edge.
+        let solids: Vec<_> = swept.solids().collect();
+        assert_eq!(solids.len(), 1);
+
+        let shell = solids[0].get().exterior();
+        assert_eq!(shell.faces.len(), 5);
+        assert!(shell.faces().any(|face| face == bottom_face));
+        assert!(shell.faces().any(|face| face == top_face));
+
+        Ok(())
+    }
+
+    #[test]
+    fn side_face_curve_of_arc() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        // This arc crosses the point where circle coordinates wrap around.
+        let circle = Circle {
+            center: Point::origin(),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+        };
+        let edge = Edge::builder(&mut shape)
+            .build_arc_from_points(circle, [[0., -1., 0.], [0., 1., 0.]])?;
+
+        let curve = side_face_curve(&edge.get());
+
+        // The start, middle, and end of the arc, and where they must end up.
+        let points = [[0., -1., 0.], [1., 0., 0.], [0., 1., 0.]];
+        let expected = [0.5, 1., 1.5].map(|t| Scalar::PI * t);
+
+        for (point, expected) in points.into_iter().zip(expected) {
+            let t = curve.point_to_curve_coords(point).local().t;
+            assert!((t - expected).abs() < Scalar::from(1e-12));
+        }
+
         Ok(())
     }
 
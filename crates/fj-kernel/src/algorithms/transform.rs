@@ -1,31 +1,62 @@
-use fj_math::Transform;
+use fj_math::{Transform, Triangle};
 
 use crate::{
     geometry::{Curve, Surface},
-    shape::{Shape, ValidationError},
-    topology::Face,
+    shape::{LocalForm, Shape, ValidationError},
+    topology::{Cycle, Edge, Face},
 };
 
 /// Transform the geometry of the shape
 ///
 /// Since the topological types refer to geometry, and don't contain any
 /// geometry themselves, this transforms the whole shape.
+///
+/// If the transform reverses orientation (i.e. it contains a reflection), all
+/// surfaces, edges, and cycles are reversed too. Otherwise, the faces of the
+/// transformed shape would point inwards.
 pub fn transform_shape(
     shape: &mut Shape,
     transform: &Transform,
 ) -> Result<(), ValidationError> {
+    let reverse = transform.reverses_orientation();
+
     shape
         .update()
         .update_all(|point| *point = transform.transform_point(point))
-        .update_all(|curve: &mut Curve<3>| *curve = curve.transform(transform))
+        .update_all(|curve: &mut Curve<3>| {
+            *curve = curve.clone().transform(transform);
+
+            if reverse {
+                *curve = curve.clone().reverse();
+            }
+        })
         .update_all(|surface: &mut Surface| {
-            *surface = surface.transform(transform)
+            *surface = surface.clone().transform(transform);
+
+            if reverse {
+                *surface = surface.clone().reverse();
+            }
+        })
+        .update_all(|edge: &mut Edge<3>| {
+            if reverse {
+                reverse_edge(edge);
+            }
+        })
+        .update_all(|cycle: &mut Cycle<3>| {
+            if reverse {
+                reverse_cycle(cycle);
+            }
         })
         .update_all(|mut face: &mut Face| {
             use std::ops::DerefMut as _;
             if let Face::Triangles(triangles) = face.deref_mut() {
                 for (triangle, _) in triangles {
                     *triangle = transform.transform_triangle(triangle);
+
+                    if reverse {
+                        let [a, b, c] = triangle.points();
+                        *triangle = Triangle::from([a, c, b]);
+                    }
                 }
             }
         })
@@ -33,3 +64,83 @@ pub fn transform_shape(
 
     Ok(())
 }
+
+/// Reverse the direction of an edge
+///
+/// Expects the edge's curve to have been reversed already. Reversing a curve
+/// negates its curve coordinates, so the local forms of the vertices need to be
+/// updated accordingly.
+fn reverse_edge(edge: &mut Edge<3>) {
+    if let Some(vertices) = &mut edge.vertices {
+        vertices.reverse();
+
+        for vertex in vertices {
+            *vertex = LocalForm::new(-*vertex.local(), vertex.canonical());
+        }
+    }
+}
+
+/// Reverse the direction of a cycle
+///
+/// Expects the cycle's edges to have been reversed already.
+fn reverse_cycle(cycle: &mut Cycle<3>) {
+    cycle.edges.reverse();
+
+    for edge in &mut cycle.edges {
+        // The local form is a copy of the canonical edge, which has been
+        // reversed in the meantime.
+        *edge = LocalForm::canonical_only(edge.canonical());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Scalar, Transform, Vector};
+
+    use crate::{geometry::Surface, shape::Shape, topology::Face};
+
+    use super::transform_shape;
+
+    #[test]
+    fn reflection() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let face = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .build()?;
+
+        transform_shape(&mut shape, &Transform::reflection([1., 0., 0.]))?;
+        let face = face.get();
+
+        // The face lies in the xy-plane. Reflecting it about the yz-plane must
+        // not change the direction it is facing.
+        let surface = face.surface();
+        let normal = surface
+            .vector_from_surface_coords([0., 0.], [1., 0.])
+            .cross(&surface.vector_from_surface_coords([0., 0.], [0., 1.]));
+        assert_eq!(normal.normalize(), Vector::unit_z());
+
+        // The exterior cycle must still be counter-clockwise, when viewed from
+        // the direction the face is facing.
+        let mut area = Scalar::ZERO;
+        for edge in face.exteriors().next().unwrap().edges() {
+            let curve = edge.curve();
+            let [a, b] = edge.vertices.unwrap().map(|vertex| {
+                let point = vertex.canonical().get().point();
+
+                // The local forms of the vertices must still be valid.
+                assert_eq!(
+                    curve.point_from_curve_coords(*vertex.local()),
+                    point
+                );
+
+                point
+            });
+
+            area += a.x * b.y - b.x * a.y;
+        }
+        assert!(area > Scalar::ZERO);
+
+        Ok(())
+    }
+}
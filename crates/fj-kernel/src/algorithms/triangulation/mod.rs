@@ -4,82 +4,123 @@ mod ray;
 
 use fj_interop::{debug::DebugInfo, mesh::Mesh};
 use fj_math::Point;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::{shape::Shape, topology::Face};
 
-use self::polygon::Polygon;
+pub(super) use self::polygon::Polygon;
 
 use super::{FaceApprox, Tolerance};
 
 /// Triangulate a shape
+///
+/// Faces are triangulated independently of each other. If the `parallel`
+/// feature is enabled, they are triangulated in parallel. The resulting mesh is
+/// the same either way.
+///
+/// The mesh is deterministic. The faces are triangulated in the order in which
+/// they were inserted into the shape, and triangulating the same face always
+/// results in the same triangles, in the same order.
 pub fn triangulate(
     shape: Shape,
     tolerance: Tolerance,
     debug_info: &mut DebugInfo,
 ) -> Mesh<Point<3>> {
+    let faces: Vec<_> = shape.faces().values().collect();
+
+    #[cfg(feature = "parallel")]
+    let faces = faces.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let faces = faces.iter();
+
+    let face_meshes: Vec<_> = faces
+        .map(|face| {
+            let mut mesh = Mesh::new();
+            let mut debug_info = DebugInfo::new();
+
+            triangulate_face(face, tolerance, &mut debug_info, &mut mesh);
+
+            (mesh, debug_info)
+        })
+        .collect();
+
+    // The face meshes are in the same order as the faces, so assembling them
+    // here doesn't depend on the order in which they were triangulated.
     let mut mesh = Mesh::new();
+    for (face_mesh, face_debug_info) in face_meshes {
+        for triangle in face_mesh.triangles() {
+            mesh.push_triangle(triangle.points, triangle.color);
+        }
+
+        debug_info
+            .triangle_edge_checks
+            .extend(face_debug_info.triangle_edge_checks);
+    }
 
-    for face in shape.faces() {
-        let face = face.get();
-        match &face {
-            Face::Face(brep) => {
-                let surface = brep.surface.get();
-                let approx = FaceApprox::new(&face, tolerance);
-
-                let points: Vec<_> = approx
-                    .points
-                    .into_iter()
-                    .map(|vertex| {
+    mesh
+}
+
+/// Triangulate a single face, adding the triangles to the mesh
+pub(crate) fn triangulate_face(
+    face: &Face,
+    tolerance: Tolerance,
+    debug_info: &mut DebugInfo,
+    mesh: &mut Mesh<Point<3>>,
+) {
+    match face {
+        Face::Face(brep) => {
+            let surface = brep.surface.get();
+            let approx = FaceApprox::new(face, tolerance);
+
+            let points: Vec<_> = approx
+                .points
+                .into_iter()
+                .map(|vertex| {
+                    // Can't panic, unless the approximation wrongfully
+                    // generates points that are not in the surface.
+                    surface.point_to_surface_coords(vertex.canonical())
+                })
+                .collect();
+            let face_as_polygon = Polygon::new(surface.clone())
+                .with_exterior(approx.exterior.points.into_iter().map(
+                    |point| {
+                        // Can't panic, unless the approximation wrongfully
+                        // generates points that are not in the surface.
+                        surface
+                            .point_to_surface_coords(point.canonical())
+                            .local()
+                    },
+                ))
+                .with_interiors(approx.interiors.into_iter().map(|interior| {
+                    interior.points.into_iter().map(|point| {
                         // Can't panic, unless the approximation wrongfully
                         // generates points that are not in the surface.
-                        surface.point_to_surface_coords(vertex.canonical())
+                        surface
+                            .point_to_surface_coords(point.canonical())
+                            .local()
                     })
-                    .collect();
-                let face_as_polygon = Polygon::new(surface)
-                    .with_exterior(approx.exterior.points.into_iter().map(
-                        |point| {
-                            // Can't panic, unless the approximation wrongfully
-                            // generates points that are not in the surface.
-                            surface
-                                .point_to_surface_coords(point.canonical())
-                                .local()
-                        },
-                    ))
-                    .with_interiors(approx.interiors.into_iter().map(
-                        |interior| {
-                            interior.points.into_iter().map(|point| {
-                                // Can't panic, unless the approximation
-                                // wrongfully generates points that are not in
-                                // the surface.
-                                surface
-                                    .point_to_surface_coords(point.canonical())
-                                    .local()
-                            })
-                        },
-                    ));
-
-                let mut triangles = delaunay::triangulate(points);
-                triangles.retain(|triangle| {
-                    face_as_polygon.contains_triangle(
-                        triangle.map(|point| point.local()),
-                        debug_info,
-                    )
-                });
-
-                for triangle in triangles {
-                    let points = triangle.map(|point| point.canonical());
-                    mesh.push_triangle(points, brep.color);
-                }
+                }));
+
+            let mut triangles = delaunay::triangulate(points);
+            triangles.retain(|triangle| {
+                face_as_polygon.contains_triangle(
+                    triangle.map(|point| point.local()),
+                    debug_info,
+                )
+            });
+
+            for triangle in triangles {
+                let points = triangle.map(|point| point.canonical());
+                mesh.push_triangle(points, brep.color);
             }
-            Face::Triangles(triangles) => {
-                for &(triangle, color) in triangles {
-                    mesh.push_triangle(triangle.points(), color);
-                }
+        }
+        Face::Triangles(triangles) => {
+            for &(triangle, color) in triangles {
+                mesh.push_triangle(triangle.points(), color);
             }
         }
     }
-
-    mesh
 }
 
 #[cfg(test)]
@@ -148,6 +189,43 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn thread_count_does_not_affect_result() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        for i in 0..16 {
+            let x = f64::from(i) * 3.;
+
+            Face::builder(Surface::xy_plane(), &mut shape)
+                .with_exterior_polygon([
+                    [x, 0., 0.],
+                    [x + 2., 0., 0.],
+                    [x + 2., 2., 0.],
+                    [x, 1., 0.],
+                ])
+                .build()?;
+        }
+
+        let triangulate_with_threads = |num_threads| -> anyhow::Result<_> {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()?;
+            let mesh = pool.install(|| triangulate(shape.clone()))?;
+
+            let triangles: Vec<_> =
+                mesh.triangles().map(|triangle| triangle.points).collect();
+
+            Ok(triangles)
+        };
+
+        let sequential = triangulate_with_threads(1)?;
+        assert_eq!(sequential.len(), 32);
+        assert_eq!(sequential, triangulate_with_threads(4)?);
+
+        Ok(())
+    }
+
     fn triangulate(shape: Shape) -> anyhow::Result<Mesh<Point<3>>> {
         let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
 
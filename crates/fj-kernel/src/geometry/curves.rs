@@ -1,6 +1,8 @@
 use std::fmt;
 
-use fj_math::{Circle, Line, Point, Transform, Vector};
+use fj_math::{
+    Circle, Ellipse, Line, NurbsCurve, Point, Scalar, Transform, Vector,
+};
 
 use crate::geometry;
 
@@ -17,13 +19,19 @@ use crate::geometry;
 /// The `D` parameter defines the dimensions in which the curve is defined.
 /// Typically, only `2` or `3` make sense, which means the curve is defined on
 /// a surface or in a space, respectively.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum Curve<const D: usize> {
     /// A circle
     Circle(Circle<D>),
 
+    /// An ellipse
+    Ellipse(Ellipse<D>),
+
     /// A line
     Line(Line<D>),
+
+    /// A NURBS curve
+    Nurbs(NurbsCurve<D>),
 }
 
 impl Curve<3> {
@@ -52,13 +60,32 @@ impl Curve<3> {
     }
 
     /// Create a new instance that is transformed by `transform`
+    ///
+    /// A circle that is transformed by a non-uniform scaling (or a shear) is no
+    /// longer a circle. In that case, an ellipse is returned.
     #[must_use]
     pub fn transform(self, transform: &Transform) -> Self {
         match self {
             Self::Circle(curve) => {
-                Self::Circle(transform.transform_circle(&curve))
+                let circle = transform.transform_circle(&curve);
+
+                if is_circle(&circle) {
+                    Self::Circle(circle)
+                } else {
+                    Self::Ellipse(Ellipse {
+                        center: circle.center,
+                        a: circle.a,
+                        b: circle.b,
+                    })
+                }
+            }
+            Self::Ellipse(curve) => {
+                Self::Ellipse(transform.transform_ellipse(&curve))
             }
             Self::Line(curve) => Self::Line(transform.transform_line(&curve)),
+            Self::Nurbs(curve) => {
+                Self::Nurbs(transform.transform_nurbs_curve(&curve))
+            }
         }
     }
 }
@@ -68,7 +95,12 @@ impl<const D: usize> Curve<D> {
     pub fn origin(&self) -> Point<D> {
         match self {
             Self::Circle(curve) => curve.center,
+            Self::Ellipse(curve) => curve.center,
             Self::Line(curve) => curve.origin,
+            Self::Nurbs(curve) => {
+                let [start, _] = curve.domain();
+                curve.point_from_curve_coords([start])
+            }
         }
     }
 
@@ -77,7 +109,9 @@ impl<const D: usize> Curve<D> {
     pub fn reverse(self) -> Self {
         match self {
             Self::Circle(curve) => Self::Circle(curve.reverse()),
+            Self::Ellipse(curve) => Self::Ellipse(curve.reverse()),
             Self::Line(curve) => Self::Line(curve.reverse()),
+            Self::Nurbs(curve) => Self::Nurbs(curve.reverse()),
         }
     }
 
@@ -100,7 +134,11 @@ impl<const D: usize> Curve<D> {
             Self::Circle(curve) => {
                 curve.point_to_circle_coords(point_canonical)
             }
+            Self::Ellipse(curve) => {
+                curve.point_to_ellipse_coords(point_canonical)
+            }
             Self::Line(curve) => curve.point_to_line_coords(point_canonical),
+            Self::Nurbs(curve) => curve.point_to_curve_coords(point_canonical),
         };
 
         geometry::Point::new(point_local, point_canonical)
@@ -113,7 +151,9 @@ impl<const D: usize> Curve<D> {
     ) -> Point<D> {
         match self {
             Self::Circle(curve) => curve.point_from_circle_coords(point),
+            Self::Ellipse(curve) => curve.point_from_ellipse_coords(point),
             Self::Line(curve) => curve.point_from_line_coords(point),
+            Self::Nurbs(curve) => curve.point_from_curve_coords(point),
         }
     }
 
@@ -124,7 +164,13 @@ impl<const D: usize> Curve<D> {
     ) -> Vector<D> {
         match self {
             Self::Circle(curve) => curve.vector_from_circle_coords(point),
+            Self::Ellipse(curve) => curve.vector_from_ellipse_coords(point),
             Self::Line(curve) => curve.vector_from_line_coords(point),
+            Self::Nurbs(curve) => {
+                // Like for the other curves, this is the vector from the origin
+                // of the curve to the point at the given curve coordinates.
+                curve.point_from_curve_coords([point.into().t]) - self.origin()
+            }
         }
     }
 }
@@ -133,7 +179,57 @@ impl<const D: usize> fmt::Display for Curve<D> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Circle(curve) => write!(f, "{:?}", curve),
+            Self::Ellipse(curve) => write!(f, "{:?}", curve),
             Self::Line(curve) => write!(f, "{:?}", curve),
+            Self::Nurbs(curve) => write!(f, "{:?}", curve),
         }
     }
 }
+
+/// Check whether the semi-diameters of a transformed circle still define one
+///
+/// They need to be of equal length and perpendicular to each other. Allows for
+/// a small relative error, to account for floating point accuracy issues.
+fn is_circle(circle: &Circle<3>) -> bool {
+    let epsilon = Scalar::from_f64(1e-12);
+
+    let a = circle.a.magnitude();
+    let b = circle.b.magnitude();
+
+    (a - b).abs() <= a * epsilon
+        && circle.a.dot(&circle.b).abs() <= a * b * epsilon
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Circle, Point, Scalar, Transform, Vector};
+
+    use super::Curve;
+
+    #[test]
+    fn transform_circle() {
+        let circle = Curve::Circle(Circle {
+            center: Point::from([1., 0., 0.]),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 1., 0.]),
+        });
+
+        let transform = Transform::rotation(Vector::unit_z() * 0.3)
+            * Transform::scale([2., 2., 2.]);
+        assert!(matches!(
+            circle.clone().transform(&transform),
+            Curve::Circle(_)
+        ));
+
+        let transform = Transform::scale([2., 1., 1.]);
+        let ellipse = circle.clone().transform(&transform);
+        assert!(matches!(ellipse, Curve::Ellipse(_)));
+
+        // Curve coordinates are preserved by the transform.
+        let t = Scalar::from(0.5);
+        assert_eq!(
+            ellipse.point_from_curve_coords([t]),
+            transform.transform_point(&circle.point_from_curve_coords([t])),
+        );
+    }
+}
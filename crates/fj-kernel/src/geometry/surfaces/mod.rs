@@ -2,17 +2,20 @@ pub mod swept;
 
 pub use self::swept::SweptCurve;
 
-use fj_math::{Line, Point, Transform, Vector};
+use fj_math::{Line, NurbsSurface, Point, Transform, Vector};
 
 use crate::geometry;
 
 use super::Curve;
 
 /// A two-dimensional shape
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum Surface {
     /// A swept curve
     SweptCurve(SweptCurve),
+
+    /// A NURBS surface
+    Nurbs(NurbsSurface),
 }
 
 impl Surface {
@@ -55,6 +58,7 @@ impl Surface {
     pub fn reverse(self) -> Self {
         match self {
             Self::SweptCurve(surface) => Self::SweptCurve(surface.reverse()),
+            Self::Nurbs(surface) => Self::Nurbs(surface.reverse()),
         }
     }
 
@@ -65,6 +69,9 @@ impl Surface {
             Self::SweptCurve(surface) => {
                 Self::SweptCurve(surface.transform(transform))
             }
+            Self::Nurbs(surface) => {
+                Self::Nurbs(transform.transform_nurbs_surface(&surface))
+            }
         }
     }
 
@@ -79,6 +86,7 @@ impl Surface {
             Self::SweptCurve(surface) => {
                 surface.point_to_surface_coords(point_3d)
             }
+            Self::Nurbs(surface) => surface.point_to_surface_coords(point_3d),
         };
 
         geometry::Point::new(point_2d, point_3d)
@@ -93,18 +101,72 @@ impl Surface {
             Self::SweptCurve(surface) => {
                 surface.point_from_surface_coords(point)
             }
+            Self::Nurbs(surface) => surface.point_from_surface_coords(point),
         }
     }
 
     /// Convert a vector in surface coordinates to model coordinates
+    ///
+    /// `point` is the point in surface coordinates, that the vector starts at.
+    /// Swept surfaces are linear in the direction of the sweep, so the result
+    /// doesn't depend on it. For NURBS surfaces, the partial derivatives at
+    /// `point` are used.
     pub fn vector_from_surface_coords(
         &self,
+        point: impl Into<Point<2>>,
         vector: impl Into<Vector<2>>,
     ) -> Vector<3> {
         match self {
             Self::SweptCurve(surface) => {
                 surface.vector_from_surface_coords(vector)
             }
+            Self::Nurbs(surface) => {
+                let vector = vector.into();
+                let [du, dv] = surface.derivatives(point);
+
+                du * vector.u + dv * vector.v
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{NurbsSurface, Point, Vector};
+
+    use super::Surface;
+
+    #[test]
+    fn vector_from_surface_coords_on_nurbs_surface() {
+        // A parabolic cylinder, z = (2u - 1)^2, which is curved in the u
+        // direction, and straight in the v direction.
+        let surface = Surface::Nurbs(NurbsSurface::from_control_points(
+            [2, 1],
+            vec![
+                vec![[0., 0., 1.], [0., 1., 1.]],
+                vec![[1., 0., -1.], [1., 1., -1.]],
+                vec![[2., 0., 1.], [2., 1., 1.]],
+            ]
+            .into_iter()
+            .map(|row| row.into_iter().map(Point::from).collect())
+            .collect(),
+        ));
+
+        // The derivative with respect to u is [2, 0, 8u - 4].
+        for (u, expected) in [(0., [2., 0., -4.]), (0.5, [2., 0., 0.])] {
+            let point = [u, 0.5];
+            assert_eq!(
+                surface.vector_from_surface_coords(point, [1., 0.]),
+                Vector::from(expected),
+            );
+            assert_eq!(
+                surface.vector_from_surface_coords(point, [0., 1.]),
+                Vector::from([0., 1., 0.]),
+            );
         }
+        assert_eq!(
+            surface.vector_from_surface_coords([0.75, 0.5], [0.5, 2.]),
+            Vector::from([1., 2., 1.]),
+        );
     }
 }
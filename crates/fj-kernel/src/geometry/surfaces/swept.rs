@@ -3,7 +3,7 @@ use fj_math::{Line, Point, Transform, Vector};
 use crate::geometry::Curve;
 
 /// A surface that was swept from a curve
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct SweptCurve {
     /// The curve that this surface was swept from
     pub curve: Curve<3>,
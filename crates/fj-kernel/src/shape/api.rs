@@ -1,21 +1,26 @@
+use std::{collections::HashSet, hash::Hash};
+
 use fj_math::{Point, Scalar};
 
 use crate::{
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
 use super::{
     stores::{Store, Stores},
-    Handle, Iter, Mapping, Object, Update, ValidationError, ValidationResult,
+    validate::{
+        validate_manifold, validate_solid, validate_stores, ValidationConfig,
+        ValidationLevel,
+    },
+    Handle, Iter, Mapping, Object, ShapeVersion, Update, ValidationError,
+    ValidationResult,
 };
 
 /// The boundary representation of a shape
 #[derive(Clone, Debug)]
 pub struct Shape {
-    distinct_min_distance: Scalar,
-    identical_max_distance: Scalar,
-
+    config: ValidationConfig,
     stores: Stores,
 }
 
@@ -23,20 +28,7 @@ impl Shape {
     /// Construct a new shape
     pub fn new() -> Self {
         Self {
-            // This should really come from `Self::DEFAULT_MIN_DISTANCE`, or a
-            // similarly named constant. Unfortunately `Scalar::from_f64` can't
-            // be `const` yet.
-            distinct_min_distance: Scalar::from_f64(5e-7), // 0.5 µm
-
-            // This value was chosen pretty arbitrarily. Seems small enough to
-            // catch errors. If it turns out it's too small (because it produces
-            // false positives due to floating-point accuracy issues), we can
-            // adjust it.
-            //
-            // This should be defined in an associated constant, so API users
-            // can see what the default is. Unfortunately, `Scalar::from_f64`
-            // can't be `const` yet.
-            identical_max_distance: Scalar::from_f64(5e-14),
+            config: ValidationConfig::default(),
 
             stores: Stores {
                 points: Store::new(),
@@ -47,6 +39,8 @@ impl Shape {
                 edges: Store::new(),
                 cycles: Store::new(),
                 faces: Store::new(),
+                shells: Store::new(),
+                solids: Store::new(),
             },
         }
     }
@@ -58,7 +52,7 @@ impl Shape {
         mut self,
         distinct_min_distance: impl Into<Scalar>,
     ) -> Self {
-        self.distinct_min_distance = distinct_min_distance.into();
+        self.config.distinct_min_distance = distinct_min_distance.into();
         self
     }
 
@@ -69,21 +63,49 @@ impl Shape {
         mut self,
         identical_max_distance: impl Into<Scalar>,
     ) -> Self {
-        self.identical_max_distance = identical_max_distance.into();
+        self.config.identical_max_distance = identical_max_distance.into();
+        self
+    }
+
+    /// Override the validation configuration
+    ///
+    /// By default, all validation is done whenever an object is inserted. Use
+    /// this method to select a cheaper [`ValidationLevel`], for example while
+    /// a shape is being edited interactively.
+    pub fn with_validation_config(mut self, config: ValidationConfig) -> Self {
+        self.config = config;
         self
     }
 
+    /// Override the validation level
+    ///
+    /// See [`Shape::with_validation_config`].
+    pub fn with_validation_level(mut self, level: ValidationLevel) -> Self {
+        self.config.level = level;
+        self
+    }
+
+    /// Access the validation configuration
+    pub fn validation_config(&self) -> ValidationConfig {
+        self.config
+    }
+
+    /// Access the minimum distance between distinct objects
+    pub fn distinct_min_distance(&self) -> Scalar {
+        self.config.distinct_min_distance
+    }
+
     /// Insert an object into the shape
     ///
     /// Validates the object, and returns an error if it is not valid. See the
     /// documentation of each object for validation requirements.
+    ///
+    /// How much validation is done depends on the validation level of the
+    /// shape. See [`Shape::with_validation_config`].
     pub fn insert<T: Object>(&mut self, object: T) -> ValidationResult<T> {
-        object.validate(
-            None,
-            self.distinct_min_distance,
-            self.identical_max_distance,
-            &self.stores,
-        )?;
+        if self.config.level.validates_on_insert() {
+            object.validate(None, &self.config, &self.stores)?;
+        }
         let handle = self.stores.get::<T>().insert(object);
         Ok(handle)
     }
@@ -93,20 +115,37 @@ impl Shape {
     /// Returns the handle that refers to the given object, if it is part of the
     /// shape. Returns `None`, if it isn't.
     ///
+    /// If `object` is present multiple times, the handle of the one that was
+    /// inserted first is returned.
+    ///
     /// # Implementation note
     ///
-    /// If `object` is present multiple times, the handle of the first that is
-    /// found is returned. This is weird. It would be better, if objects were
-    /// unique, but currently they are stored in `Vec`s.
+    /// Points and vertices are looked up using a spatial index, which only
+    /// needs to compare `object` to the objects at the same position. All other
+    /// objects require a linear search.
     ///
-    /// This probably isn't worth thinking too much about right now. At some
-    /// point, we need smarter and probably more performant object storage
-    /// anyway.
+    /// It would be better, if objects were unique, so there could only ever be
+    /// one matching handle. Validation only guarantees that for some objects.
     pub fn get_handle<T: Object>(&self, object: &T) -> Option<Handle<T>> {
-        self.stores
-            .get::<T>()
-            .iter()
-            .find(|obj| &obj.get() == object)
+        let store = self.stores.get::<T>();
+
+        match object.position() {
+            Some(position) => store
+                .within(position, Scalar::ZERO)
+                .into_iter()
+                .find(|obj| &obj.get() == object),
+            None => store.iter().find(|obj| &obj.get() == object),
+        }
+    }
+
+    /// Access the vertex that is nearest to the given point
+    ///
+    /// Returns `None`, if the shape has no vertices.
+    pub fn nearest_vertex(
+        &self,
+        point: impl Into<Point<3>>,
+    ) -> Option<Handle<Vertex>> {
+        self.stores.vertices.nearest(point.into())
     }
 
     /// Get handle of an identical object, if it exists, or add the object
@@ -167,20 +206,181 @@ impl Shape {
         for object in other.faces() {
             object.get().merge_into(Some(object), self, &mut mapping)?;
         }
+        for object in other.shells() {
+            object.get().merge_into(Some(object), self, &mut mapping)?;
+        }
+        for object in other.solids() {
+            object.get().merge_into(Some(object), self, &mut mapping)?;
+        }
 
         Ok(mapping)
     }
 
+    /// Validate that all shells of the shape are closed manifolds
+    ///
+    /// Checks that every edge of a shell is shared by exactly two of its faces,
+    /// and that those faces are oriented consistently. Once all shells are
+    /// known to be closed, checks that the shells of every solid bound it from
+    /// the correct side. Returns an error for the first shell or solid that
+    /// doesn't meet these requirements.
+    ///
+    /// Unlike other validation, this doesn't happen when objects are inserted,
+    /// as a shell can only be checked once it is complete. Faces in triangle
+    /// representation don't refer to any edges. The boundaries of those faces
+    /// are matched to the edges of other faces by their geometry instead.
+    pub fn validate_manifold(&self) -> Result<(), ValidationError> {
+        for shell in self.shells() {
+            validate_manifold(&shell.get(), self.config.distinct_min_distance)?;
+        }
+        for solid in self.solids() {
+            validate_solid(&solid.get())?;
+        }
+
+        Ok(())
+    }
+
+    /// Fully validate all objects in the shape
+    ///
+    /// This is done regardless of the validation level of the shape. Use it
+    /// to catch up on validation, if the level is [`ValidationLevel::Deferred`].
+    ///
+    /// Returns an error for the first invalid object that is found.
+    pub fn validate_all(&self) -> Result<(), ValidationError> {
+        let config = ValidationConfig {
+            level: ValidationLevel::Full,
+            ..self.config
+        };
+        validate_stores(&self.stores, &config)
+    }
+
+    /// Remove an object from the shape
+    ///
+    /// Returns the removed object. Fails, if the object is not part of the
+    /// shape, or if any other object in the shape still refers to it. Objects
+    /// that refer to the removed object must be removed first.
+    ///
+    /// Handles to the removed object must not be used afterwards. Any
+    /// [`Mapping`] that contains such handles can be cleaned up using
+    /// [`Mapping::remove_dangling`].
+    pub fn remove<T: Object>(
+        &mut self,
+        handle: &Handle<T>,
+    ) -> Result<T, RemoveError> {
+        let mut store = self.stores.get::<T>();
+
+        if !store.contains(handle) {
+            return Err(RemoveError::NotFound);
+        }
+        if T::is_referenced(handle, self) {
+            return Err(RemoveError::Referenced);
+        }
+
+        let object = store
+            .remove(handle)
+            .expect("Checked above that object is present");
+        Ok(object)
+    }
+
+    /// Remove all objects that can't be reached from any face
+    ///
+    /// Faces, shells, and solids are always kept. All other objects are
+    /// removed, unless they are referred to by a face, directly or indirectly.
+    /// This cleans up objects that have been orphaned by operations that
+    /// replace parts of a shape.
+    ///
+    /// Any [`Mapping`] that refers to objects in this shape can be cleaned up
+    /// afterwards using [`Mapping::remove_dangling`].
+    pub fn gc(&mut self) {
+        let mut surfaces = HashSet::new();
+        let mut cycles = HashSet::new();
+        for face in self.faces() {
+            if let Face::Face(face) = face.get() {
+                surfaces.insert(face.surface.clone());
+                cycles.extend(
+                    face.exteriors
+                        .as_handle()
+                        .chain(face.interiors.as_handle()),
+                );
+            }
+        }
+
+        let mut edges = HashSet::new();
+        for cycle in &cycles {
+            edges.extend(cycle.get().edges.iter().map(|edge| edge.canonical()));
+        }
+
+        let mut curves = HashSet::new();
+        let mut vertices = HashSet::new();
+        for edge in &edges {
+            let edge = edge.get();
+            curves.insert(edge.curve.canonical());
+            vertices.extend(
+                edge.vertices
+                    .iter()
+                    .flatten()
+                    .map(|vertex| vertex.canonical()),
+            );
+        }
+
+        let points: HashSet<_> =
+            vertices.iter().map(|vertex| vertex.get().point).collect();
+
+        // Objects that refer to other objects must be removed first, so the
+        // store is never left with a dangling reference.
+        self.retain(&cycles);
+        self.retain(&edges);
+        self.retain(&vertices);
+        self.retain(&curves);
+        self.retain(&surfaces);
+        self.retain(&points);
+    }
+
+    fn retain<T: Object>(&mut self, reachable: &HashSet<Handle<T>>)
+    where
+        Handle<T>: Eq + Hash,
+    {
+        let mut store = self.stores.get::<T>();
+
+        for handle in store.iter() {
+            if !reachable.contains(&handle) {
+                store.remove(&handle);
+            }
+        }
+    }
+
     /// Update objects in the shape
     ///
     /// Returns [`Update`], and API that can be used to update objects in the
     /// shape.
     pub fn update(&mut self) -> Update {
-        Update::new(
-            self.distinct_min_distance,
-            self.identical_max_distance,
-            &mut self.stores,
-        )
+        Update::new(self.config, &mut self.stores)
+    }
+
+    /// Create a version of the shape
+    ///
+    /// The version captures all objects currently in the shape. It is not
+    /// affected by any later changes to the shape. This is cheap, as the
+    /// version shares its structure with the shape.
+    pub fn version(&self) -> ShapeVersion {
+        ShapeVersion::new(&self.stores)
+    }
+
+    /// Restore an earlier version of the shape
+    ///
+    /// Undoes all changes made to the shape since `version` was created.
+    /// Handles to objects that were added since then must not be used
+    /// afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `version` was not created from this shape.
+    pub fn restore(&mut self, version: &ShapeVersion) {
+        assert!(
+            version.is_version_of(&self.stores),
+            "Can't restore version of another shape"
+        );
+
+        version.restore(&mut self.stores);
     }
 
     /// Clone the shape
@@ -193,7 +393,7 @@ impl Shape {
     }
 
     fn clone_shape_inner(&self) -> Result<(Shape, Mapping), ValidationError> {
-        let mut target = Shape::new();
+        let mut target = Shape::new().with_validation_config(self.config);
         let mut mapping = Mapping::new();
 
         for original in self.points() {
@@ -224,58 +424,80 @@ impl Shape {
             let cloned = target.merge(original.get())?;
             mapping.faces.insert(original, cloned);
         }
+        for original in self.shells() {
+            let cloned = target.merge(original.get())?;
+            mapping.shells.insert(original, cloned);
+        }
+        for original in self.solids() {
+            let cloned = target.merge(original.get())?;
+            mapping.solids.insert(original, cloned);
+        }
 
         Ok((target, mapping))
     }
 
     /// Access an iterator over all points
     ///
-    /// The caller must not make any assumptions about the order of points.
+    /// Objects are returned in the order in which they were inserted.
     pub fn points(&self) -> Iter<Point<3>> {
         self.stores.points.iter()
     }
 
     /// Access an iterator over all curves
     ///
-    /// The caller must not make any assumptions about the order of curves.
+    /// Objects are returned in the order in which they were inserted.
     pub fn curves(&self) -> Iter<Curve<3>> {
         self.stores.curves.iter()
     }
 
     /// Access an iterator over all surfaces
     ///
-    /// The caller must not make any assumptions about the order of surfaces.
+    /// Objects are returned in the order in which they were inserted.
     pub fn surfaces(&self) -> Iter<Surface> {
         self.stores.surfaces.iter()
     }
 
     /// Access iterator over all vertices
     ///
-    /// The caller must not make any assumptions about the order of vertices.
+    /// Objects are returned in the order in which they were inserted.
     pub fn vertices(&self) -> Iter<Vertex> {
         self.stores.vertices.iter()
     }
 
     /// Access iterator over all edges
     ///
-    /// The caller must not make any assumptions about the order of edges.
+    /// Objects are returned in the order in which they were inserted.
     pub fn edges(&self) -> Iter<Edge<3>> {
         self.stores.edges.iter()
     }
 
     /// Access an iterator over all cycles
     ///
-    /// The caller must not make any assumptions about the order of cycles.
+    /// Objects are returned in the order in which they were inserted.
     pub fn cycles(&self) -> Iter<Cycle<3>> {
         self.stores.cycles.iter()
     }
 
     /// Access an iterator over all faces
     ///
-    /// The caller must not make any assumptions about the order of faces.
+    /// Objects are returned in the order in which they were inserted.
     pub fn faces(&self) -> Iter<Face> {
         self.stores.faces.iter()
     }
+
+    /// Access an iterator over all shells
+    ///
+    /// Objects are returned in the order in which they were inserted.
+    pub fn shells(&self) -> Iter<Shell> {
+        self.stores.shells.iter()
+    }
+
+    /// Access an iterator over all solids
+    ///
+    /// Objects are returned in the order in which they were inserted.
+    pub fn solids(&self) -> Iter<Solid> {
+        self.stores.solids.iter()
+    }
 }
 
 impl Default for Shape {
@@ -284,6 +506,20 @@ impl Default for Shape {
     }
 }
 
+/// An error that can occur when removing an object from a [`Shape`]
+///
+/// Returned by [`Shape::remove`].
+#[derive(Debug, thiserror::Error)]
+pub enum RemoveError {
+    /// The object is not part of the shape
+    #[error("Object to remove is not part of the shape")]
+    NotFound,
+
+    /// The object is still referred to by other objects in the shape
+    #[error("Object to remove is still referred to by other objects")]
+    Referenced,
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::{Deref, DerefMut};
@@ -292,8 +528,11 @@ mod tests {
 
     use crate::{
         geometry::{Curve, Surface},
-        shape::{Handle, LocalForm, Shape, ValidationError, ValidationResult},
-        topology::{Cycle, Edge, Face, Vertex},
+        shape::{
+            Handle, LocalForm, RemoveError, Shape, ValidationError,
+            ValidationLevel, ValidationResult,
+        },
+        topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
     };
 
     const MIN_DISTANCE: f64 = 5e-7;
@@ -319,6 +558,12 @@ mod tests {
         assert!(shape.get_handle(&curve.get()).as_ref() == Some(&curve));
         assert!(shape.get_handle(&surface.get()).as_ref() == Some(&surface));
 
+        // Points and surfaces don't need to be unique.
+        shape.insert(point.get())?;
+        shape.insert(surface.get())?;
+        assert!(shape.get_handle(&point.get()).as_ref() == Some(&point));
+        assert!(shape.get_handle(&surface.get()).as_ref() == Some(&surface));
+
         let vertex = Vertex { point };
         let edge = Edge::new(curve, None);
 
@@ -343,6 +588,18 @@ mod tests {
         let face = shape.insert(face)?;
         assert!(shape.get_handle(&face.get()).as_ref() == Some(&face));
 
+        let shell = Shell::new(vec![face]);
+        assert!(shape.get_handle(&shell).is_none());
+
+        let shell = shape.insert(shell)?;
+        assert!(shape.get_handle(&shell.get()).as_ref() == Some(&shell));
+
+        let solid = Solid::new(shell, Vec::new());
+        assert!(shape.get_handle(&solid).is_none());
+
+        let solid = shape.insert(solid)?;
+        assert!(shape.get_handle(&solid.get()).as_ref() == Some(&solid));
+
         Ok(())
     }
 
@@ -457,6 +714,299 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn add_shell() -> anyhow::Result<()> {
+        let mut shape = TestShape::new();
+        let mut other = TestShape::new();
+
+        // Trying to refer to face that is not from the same shape. Should fail.
+        let face = other.add_face()?;
+        let err = shape.insert(Shell::new(vec![face.clone()])).unwrap_err();
+        assert!(err.missing_face(&face));
+
+        // Referring to face that *is* from the same shape. Should work.
+        let face = shape.add_face()?;
+        shape.insert(Shell::new(vec![face]))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_solid() -> anyhow::Result<()> {
+        let mut shape = TestShape::new();
+        let mut other = TestShape::new();
+
+        let exterior = other.add_shell()?;
+        let interior = other.add_shell()?;
+
+        // Nothing has been added to `shape`. Should fail.
+        let err = shape
+            .insert(Solid::new(exterior.clone(), vec![interior.clone()]))
+            .unwrap_err();
+        assert!(err.missing_shell(&exterior));
+        assert!(err.missing_shell(&interior));
+
+        let exterior = shape.add_shell()?;
+        let interior = shape.add_shell()?;
+
+        // Everything has been added to `shape` now. Should work!
+        shape.insert(Solid::new(exterior, vec![interior]))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn clone_shape_with_solid() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        let solid = Solid::builder(&mut shape)
+            .build_cube_from_edge_size(Scalar::ONE)?;
+
+        let (cloned, mapping) = shape.clone_shape();
+
+        let solid_cloned = mapping.solids().get(&solid).unwrap();
+        assert!(cloned.solids().any(|solid| &solid == solid_cloned));
+        assert_eq!(solid_cloned.get(), solid.get());
+        assert_eq!(solid_cloned.get().exterior().faces.len(), 6);
+
+        // The cloned solid refers to the cloned shell and faces.
+        let shell_cloned = mapping.shells().get(&solid.get().exterior).unwrap();
+        assert_eq!(&solid_cloned.get().exterior, shell_cloned);
+        for face in &solid_cloned.get().exterior().faces {
+            assert!(cloned.faces().any(|f| &f == face));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        let mut other = Shape::new();
+
+        let a = Vertex::builder(&mut shape).build_from_point([0., 0., 0.])?;
+        let b = Vertex::builder(&mut shape).build_from_point([1., 0., 0.])?;
+        let edge = Edge::builder(&mut shape)
+            .build_line_segment_from_vertices([a.clone(), b.clone()])?;
+
+        // Objects that are referred to by other objects can't be removed.
+        let point = a.get().point;
+        assert!(matches!(shape.remove(&point), Err(RemoveError::Referenced)));
+        assert!(matches!(shape.remove(&a), Err(RemoveError::Referenced)));
+
+        // Objects from other shapes can't be removed.
+        let c = Vertex::builder(&mut other).build_from_point([0., 0., 0.])?;
+        assert!(matches!(shape.remove(&c), Err(RemoveError::NotFound)));
+
+        // Once nothing refers to them anymore, objects can be removed.
+        shape.remove(&edge)?;
+        shape.remove(&a)?;
+        assert_eq!(shape.remove(&point)?, Point::from([0., 0., 0.]));
+        assert!(matches!(shape.remove(&point), Err(RemoveError::NotFound)));
+
+        assert!(shape.edges().next().is_none());
+        assert!(shape.get_handle(&Point::from([0., 0., 0.])).is_none());
+        assert_eq!(shape.nearest_vertex([0., 0., 0.]), Some(b));
+        shape.validate_all()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn gc() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let face = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]])
+            .build()?;
+
+        // Orphaned objects, that can't be reached from the face.
+        let orphan =
+            Vertex::builder(&mut shape).build_from_point([5., 5., 5.])?;
+        Edge::builder(&mut shape)
+            .build_line_segment_from_points([[0., 0., 1.], [1., 0., 1.]])?;
+        shape.insert(Surface::xz_plane())?;
+
+        // Removing objects from a cloned shape leaves the mapping dangling.
+        let (mut cloned, mut mapping) = shape.clone_shape();
+        cloned.gc();
+        mapping.remove_dangling();
+        assert_eq!(mapping.vertices().len(), 3);
+        assert!(!mapping.vertices().contains_key(&orphan));
+        assert_eq!(mapping.faces().len(), 1);
+
+        shape.gc();
+
+        assert_eq!(shape.points().count(), 3);
+        assert_eq!(shape.curves().count(), 3);
+        assert_eq!(shape.surfaces().count(), 1);
+        assert_eq!(shape.vertices().count(), 3);
+        assert_eq!(shape.edges().count(), 3);
+        assert_eq!(shape.cycles().count(), 1);
+        assert!(shape.faces().any(|f| f == face));
+        shape.validate_all()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn nearest_vertex() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        assert!(shape.nearest_vertex([0., 0., 0.]).is_none());
+
+        let a = Vertex::builder(&mut shape).build_from_point([0., 0., 0.])?;
+        let b = Vertex::builder(&mut shape).build_from_point([1., 0., 0.])?;
+
+        assert_eq!(shape.nearest_vertex([0.2, 0., 0.]), Some(a.clone()));
+        assert_eq!(shape.nearest_vertex([0.8, 5., 0.]), Some(b.clone()));
+
+        // Updating the points moves the vertices. Lookups must still work.
+        shape
+            .update()
+            .update_all(|point: &mut Point<3>| *point = *point + [10., 0., 0.])
+            .validate()?;
+
+        assert_eq!(shape.nearest_vertex([10.2, 0., 0.]), Some(a.clone()));
+        assert_eq!(shape.nearest_vertex([20., 0., 0.]), Some(b));
+        assert_eq!(
+            shape.get_handle(&Point::from([10., 0., 0.])),
+            Some(a.get().point)
+        );
+        assert_eq!(shape.get_handle(&a.get()), Some(a));
+        assert!(shape.get_handle(&Point::from([0., 0., 0.])).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let a = Vertex::builder(&mut shape).build_from_point([0., 0., 0.])?;
+        let before = shape.version();
+
+        let b = Vertex::builder(&mut shape).build_from_point([1., 0., 0.])?;
+        shape
+            .update()
+            .update_all(|point: &mut Point<3>| *point = *point + [10., 0., 0.])
+            .validate()?;
+        let after = shape.version();
+
+        shape.restore(&before);
+
+        assert_eq!(shape.vertices().count(), 1);
+        assert_eq!(a.get().point(), Point::from([0., 0., 0.]));
+        assert!(!b.is_valid());
+        assert_eq!(shape.nearest_vertex([10., 0., 0.]), Some(a.clone()));
+
+        // Later versions stay readable after an earlier one was restored.
+        assert_eq!(after.get(&a.get().point), Some(Point::from([10., 0., 0.])));
+        assert!(after.get(&b).is_some());
+        assert_eq!(after.objects::<Vertex>().count(), 2);
+
+        // Objects inserted after restoring don't reuse the removed handles.
+        let c = Vertex::builder(&mut shape).build_from_point([1., 0., 0.])?;
+        assert_ne!(b, c);
+        assert!(after.get(&c).is_none());
+        shape.validate_all()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let a = Vertex::builder(&mut shape).build_from_point([0., 0., 0.])?;
+        let b = Vertex::builder(&mut shape).build_from_point([1., 0., 0.])?;
+        let v1 = shape.version();
+        assert!(v1.diff::<Vertex>(&v1).is_empty());
+
+        let c = Vertex::builder(&mut shape).build_from_point([2., 0., 0.])?;
+        let a_value = a.get();
+        shape.remove(&a)?;
+        let v2 = shape.version();
+
+        let diff = v1.diff::<Vertex>(&v2);
+        assert!(diff.added == vec![c.clone()]);
+        assert!(diff.removed == vec![a.clone()]);
+        assert!(diff.updated.is_empty());
+        assert_eq!(v1.get(&a), Some(a_value));
+        assert!(v2.get(&a).is_none());
+
+        let diff = v1.diff::<Point<3>>(&v2);
+        assert!(diff.added == vec![c.get().point]);
+        assert!(diff.removed.is_empty());
+
+        // Only objects whose value changed are considered updated.
+        shape
+            .update()
+            .update_all(|point: &mut Point<3>| {
+                if *point == Point::from([1., 0., 0.]) {
+                    *point = Point::from([1., 1., 0.]);
+                }
+            })
+            .validate()?;
+        let v3 = shape.version();
+
+        let diff = v2.diff::<Point<3>>(&v3);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.updated == vec![b.get().point]);
+        assert!(v2.diff::<Vertex>(&v3).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validation_level() -> anyhow::Result<()> {
+        let mut other = Shape::new();
+        let curve = other.insert(Curve::x_axis())?;
+
+        // Structural validation is done, but uniqueness validation isn't.
+        let mut shape =
+            Shape::new().with_validation_level(ValidationLevel::Structural);
+        let point = shape.insert(Point::from([0., 0., 0.]))?;
+        shape.insert(Vertex {
+            point: point.clone(),
+        })?;
+        shape.insert(Vertex {
+            point: point.clone(),
+        })?;
+        let err = shape.insert(Edge::new(curve.clone(), None)).unwrap_err();
+        assert!(err.missing_curve(&curve));
+
+        // No validation at all.
+        let mut shape =
+            Shape::new().with_validation_level(ValidationLevel::Off);
+        shape.insert(Edge::new(curve.clone(), None))?;
+        assert!(shape.validate_all().unwrap_err().missing_curve(&curve));
+
+        // Validation is only done when explicitly requested.
+        let mut shape =
+            Shape::new().with_validation_level(ValidationLevel::Deferred);
+        let point = shape.insert(Point::from([0., 0., 0.]))?;
+        shape.insert(Vertex {
+            point: point.clone(),
+        })?;
+        shape.insert(Vertex { point })?;
+        assert!(matches!(
+            shape.validate_all(),
+            Err(ValidationError::Uniqueness(_))
+        ));
+
+        // Updates respect the validation level, too.
+        shape
+            .update()
+            .update_all(|vertex: &mut Vertex| *vertex = vertex.clone())
+            .validate()?;
+
+        // Cloned shapes inherit the validation configuration.
+        let (cloned, _) = shape.clone_shape();
+        assert_eq!(cloned.validation_config(), shape.validation_config());
+
+        Ok(())
+    }
+
     struct TestShape {
         inner: Shape,
         next_point: Point<3>,
@@ -495,6 +1045,24 @@ mod tests {
             let cycle = self.insert(Cycle::new(vec![edge]))?;
             Ok(cycle)
         }
+
+        fn add_face(&mut self) -> anyhow::Result<Handle<Face>> {
+            let surface = self.add_surface()?;
+            let cycle = self.add_cycle()?;
+            let face = self.insert(Face::new(
+                surface,
+                vec![cycle],
+                Vec::new(),
+                [255, 0, 0, 255],
+            ))?;
+            Ok(face)
+        }
+
+        fn add_shell(&mut self) -> anyhow::Result<Handle<Shell>> {
+            let face = self.add_face()?;
+            let shell = self.insert(Shell::new(vec![face]))?;
+            Ok(shell)
+        }
     }
 
     impl Deref for TestShape {
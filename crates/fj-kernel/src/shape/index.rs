@@ -0,0 +1,137 @@
+use fj_math::{Point, Scalar};
+use rstar::{primitives::GeomWithData, RTree};
+
+use super::stores::ObjectKey;
+
+/// A spatial index over the objects of a store
+///
+/// Wraps an R*-tree, which makes all queries sub-linear in the number of
+/// objects, regardless of how they are distributed.
+#[derive(Debug, Default)]
+pub struct SpatialIndex {
+    tree: RTree<Entry>,
+}
+
+type Entry = GeomWithData<[f64; 3], ObjectKey>;
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: ObjectKey, position: Point<3>) {
+        self.tree.insert(entry(key, position));
+    }
+
+    pub fn remove(&mut self, key: ObjectKey, position: Point<3>) {
+        self.tree.remove(&entry(key, position));
+    }
+
+    /// Replace all objects in the index
+    ///
+    /// This is faster than inserting the objects one by one.
+    pub fn rebuild(
+        &mut self,
+        objects: impl IntoIterator<Item = (ObjectKey, Point<3>)>,
+    ) {
+        let entries = objects
+            .into_iter()
+            .map(|(key, position)| entry(key, position))
+            .collect();
+        self.tree = RTree::bulk_load(entries);
+    }
+
+    /// Find all objects that are at most `radius` away from `point`
+    ///
+    /// The returned keys are sorted, to make the result independent of the
+    /// structure of the tree.
+    pub fn within(&self, point: Point<3>, radius: Scalar) -> Vec<ObjectKey> {
+        let radius = radius.into_f64();
+
+        let mut keys: Vec<_> = self
+            .tree
+            .locate_within_distance(coords(point), radius * radius)
+            .map(|entry| entry.data)
+            .collect();
+
+        keys.sort();
+        keys
+    }
+
+    /// Find the object that is nearest to `point`
+    ///
+    /// If multiple objects are equally near, the one with the smallest key is
+    /// returned.
+    pub fn nearest(&self, point: Point<3>) -> Option<ObjectKey> {
+        let mut candidates = self
+            .tree
+            .nearest_neighbor_iter_with_distance_2(&coords(point));
+
+        let (first, distance) = candidates.next()?;
+        let equally_near = candidates
+            .take_while(|&(_, other_distance)| other_distance <= distance)
+            .map(|(entry, _)| entry.data);
+
+        Some(equally_near.fold(first.data, ObjectKey::min))
+    }
+}
+
+fn entry(key: ObjectKey, position: Point<3>) -> Entry {
+    GeomWithData::new(coords(position), key)
+}
+
+fn coords(point: Point<3>) -> [f64; 3] {
+    point.coords.components.map(Scalar::into_f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::shape::stores::ObjectKey;
+
+    use super::SpatialIndex;
+
+    #[test]
+    fn within() {
+        let mut index = SpatialIndex::new();
+
+        let a = ObjectKey(0);
+        let b = ObjectKey(1);
+        let c = ObjectKey(2);
+
+        index.insert(a, Point::from([0., 0., 0.]));
+        index.insert(b, Point::from([1., 0., 0.]));
+        index.insert(c, Point::from([-3., 2., 1.]));
+
+        let origin = Point::from([0., 0., 0.]);
+        assert_eq!(index.within(origin, Scalar::ZERO), vec![a]);
+        assert_eq!(index.within(origin, Scalar::ONE), vec![a, b]);
+        assert_eq!(index.within(origin, Scalar::from_f64(10.)), vec![a, b, c]);
+        assert!(index
+            .within(Point::from([0., 5., 0.]), Scalar::ONE)
+            .is_empty());
+    }
+
+    #[test]
+    fn nearest() {
+        let mut index = SpatialIndex::new();
+
+        assert_eq!(index.nearest(Point::from([0., 0., 0.])), None);
+
+        let a = ObjectKey(0);
+        let b = ObjectKey(1);
+        let c = ObjectKey(2);
+
+        index.rebuild([
+            (c, Point::from([1., 0., 0.])),
+            (a, Point::from([-1., 0., 0.])),
+            (b, Point::from([100., 0., 0.])),
+        ]);
+
+        // `a` and `c` are equally near. The smaller key wins.
+        assert_eq!(index.nearest(Point::from([0., 0., 0.])), Some(a));
+        assert_eq!(index.nearest(Point::from([0.05, 0., 0.])), Some(c));
+        assert_eq!(index.nearest(Point::from([60., 0., 0.])), Some(b));
+    }
+}
@@ -4,10 +4,10 @@ use fj_math::Point;
 
 use crate::{
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
-use super::Handle;
+use super::{Handle, Object};
 
 /// A mapping between objects in different shapes
 pub struct Mapping {
@@ -18,6 +18,8 @@ pub struct Mapping {
     pub(super) edges: OneMapping<Edge<3>>,
     pub(super) cycles: OneMapping<Cycle<3>>,
     pub(super) faces: OneMapping<Face>,
+    pub(super) shells: OneMapping<Shell>,
+    pub(super) solids: OneMapping<Solid>,
 }
 
 impl Mapping {
@@ -30,9 +32,30 @@ impl Mapping {
             edges: OneMapping::new(),
             cycles: OneMapping::new(),
             faces: OneMapping::new(),
+            shells: OneMapping::new(),
+            solids: OneMapping::new(),
         }
     }
 
+    /// Remove all entries that refer to removed objects
+    ///
+    /// Objects can be removed from a shape using [`Shape::remove`] or
+    /// [`Shape::gc`]. This leaves any entries that refer to them dangling.
+    ///
+    /// [`Shape::remove`]: super::Shape::remove
+    /// [`Shape::gc`]: super::Shape::gc
+    pub fn remove_dangling(&mut self) {
+        remove_dangling(&mut self.points);
+        remove_dangling(&mut self.curves);
+        remove_dangling(&mut self.surfaces);
+        remove_dangling(&mut self.vertices);
+        remove_dangling(&mut self.edges);
+        remove_dangling(&mut self.cycles);
+        remove_dangling(&mut self.faces);
+        remove_dangling(&mut self.shells);
+        remove_dangling(&mut self.solids);
+    }
+
     /// Access iterator over the mapped points
     pub fn points(&self) -> &OneMapping<Point<3>> {
         &self.points
@@ -67,6 +90,20 @@ impl Mapping {
     pub fn faces(&self) -> &OneMapping<Face> {
         &self.faces
     }
+
+    /// Access iterator over the mapped shells
+    pub fn shells(&self) -> &OneMapping<Shell> {
+        &self.shells
+    }
+
+    /// Access iterator over the mapped solids
+    pub fn solids(&self) -> &OneMapping<Solid> {
+        &self.solids
+    }
 }
 
 pub type OneMapping<T> = HashMap<Handle<T>, Handle<T>>;
+
+fn remove_dangling<T: Object>(mapping: &mut OneMapping<T>) {
+    mapping.retain(|original, mapped| original.is_valid() && mapped.is_valid());
+}
@@ -3,22 +3,26 @@
 //! See [`Shape`], which is the main entry point to this API.
 
 mod api;
+mod index;
 mod local;
 mod mapping;
 mod object;
 mod stores;
 mod update;
 mod validate;
+mod version;
 
 pub use self::{
-    api::Shape,
+    api::{RemoveError, Shape},
     local::LocalForm,
     mapping::Mapping,
     object::Object,
     stores::{Handle, Iter},
     update::Update,
     validate::{
-        GeometricIssues, StructuralIssues, UniquenessIssues, ValidationError,
-        ValidationResult,
+        CycleOverlap, CycleSelfIntersection, GeometricIssues, ManifoldIssues,
+        ShellEdge, SolidIssues, StructuralIssues, UniquenessIssues,
+        ValidationConfig, ValidationError, ValidationLevel, ValidationResult,
     },
+    version::{ObjectsDiff, ShapeVersion},
 };
@@ -2,7 +2,7 @@ use fj_math::Point;
 
 use crate::{
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
 use super::{
@@ -23,6 +23,21 @@ pub trait Object:
         shape: &mut Shape,
         mapping: &mut Mapping,
     ) -> ValidationResult<Self>;
+
+    /// Internal function
+    ///
+    /// Indicates whether any object in `shape` refers to the object that
+    /// `handle` refers to. Used by [`Shape::remove`].
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool;
+
+    /// Internal function
+    ///
+    /// Returns the position of the object, if it has one. Objects that have a
+    /// position are tracked in a spatial index, which speeds up lookups and
+    /// validation.
+    fn position(&self) -> Option<Point<3>> {
+        None
+    }
 }
 
 impl private::Sealed for Point<3> {}
@@ -33,6 +48,8 @@ impl private::Sealed for Vertex {}
 impl private::Sealed for Edge<3> {}
 impl private::Sealed for Cycle<3> {}
 impl private::Sealed for Face {}
+impl private::Sealed for Shell {}
+impl private::Sealed for Solid {}
 
 impl Object for Point<3> {
     fn merge_into(
@@ -49,6 +66,14 @@ impl Object for Point<3> {
 
         Ok(merged)
     }
+
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool {
+        shape.vertices().any(|vertex| &vertex.get().point == handle)
+    }
+
+    fn position(&self) -> Option<Point<3>> {
+        Some(*self)
+    }
 }
 
 impl Object for Curve<3> {
@@ -66,6 +91,12 @@ impl Object for Curve<3> {
 
         Ok(merged)
     }
+
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool {
+        shape
+            .edges()
+            .any(|edge| &edge.get().curve.canonical() == handle)
+    }
 }
 
 impl Object for Surface {
@@ -83,6 +114,13 @@ impl Object for Surface {
 
         Ok(merged)
     }
+
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool {
+        shape.faces().any(|face| match face.get() {
+            Face::Face(face) => &face.surface == handle,
+            Face::Triangles(_) => false,
+        })
+    }
 }
 
 impl Object for Vertex {
@@ -102,6 +140,20 @@ impl Object for Vertex {
 
         Ok(merged)
     }
+
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool {
+        shape.edges().any(|edge| {
+            edge.get()
+                .vertices
+                .iter()
+                .flatten()
+                .any(|vertex| &vertex.canonical() == handle)
+        })
+    }
+
+    fn position(&self) -> Option<Point<3>> {
+        Some(self.point())
+    }
 }
 
 impl Object for Edge<3> {
@@ -144,6 +196,16 @@ impl Object for Edge<3> {
 
         Ok(merged)
     }
+
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool {
+        shape.cycles().any(|cycle| {
+            cycle
+                .get()
+                .edges
+                .iter()
+                .any(|edge| &edge.canonical() == handle)
+        })
+    }
 }
 
 impl Object for Cycle<3> {
@@ -168,6 +230,17 @@ impl Object for Cycle<3> {
 
         Ok(merged)
     }
+
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool {
+        shape.faces().any(|face| match face.get() {
+            Face::Face(face) => face
+                .exteriors
+                .as_handle()
+                .chain(face.interiors.as_handle())
+                .any(|cycle| &cycle == handle),
+            Face::Triangles(_) => false,
+        })
+    }
 }
 
 impl Object for Face {
@@ -212,6 +285,76 @@ impl Object for Face {
 
         Ok(merged)
     }
+
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool {
+        shape
+            .shells()
+            .any(|shell| shell.get().faces.contains(handle))
+    }
+}
+
+impl Object for Shell {
+    fn merge_into(
+        self,
+        handle: Option<Handle<Self>>,
+        shape: &mut Shape,
+        mapping: &mut Mapping,
+    ) -> ValidationResult<Self> {
+        let mut faces = Vec::new();
+        for face in self.faces {
+            let face = face.get().merge_into(Some(face), shape, mapping)?;
+            faces.push(face);
+        }
+
+        let merged = shape.get_handle_or_insert(Shell::new(faces))?;
+
+        if let Some(handle) = handle {
+            mapping.shells.insert(handle, merged.clone());
+        }
+
+        Ok(merged)
+    }
+
+    fn is_referenced(handle: &Handle<Self>, shape: &Shape) -> bool {
+        shape
+            .solids()
+            .any(|solid| solid.get().all_shells().any(|shell| &shell == handle))
+    }
+}
+
+impl Object for Solid {
+    fn merge_into(
+        self,
+        handle: Option<Handle<Self>>,
+        shape: &mut Shape,
+        mapping: &mut Mapping,
+    ) -> ValidationResult<Self> {
+        let exterior = self.exterior.get().merge_into(
+            Some(self.exterior),
+            shape,
+            mapping,
+        )?;
+
+        let mut interiors = Vec::new();
+        for shell in self.interiors {
+            let shell = shell.get().merge_into(Some(shell), shape, mapping)?;
+            interiors.push(shell);
+        }
+
+        let merged =
+            shape.get_handle_or_insert(Solid::new(exterior, interiors))?;
+
+        if let Some(handle) = handle {
+            mapping.solids.insert(handle, merged.clone());
+        }
+
+        Ok(merged)
+    }
+
+    fn is_referenced(_: &Handle<Self>, _: &Shape) -> bool {
+        // Nothing refers to solids.
+        false
+    }
 }
 
 mod private {
@@ -1,20 +1,22 @@
 use std::{
     fmt,
     hash::{Hash, Hasher},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 use anymap::AnyMap;
-use fj_math::Point;
+use fj_math::{Point, Scalar};
 use parking_lot::{RwLock, RwLockReadGuard};
-use slotmap::{DefaultKey, SlotMap};
 
 use crate::{
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
-use super::Object;
+use super::{index::SpatialIndex, Object};
 
 #[derive(Clone, Debug)]
 pub struct Stores {
@@ -26,6 +28,8 @@ pub struct Stores {
     pub edges: Store<Edge<3>>,
     pub cycles: Store<Cycle<3>>,
     pub faces: Store<Face>,
+    pub shells: Store<Shell>,
+    pub solids: Store<Solid>,
 }
 
 impl Stores {
@@ -40,6 +44,8 @@ impl Stores {
         stores.insert(self.edges.clone());
         stores.insert(self.cycles.clone());
         stores.insert(self.faces.clone());
+        stores.insert(self.shells.clone());
+        stores.insert(self.solids.clone());
 
         stores
             .remove::<Store<T>>()
@@ -47,44 +53,96 @@ impl Stores {
             // for all types of objects above.
             .expect("Invalid object type")
     }
+
+    /// Rebuild the spatial indices of all stores
+    ///
+    /// This must be called after objects have been updated. The position of a
+    /// vertex depends on its point, so updating the points invalidates the
+    /// index of the vertices too.
+    pub fn rebuild_indices(&self) {
+        self.points.rebuild_index();
+        self.vertices.rebuild_index();
+    }
 }
 
 #[derive(Debug)]
 pub struct Store<T: Object> {
     objects: Arc<RwLock<Objects<T>>>,
+
+    /// The key that is going to be assigned to the next inserted object
+    ///
+    /// Keys are never reused, not even after an object has been removed, or an
+    /// earlier version of the store has been restored. See [`ObjectKey`].
+    next_key: Arc<AtomicU64>,
+
+    /// Spatial index of the objects that have a position
+    ///
+    /// See [`Object::position`].
+    index: Arc<RwLock<SpatialIndex>>,
 }
 
 impl<T: Object> Store<T> {
     pub fn new() -> Self {
         Self {
-            objects: Arc::new(RwLock::new(SlotMap::new())),
+            objects: Arc::new(RwLock::new(Objects::new())),
+            next_key: Arc::new(AtomicU64::new(0)),
+            index: Arc::new(RwLock::new(SpatialIndex::new())),
         }
     }
 
     pub fn insert(&mut self, object: T) -> Handle<T> {
-        let key = self.objects.write().insert(object);
+        let position = object.position();
+        let key = ObjectKey(self.next_key.fetch_add(1, Ordering::Relaxed));
+        self.objects.write().insert(key, object);
+
+        if let Some(position) = position {
+            self.index.write().insert(key, position);
+        }
+
         Handle::new(key, self.clone())
     }
 
+    pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
+        if !self.contains(handle) {
+            return None;
+        }
+
+        let object = self.objects.write().remove(&handle.key())?;
+
+        // The position of a vertex depends on its point, so the point must
+        // not be removed before the vertex.
+        if let Some(position) = object.position() {
+            self.index.write().remove(handle.key(), position);
+        }
+
+        Some(object)
+    }
+
     pub fn contains(&self, object: &Handle<T>) -> bool {
-        object.store() == self && self.objects.read().contains_key(object.key())
+        object.store() == self
+            && self.objects.read().contains_key(&object.key())
     }
 
     pub fn read(&self) -> RwLockReadGuard<Objects<T>> {
         self.objects.read()
     }
 
+    /// Iterate over all objects, in the order in which they were inserted
     pub fn iter(&self) -> Iter<T> {
         // The allocation here is unfortunate, but I think it's fine for now. If
         // this turns into a performance issue, it should be possible to avoid
         // it by adding methods to `Store`, that are geared towards implementing
         // iterators.
+        //
+        // Keys are assigned in ascending order, and `Iter` pops its elements
+        // from the back, so they need to be collected in reverse.
         Iter {
             elements: self
                 .objects
                 .read()
-                .iter()
-                .map(|(key, _)| Handle::new(key, self.clone()))
+                .keys()
+                .rev()
+                .map(|&key| Handle::new(key, self.clone()))
                 .collect(),
         }
     }
@@ -93,11 +151,64 @@ impl<T: Object> Store<T> {
     where
         F: FnMut(&mut T),
     {
-        for (_, object) in self.objects.write().iter_mut() {
-            f(object);
+        let mut objects = self.objects.write();
+
+        // The map can't be iterated over mutably. Updating the objects one by
+        // one instead only copies the parts of the map that are shared with
+        // earlier versions.
+        let keys: Vec<_> = objects.keys().copied().collect();
+        for key in keys {
+            if let Some(object) = objects.get_mut(&key) {
+                f(object);
+            }
         }
     }
 
+    /// Access the current version of the objects
+    ///
+    /// This is cheap, as the returned objects share their structure with the
+    /// store. Changes to the store are not reflected in them.
+    pub fn snapshot(&self) -> Objects<T> {
+        self.objects.read().clone()
+    }
+
+    /// Replace all objects with an earlier version
+    ///
+    /// Leaves the spatial index outdated. See [`Stores::rebuild_indices`].
+    pub fn restore(&mut self, objects: Objects<T>) {
+        *self.objects.write() = objects;
+    }
+
+    /// Find all objects that are at most `radius` away from `point`
+    ///
+    /// Only objects that have a position are considered. The handles are
+    /// returned in a deterministic order.
+    pub fn within(&self, point: Point<3>, radius: Scalar) -> Vec<Handle<T>> {
+        self.index
+            .read()
+            .within(point, radius)
+            .into_iter()
+            .map(|key| Handle::new(key, self.clone()))
+            .collect()
+    }
+
+    /// Find the object that is nearest to `point`
+    ///
+    /// Only objects that have a position are considered.
+    pub fn nearest(&self, point: Point<3>) -> Option<Handle<T>> {
+        let key = self.index.read().nearest(point)?;
+        Some(Handle::new(key, self.clone()))
+    }
+
+    fn rebuild_index(&self) {
+        let objects = self.objects.read();
+        let positions = objects.iter().filter_map(|(&key, object)| {
+            object.position().map(|position| (key, position))
+        });
+
+        self.index.write().rebuild(positions);
+    }
+
     fn ptr(&self) -> *const () {
         Arc::as_ptr(&self.objects) as _
     }
@@ -110,6 +221,8 @@ impl<T: Object> Clone for Store<T> {
     fn clone(&self) -> Self {
         Self {
             objects: self.objects.clone(),
+            next_key: self.next_key.clone(),
+            index: self.index.clone(),
         }
     }
 }
@@ -146,7 +259,18 @@ impl<T: Object> Hash for Store<T> {
     }
 }
 
-pub type Objects<T> = SlotMap<DefaultKey, T>;
+/// The objects of a [`Store`]
+///
+/// This is a persistent map. Cloning it is cheap, and the clone shares its
+/// structure with the original. Both can be modified independently afterwards.
+pub type Objects<T> = im::OrdMap<ObjectKey, T>;
+
+/// Identifies an object within a [`Store`]
+///
+/// Keys are assigned in ascending order, and are never reused. This makes them
+/// a stable identity for an object across all versions of a store.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ObjectKey(pub(super) u64);
 
 /// A handle to an object stored within [`Shape`]
 ///
@@ -173,16 +297,16 @@ pub type Objects<T> = SlotMap<DefaultKey, T>;
 /// memory location.
 #[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct Handle<T: Object> {
-    key: DefaultKey,
+    key: ObjectKey,
     store: Store<T>,
 }
 
 impl<T: Object> Handle<T> {
-    pub(super) fn new(key: DefaultKey, store: Store<T>) -> Self {
+    pub(super) fn new(key: ObjectKey, store: Store<T>) -> Self {
         Self { key, store }
     }
 
-    pub(super) fn key(&self) -> DefaultKey {
+    pub(super) fn key(&self) -> ObjectKey {
         self.key
     }
 
@@ -190,17 +314,29 @@ impl<T: Object> Handle<T> {
         &self.store
     }
 
+    /// Indicate whether the object that the handle references still exists
+    ///
+    /// Returns `false`, if the object has been removed from its shape.
+    pub(super) fn is_valid(&self) -> bool {
+        self.store.contains(self)
+    }
+
     /// Access the object that the handle references
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the object has been removed from its shape. See
+    /// [`Shape::remove`] and [`Shape::gc`].
     pub fn get(&self) -> T
     where
         T: Clone,
     {
         self.store
             .read()
-            .get(self.key)
-            // Can't panic, unless the handle was invalid in the first place.
-            // Objects are never removed from `Store`, so if we have a handle
-            // pointing to it, it should be there.
+            .get(&self.key)
+            // Can't panic, unless the handle was invalid in the first place,
+            // or the object has been removed. Objects are only removed from
+            // `Store`, if nothing else in the shape refers to them.
             .expect("Invalid handle")
             .clone()
     }
@@ -217,7 +353,8 @@ where
 
 /// An iterator over geometric or topological objects
 ///
-/// Returned by various methods of the [`Shape`] API.
+/// Returned by various methods of the [`Shape`] API. Yields the objects in the
+/// order in which they were inserted into the shape.
 pub struct Iter<T: Object> {
     elements: Vec<Handle<T>>,
 }
@@ -1,27 +1,26 @@
-use fj_math::Scalar;
-
-use super::{stores::Stores, validate::Validate as _, Object, ValidationError};
+use super::{
+    stores::Stores,
+    validate::{validate_stores, ValidationConfig},
+    Object, ValidationError,
+};
 
 /// API to update a `Shape`
 ///
 /// See [`Shape::update`].
 #[must_use]
 pub struct Update<'r> {
-    min_distance: Scalar,
-    max_distance: Scalar,
+    config: ValidationConfig,
     stores: &'r mut Stores,
     executed: bool,
 }
 
 impl<'r> Update<'r> {
     pub(super) fn new(
-        min_distance: Scalar,
-        max_distance: Scalar,
+        config: ValidationConfig,
         stores: &'r mut Stores,
     ) -> Self {
         Self {
-            min_distance,
-            max_distance,
+            config,
             stores,
             executed: false,
         }
@@ -38,6 +37,11 @@ impl<'r> Update<'r> {
     /// The update is validated automatically, when this `Update` instance is
     /// dropped. It is recommended to validate by calling this method though, as
     /// no [`ValidationError`] will be available otherwise.
+    ///
+    /// Validation is skipped, if the validation level of the shape doesn't
+    /// call for validation on insertion. See [`ValidationLevel`].
+    ///
+    /// [`ValidationLevel`]: super::ValidationLevel
     pub fn validate(mut self) -> Result<(), ValidationError> {
         self.validate_inner()
     }
@@ -46,63 +50,12 @@ impl<'r> Update<'r> {
         if !self.executed {
             self.executed = true;
 
-            // Validating every single object is certainly not ideal from a
-            // performance perspective, but it will do for now.
-            for object in self.stores.points.iter() {
-                object.get().validate(
-                    Some(&object),
-                    self.min_distance,
-                    self.max_distance,
-                    self.stores,
-                )?;
-            }
-            for object in self.stores.curves.iter() {
-                object.get().validate(
-                    Some(&object),
-                    self.min_distance,
-                    self.max_distance,
-                    self.stores,
-                )?;
-            }
-            for object in self.stores.surfaces.iter() {
-                object.get().validate(
-                    Some(&object),
-                    self.min_distance,
-                    self.max_distance,
-                    self.stores,
-                )?;
-            }
-            for object in self.stores.vertices.iter() {
-                object.get().validate(
-                    Some(&object),
-                    self.min_distance,
-                    self.max_distance,
-                    self.stores,
-                )?;
-            }
-            for object in self.stores.edges.iter() {
-                object.get().validate(
-                    Some(&object),
-                    self.min_distance,
-                    self.max_distance,
-                    self.stores,
-                )?;
-            }
-            for object in self.stores.cycles.iter() {
-                object.get().validate(
-                    Some(&object),
-                    self.min_distance,
-                    self.max_distance,
-                    self.stores,
-                )?;
-            }
-            for object in self.stores.faces.iter() {
-                object.get().validate(
-                    Some(&object),
-                    self.min_distance,
-                    self.max_distance,
-                    self.stores,
-                )?;
+            // Updated objects might have moved. Nobody can look at the stores
+            // while this `Update` exists, so it's enough to do this once.
+            self.stores.rebuild_indices();
+
+            if self.config.level.validates_on_insert() {
+                validate_stores(self.stores, &self.config)?;
             }
         }
 
@@ -1,8 +1,24 @@
 use std::fmt;
 
-use fj_math::{Point, Scalar};
+use fj_math::{Line, Point, Scalar, Segment};
+use rstar::{
+    primitives::{GeomWithData, Rectangle},
+    RTree, AABB,
+};
 
-use crate::topology::Edge;
+use crate::{
+    algorithms::intersection::{line_segment, LineSegmentIntersection},
+    geometry::{Curve, Surface},
+    shape::Handle,
+    topology::{Cycle, Edge, Face, Vertex},
+};
+
+use super::manifold::sample_edge;
+
+/// The number of segments that curved edges are approximated with
+///
+/// Straight edges are represented exactly by a single segment.
+const CURVE_SEGMENTS: usize = 64;
 
 pub fn validate_edge(
     edge: &Edge<3>,
@@ -35,13 +51,301 @@ pub fn validate_edge(
         if !edge_vertex_mismatches.is_empty() {
             return Err(GeometricIssues {
                 edge_vertex_mismatches,
+                ..GeometricIssues::default()
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_face(face: &Face) -> Result<(), GeometricIssues> {
+    let face = match face {
+        Face::Face(face) => face,
+        // Faces in triangle representation don't have any cycles.
+        Face::Triangles(_) => return Ok(()),
+    };
+    let surface = face.surface();
+
+    let cycles: Vec<_> = face
+        .exteriors
+        .as_handle()
+        .chain(face.interiors.as_handle())
+        .collect();
+    let segments: Vec<_> = cycles
+        .iter()
+        .enumerate()
+        .flat_map(|(i, cycle)| cycle_segments(i, &cycle.get(), &surface))
+        .collect();
+
+    // Intersections are recorded as the indices of the two cycles involved,
+    // and the point where they intersect. A point where many segments meet
+    // would otherwise be reported many times.
+    let mut intersections: Vec<_> = shared_vertices(&segments)
+        .into_iter()
+        .map(|(i, point)| (i, i, point))
+        .collect();
+
+    // Only segments whose bounding boxes touch can intersect. Finding those
+    // using a spatial index avoids checking every pair of segments.
+    let tree = RTree::bulk_load(
+        segments
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| {
+                let [a, b] = segment.segment.points().map(coords);
+                GeomWithData::new(Rectangle::from_corners(a, b), i)
+            })
+            .collect(),
+    );
+
+    for (i, a) in segments.iter().enumerate() {
+        let [p, q] = a.segment.points().map(coords);
+        let candidates = tree
+            .locate_in_envelope_intersecting(&AABB::from_corners(p, q))
+            .map(|entry| entry.data)
+            .filter(|&j| j > i);
+
+        for b in candidates.map(|j| &segments[j]) {
+            if let Some(point) = segment_intersection(a, b) {
+                intersections.push((a.cycle, b.cycle, point));
+            }
+        }
+    }
+
+    intersections.sort();
+    intersections.dedup();
+
+    let mut cycle_self_intersections = Vec::new();
+    let mut cycle_overlaps = Vec::new();
+
+    for (a, b, point) in intersections {
+        let point = surface.point_from_surface_coords(point);
+
+        if a == b {
+            cycle_self_intersections.push(CycleSelfIntersection {
+                cycle: cycles[a].clone(),
+                point,
+            });
+        } else {
+            cycle_overlaps.push(CycleOverlap {
+                cycles: [cycles[a].clone(), cycles[b].clone()],
+                point,
             });
         }
     }
 
+    if !cycle_self_intersections.is_empty() || !cycle_overlaps.is_empty() {
+        return Err(GeometricIssues {
+            cycle_self_intersections,
+            cycle_overlaps,
+            ..GeometricIssues::default()
+        });
+    }
+
     Ok(())
 }
 
+/// A segment of the approximation of a cycle
+struct CycleSegment {
+    /// The index of the cycle within its face
+    cycle: usize,
+
+    /// The segment, in surface coordinates
+    segment: Segment<2>,
+
+    /// The joints at the ends of the segment
+    joints: [Joint; 2],
+}
+
+impl CycleSegment {
+    /// Find the joint that this segment shares with another one, if any
+    ///
+    /// Returns the index of the joint within both segments. Segments of
+    /// different cycles never share a joint, even if they meet in the same
+    /// vertex.
+    fn shared_joint(&self, other: &Self) -> Option<[usize; 2]> {
+        if self.cycle != other.cycle {
+            return None;
+        }
+
+        self.joints.iter().enumerate().find_map(|(i, joint)| {
+            let j = other.joints.iter().position(|other| other == joint)?;
+            Some([i, j])
+        })
+    }
+}
+
+/// A point where the segments of a cycle are connected
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Joint {
+    /// A vertex that connects two edges
+    Vertex(Handle<Vertex>),
+
+    /// A point within an edge, identified by the indices of edge and point
+    Edge(usize, usize),
+}
+
+/// Approximate the edges of a cycle with segments in surface coordinates
+///
+/// The edges are approximated one by one, so this doesn't rely on the order of
+/// the edges within the cycle.
+fn cycle_segments(
+    index: usize,
+    cycle: &Cycle<3>,
+    surface: &Surface,
+) -> Vec<CycleSegment> {
+    let mut segments = Vec::new();
+
+    for (i, edge) in cycle.edges().enumerate() {
+        let num_segments = match edge.curve() {
+            Curve::Line(_) => 1,
+            _ => CURVE_SEGMENTS,
+        };
+        let mut points = sample_edge(&edge, num_segments);
+        let mut joints: Vec<_> =
+            (0..=num_segments).map(|j| Joint::Edge(i, j)).collect();
+
+        // Use the exact vertices for the ends of the edge. Neighboring edges
+        // need to end in identical points, or they would be mistaken for
+        // intersecting edges.
+        match &edge.vertices {
+            Some([a, b]) => {
+                points[0] = a.canonical().get().point();
+                points[num_segments] = b.canonical().get().point();
+                joints[0] = Joint::Vertex(a.canonical());
+                joints[num_segments] = Joint::Vertex(b.canonical());
+            }
+            None => {
+                points[num_segments] = points[0];
+                joints[num_segments] = Joint::Edge(i, 0);
+            }
+        }
+
+        let points: Vec<_> = points
+            .into_iter()
+            .map(|point| surface.point_to_surface_coords(point).local())
+            .collect();
+
+        for (ab, joints) in points.windows(2).zip(joints.windows(2)) {
+            segments.push(CycleSegment {
+                cycle: index,
+                segment: Segment::from_points([ab[0], ab[1]]),
+                joints: [joints[0].clone(), joints[1].clone()],
+            });
+        }
+    }
+
+    segments
+}
+
+/// Find vertices that connect more than two edges of the same cycle
+///
+/// The cycle touches itself in such a vertex. As all segments that meet there
+/// share the same joint, this isn't found by [`segment_intersection`].
+///
+/// Returns the index of the cycle, and the point of the vertex.
+fn shared_vertices(segments: &[CycleSegment]) -> Vec<(usize, Point<2>)> {
+    let mut ends = Vec::new();
+
+    for segment in segments {
+        for (joint, point) in
+            segment.joints.iter().zip(segment.segment.points())
+        {
+            if let Joint::Vertex(vertex) = joint {
+                ends.push((segment.cycle, vertex, point));
+            }
+        }
+    }
+
+    ends.sort_by(|(a, a_vertex, _), (b, b_vertex, _)| {
+        (a, a_vertex).cmp(&(b, b_vertex))
+    });
+
+    let mut shared = Vec::new();
+    let mut i = 0;
+    while i < ends.len() {
+        let (cycle, vertex, point) = ends[i];
+        let num_ends = ends[i..]
+            .iter()
+            .take_while(|&&(c, v, _)| c == cycle && v == vertex)
+            .count();
+
+        // Each edge that connects to the vertex has one end there.
+        if num_ends > 2 {
+            shared.push((cycle, point));
+        }
+
+        i += num_ends;
+    }
+
+    shared
+}
+
+/// Compute the intersection of two segments, if any
+///
+/// Neighboring segments of a cycle always meet in the joint they share, so
+/// that isn't considered an intersection. They only intersect, if they fold
+/// back onto each other.
+fn segment_intersection(
+    a: &CycleSegment,
+    b: &CycleSegment,
+) -> Option<Point<2>> {
+    let [a0, a1] = a.segment.points();
+    let [b0, b1] = b.segment.points();
+
+    let line = Line::from_points([a0, a1]);
+
+    let shared_joint = a.shared_joint(b);
+
+    match line_segment(&line, &b.segment)? {
+        LineSegmentIntersection::PointOnLine(t) => {
+            // Segments that are not on the same line can only meet in one
+            // point. If they share a joint, that's the one.
+            let on_a = t >= Scalar::ZERO && t <= Scalar::ONE;
+            (on_a && shared_joint.is_none())
+                .then(|| line.point_from_line_coords([t]))
+        }
+        LineSegmentIntersection::Coincident => {
+            // The segments are on the same line. They intersect, if their
+            // ranges on that line overlap.
+            match shared_joint {
+                // Folding back means that the other end of `b` lies on the
+                // same side of the shared joint as `a`. The end of the overlap
+                // is reported.
+                Some([i, j]) => {
+                    let t =
+                        line.point_to_line_coords(b.segment.points()[1 - j]).t;
+
+                    let folds_back = if i == 0 {
+                        t > Scalar::ZERO
+                    } else {
+                        t < Scalar::ONE
+                    };
+                    folds_back.then(|| {
+                        line.point_from_line_coords([t
+                            .max(Scalar::ZERO)
+                            .min(Scalar::ONE)])
+                    })
+                }
+                None => {
+                    let [t0, t1] = [b0, b1]
+                        .map(|point| line.point_to_line_coords(point).t);
+
+                    let start = t0.min(t1).max(Scalar::ZERO);
+                    let end = t0.max(t1).min(Scalar::ONE);
+
+                    (start <= end).then(|| line.point_from_line_coords([start]))
+                }
+            }
+        }
+    }
+}
+
+fn coords(point: Point<2>) -> [f64; 2] {
+    point.coords.components.map(Scalar::into_f64)
+}
+
 /// Geometric issues found during validation
 ///
 /// Used by [`ValidationError`].
@@ -51,6 +355,12 @@ pub fn validate_edge(
 pub struct GeometricIssues {
     /// Mismatches between the local and canonical forms of edge vertices
     pub edge_vertex_mismatches: Vec<EdgeVertexMismatch>,
+
+    /// Points where a cycle of a face intersects itself
+    pub cycle_self_intersections: Vec<CycleSelfIntersection>,
+
+    /// Points where cycles of the same face intersect each other
+    pub cycle_overlaps: Vec<CycleOverlap>,
 }
 
 impl fmt::Display for GeometricIssues {
@@ -64,6 +374,20 @@ impl fmt::Display for GeometricIssues {
                 writeln!(f, "  - {}", mismatch)?;
             }
         }
+        if !self.cycle_self_intersections.is_empty() {
+            writeln!(f, "- Self-intersecting cycles:")?;
+
+            for intersection in &self.cycle_self_intersections {
+                writeln!(f, "  - {}", intersection)?;
+            }
+        }
+        if !self.cycle_overlaps.is_empty() {
+            writeln!(f, "- Overlapping cycles:")?;
+
+            for overlap in &self.cycle_overlaps {
+                writeln!(f, "  - {}", overlap)?;
+            }
+        }
 
         Ok(())
     }
@@ -95,13 +419,53 @@ impl fmt::Display for EdgeVertexMismatch {
     }
 }
 
+/// A point where a cycle of a face intersects itself
+#[derive(Debug)]
+pub struct CycleSelfIntersection {
+    /// The cycle that intersects itself
+    pub cycle: Handle<Cycle<3>>,
+
+    /// The point of the intersection
+    pub point: Point<3>,
+}
+
+impl fmt::Display for CycleSelfIntersection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cycle: {:?}, at: {:?}", self.cycle.get(), self.point)
+    }
+}
+
+/// A point where two cycles of the same face intersect
+#[derive(Debug)]
+pub struct CycleOverlap {
+    /// The cycles that intersect
+    pub cycles: [Handle<Cycle<3>>; 2],
+
+    /// The point of the intersection
+    pub point: Point<3>,
+}
+
+impl fmt::Display for CycleOverlap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b] = &self.cycles;
+        write!(
+            f,
+            "cycles: {:?} and {:?}, at: {:?}",
+            a.get(),
+            b.get(),
+            self.point
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use fj_math::Scalar;
+    use fj_math::{Point, Scalar};
 
     use crate::{
-        shape::{LocalForm, Shape},
-        topology::Edge,
+        geometry::Surface,
+        shape::{LocalForm, Shape, ValidationError},
+        topology::{Edge, Face},
     };
 
     #[test]
@@ -129,4 +493,142 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn validate_face() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+
+        let square = [[0., 0., 0.], [2., 0., 0.], [2., 2., 0.], [0., 2., 0.]];
+
+        // A square with a hole in it. Should work.
+        Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon(square)
+            .with_interior_polygon([
+                [0.5, 0.5, 0.],
+                [1.5, 0.5, 0.],
+                [1.5, 1.5, 0.],
+                [0.5, 1.5, 0.],
+            ])
+            .build()?;
+
+        // A bow tie, whose edges cross in the middle.
+        let result = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([
+                [0., 0., 0.],
+                [1., 1., 0.],
+                [1., 0., 0.],
+                [0., 1., 0.],
+            ])
+            .build();
+        match result {
+            Err(ValidationError::Geometric(issues)) => {
+                let points: Vec<_> = issues
+                    .cycle_self_intersections
+                    .iter()
+                    .map(|intersection| intersection.point)
+                    .collect();
+                assert_eq!(points, [Point::from([0.5, 0.5, 0.])]);
+                assert!(issues.cycle_overlaps.is_empty());
+            }
+            result => panic!("Expected geometric issues, got {:?}", result),
+        }
+
+        // A hole that reaches outside of the face.
+        let result = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon(square)
+            .with_interior_polygon([
+                [1., 1., 0.],
+                [3., 1., 0.],
+                [3., 1.5, 0.],
+                [1., 1.5, 0.],
+            ])
+            .build();
+        match result {
+            Err(ValidationError::Geometric(issues)) => {
+                let mut points: Vec<_> = issues
+                    .cycle_overlaps
+                    .iter()
+                    .map(|overlap| overlap.point)
+                    .collect();
+                points.sort();
+                assert_eq!(
+                    points,
+                    [Point::from([2., 1., 0.]), Point::from([2., 1.5, 0.])]
+                );
+                assert!(issues.cycle_self_intersections.is_empty());
+            }
+            result => panic!("Expected geometric issues, got {:?}", result),
+        }
+
+        // A hole that touches the outside of the face in a shared vertex.
+        let result = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon(square)
+            .with_interior_polygon([[0., 0., 0.], [1., 0.5, 0.], [0.5, 1., 0.]])
+            .build();
+        match result {
+            Err(ValidationError::Geometric(issues)) => {
+                let points: Vec<_> = issues
+                    .cycle_overlaps
+                    .iter()
+                    .map(|overlap| overlap.point)
+                    .collect();
+                assert_eq!(points, [Point::from([0., 0., 0.])]);
+                assert!(issues.cycle_self_intersections.is_empty());
+            }
+            result => panic!("Expected geometric issues, got {:?}", result),
+        }
+
+        // A figure eight, which touches itself in a shared vertex.
+        let result = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([
+                [0., 0., 0.],
+                [1., 1., 0.],
+                [2., 0., 0.],
+                [2., 2., 0.],
+                [1., 1., 0.],
+                [0., 2., 0.],
+            ])
+            .build();
+        match result {
+            Err(ValidationError::Geometric(issues)) => {
+                let points: Vec<_> = issues
+                    .cycle_self_intersections
+                    .iter()
+                    .map(|intersection| intersection.point)
+                    .collect();
+                assert_eq!(points, [Point::from([1., 1., 0.])]);
+                assert!(issues.cycle_overlaps.is_empty());
+            }
+            result => panic!("Expected geometric issues, got {:?}", result),
+        }
+
+        // Cycles with an edge that folds back onto the previous one.
+        for (polygon, expected) in [
+            (
+                vec![[0., 0., 0.], [2., 0., 0.], [1., 0., 0.], [1., 1., 0.]],
+                [1., 0., 0.],
+            ),
+            (vec![[0., 0., 0.], [2., 0., 0.]], [2., 0., 0.]),
+        ] {
+            let result = Face::builder(Surface::xy_plane(), &mut shape)
+                .with_exterior_polygon(polygon)
+                .build();
+            match result {
+                Err(ValidationError::Geometric(issues)) => {
+                    let points: Vec<_> = issues
+                        .cycle_self_intersections
+                        .iter()
+                        .map(|intersection| intersection.point)
+                        .collect();
+                    assert_eq!(points, [Point::from(expected)]);
+                    assert!(issues.cycle_overlaps.is_empty());
+                }
+                result => {
+                    panic!("Expected geometric issues, got {:?}", result)
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,695 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    f64::consts::TAU,
+};
+
+use fj_interop::mesh::{Color, ManifoldIssues};
+use fj_math::{Point, Scalar, Triangle, Vector};
+
+use crate::{
+    geometry::{Curve, Surface},
+    shape::Handle,
+    topology::{Cycle, Edge, Face, Shell, Vertex},
+};
+
+/// The number of segments that each edge is sampled with
+///
+/// The samples are used to determine the orientation of cycles, and to tell
+/// apart edges that connect the same vertices. This needs to be even, so the
+/// middle sample is the same, regardless of the direction of traversal.
+const SAMPLES: usize = 8;
+
+pub fn validate_shell(
+    shell: &Shell,
+    min_distance: Scalar,
+) -> Result<(), ManifoldIssues<ShellEdge, Handle<Face>>> {
+    let mut issues = ManifoldIssues::default();
+
+    let mut uses = Vec::new();
+    let mut segments = Vec::new();
+    for handle in &shell.faces {
+        match handle.get() {
+            Face::Face(face) => {
+                let surface = face.surface();
+
+                for cycle in face.exteriors() {
+                    uses.extend(edge_uses(handle, &cycle, &surface, true));
+                }
+                for cycle in face.interiors() {
+                    uses.extend(edge_uses(handle, &cycle, &surface, false));
+                }
+            }
+            Face::Triangles(triangles) => {
+                segments.extend(boundary_segments(
+                    handle,
+                    &triangles,
+                    &mut issues,
+                ));
+            }
+        }
+    }
+
+    // Group the uses by the edge they refer to. Edges are bucketed by their
+    // vertices first, to keep the number of comparisons down.
+    let mut buckets: HashMap<_, Vec<Vec<EdgeUse>>> = HashMap::new();
+    for edge_use in uses {
+        let groups = buckets.entry(edge_use.vertices.clone()).or_default();
+
+        match groups
+            .iter_mut()
+            .find(|group| group[0].is_same_edge(&edge_use, min_distance))
+        {
+            Some(group) => group.push(edge_use),
+            None => groups.push(vec![edge_use]),
+        }
+    }
+    let groups: Vec<_> = buckets.into_values().flatten().collect();
+
+    // Faces in triangle representation don't refer to any edges. The segments
+    // of their boundaries are matched to the edges of the other faces by their
+    // geometry instead. Each face that covers an edge counts as one use, with
+    // the direction of its segment closest to the middle of the edge.
+    let mut triangle_uses: Vec<Vec<(Handle<Face>, Vector<3>, Scalar)>> =
+        groups.iter().map(|_| Vec::new()).collect();
+    let mut unmatched: BTreeMap<[Point<3>; 2], Vec<(Handle<Face>, bool)>> =
+        BTreeMap::new();
+
+    for (face, [a, b]) in segments {
+        let group = groups
+            .iter()
+            .position(|group| group[0].covers([a, b], min_distance));
+
+        match group {
+            Some(i) => {
+                let tangent = b - a;
+                let distance =
+                    (a + tangent / 2. - groups[i][0].midpoint).magnitude();
+
+                let uses = &mut triangle_uses[i];
+                match uses.iter_mut().find(|(f, _, _)| f == &face) {
+                    Some(closest) => {
+                        if distance < closest.2 {
+                            *closest = (face, tangent, distance);
+                        }
+                    }
+                    None => uses.push((face, tangent, distance)),
+                }
+            }
+            None => {
+                // Boundary segments that don't lie on any edge must be shared
+                // with another face in triangle representation.
+                unmatched
+                    .entry(if a < b { [a, b] } else { [b, a] })
+                    .or_default()
+                    .push((face, a < b));
+            }
+        }
+    }
+
+    for (group, triangle_uses) in groups.into_iter().zip(triangle_uses) {
+        let faces: Vec<_> = group
+            .iter()
+            .map(|edge_use| (edge_use.face.clone(), edge_use.tangent))
+            .chain(
+                triangle_uses
+                    .into_iter()
+                    .map(|(face, tangent, _)| (face, tangent)),
+            )
+            .collect();
+
+        match faces.as_slice() {
+            [_] => {
+                issues
+                    .open_edges
+                    .push(ShellEdge::Edge(group[0].edge.clone()));
+            }
+            [(a, a_tangent), (b, b_tangent)] => {
+                // Faces that are oriented consistently traverse their shared
+                // edge in opposite directions.
+                if a_tangent.dot(b_tangent) >= Scalar::ZERO {
+                    issues.inconsistent_orientations.push(sorted(a, b));
+                }
+            }
+            _ => {
+                issues.non_manifold_edges.extend(
+                    group
+                        .iter()
+                        .map(|edge_use| ShellEdge::Edge(edge_use.edge.clone())),
+                );
+            }
+        }
+    }
+
+    for (segment, faces) in unmatched {
+        match faces.as_slice() {
+            [_] => issues.open_edges.push(ShellEdge::Segment(segment)),
+            [(a, a_forward), (b, b_forward)] => {
+                if a_forward == b_forward {
+                    issues.inconsistent_orientations.push(sorted(a, b));
+                }
+            }
+            _ => issues.non_manifold_edges.push(ShellEdge::Segment(segment)),
+        }
+    }
+
+    if issues.is_empty() {
+        return Ok(());
+    }
+
+    for edges in [&mut issues.open_edges, &mut issues.non_manifold_edges] {
+        edges.sort();
+        edges.dedup();
+    }
+    issues.inconsistent_orientations.sort();
+    issues.inconsistent_orientations.dedup();
+
+    Err(issues)
+}
+
+/// An edge of a shell, as reported in [`ManifoldIssues`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum ShellEdge {
+    /// An edge of a face in boundary representation
+    Edge(Handle<Edge<3>>),
+
+    /// A segment between triangles of a face in triangle representation
+    ///
+    /// The points of the segment are sorted.
+    Segment([Point<3>; 2]),
+}
+
+/// Check the triangles of a face, and compute the boundary of the face
+///
+/// Issues between the triangles are added to `issues`. Returns the segments
+/// of the boundary, in the direction in which the triangles traverse them.
+fn boundary_segments(
+    face: &Handle<Face>,
+    triangles: &[(Triangle<3>, Color)],
+    issues: &mut ManifoldIssues<ShellEdge, Handle<Face>>,
+) -> Vec<(Handle<Face>, [Point<3>; 2])> {
+    let mut uses: BTreeMap<[Point<3>; 2], Vec<bool>> = BTreeMap::new();
+    for (triangle, _) in triangles {
+        let [a, b, c] = triangle.points();
+
+        for [from, to] in [[a, b], [b, c], [c, a]] {
+            let segment = if from < to { [from, to] } else { [to, from] };
+            uses.entry(segment).or_default().push(from < to);
+        }
+    }
+
+    let mut boundary = Vec::new();
+    for (segment, uses) in uses {
+        match uses.as_slice() {
+            [forward] => {
+                let [a, b] = segment;
+                let segment = if *forward { [a, b] } else { [b, a] };
+                boundary.push((face.clone(), segment));
+            }
+            [a_forward, b_forward] => {
+                if a_forward == b_forward {
+                    issues
+                        .inconsistent_orientations
+                        .push([face.clone(), face.clone()]);
+                }
+            }
+            _ => issues.non_manifold_edges.push(ShellEdge::Segment(segment)),
+        }
+    }
+
+    boundary
+}
+
+fn sorted(a: &Handle<Face>, b: &Handle<Face>) -> [Handle<Face>; 2] {
+    let mut faces = [a.clone(), b.clone()];
+    faces.sort();
+    faces
+}
+
+/// Compute how a face uses the edges of one of its cycles
+///
+/// The edges of a cycle aren't necessarily stored in the order in which they
+/// connect, nor do they point in the direction of the cycle. Both are
+/// reconstructed here. The direction is chosen such, that exterior cycles run
+/// counter-clockwise around the surface normal, and interior cycles clockwise.
+fn edge_uses(
+    face: &Handle<Face>,
+    cycle: &Cycle<3>,
+    surface: &Surface,
+    exterior: bool,
+) -> Vec<EdgeUse> {
+    let mut edges: Vec<_> = cycle
+        .edges
+        .iter()
+        .map(|edge| (edge.canonical(), edge.canonical().get()))
+        .collect();
+
+    let mut chain = Vec::new();
+    if !edges.is_empty() {
+        let (handle, edge) = edges.remove(0);
+        let mut end = edge.vertices.clone().map(|[_, b]| b.canonical());
+        chain.push((handle, edge, false));
+
+        while !edges.is_empty() {
+            let next = edges.iter().enumerate().find_map(|(i, (_, edge))| {
+                let [a, b] = edge.vertices.clone()?.map(|v| v.canonical());
+
+                if Some(&a) == end.as_ref() {
+                    Some((i, false, b))
+                } else if Some(&b) == end.as_ref() {
+                    Some((i, true, a))
+                } else {
+                    None
+                }
+            });
+
+            match next {
+                Some((i, reversed, next_end)) => {
+                    let (handle, edge) = edges.remove(i);
+                    chain.push((handle, edge, reversed));
+                    end = Some(next_end);
+                }
+                None => {
+                    // The edges don't form a proper cycle. That's not what
+                    // we're checking for here, so just take them as they are.
+                    chain.extend(
+                        edges
+                            .drain(..)
+                            .map(|(handle, edge)| (handle, edge, false)),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut uses = Vec::new();
+    let mut samples = Vec::new();
+    for (handle, edge, reversed) in chain {
+        let mut points = sample_edge(&edge, SAMPLES);
+        if reversed {
+            points.reverse();
+        }
+
+        let mid = SAMPLES / 2;
+        let tangent = points[mid + 1] - points[mid - 1];
+
+        let vertices = edge.vertices.clone().map(|vertices| {
+            let mut vertices = vertices.map(|vertex| vertex.canonical());
+            vertices.sort();
+            vertices
+        });
+
+        samples.extend(
+            points
+                .iter()
+                .map(|&point| surface.point_to_surface_coords(point).local()),
+        );
+        uses.push(EdgeUse {
+            face: face.clone(),
+            edge: handle,
+            curve: edge.curve(),
+            range: edge_range(&edge),
+            vertices,
+            midpoint: points[mid],
+            tangent,
+        });
+    }
+
+    if (signed_area(&samples) > Scalar::ZERO) != exterior {
+        for edge_use in &mut uses {
+            edge_use.tangent = -edge_use.tangent;
+        }
+    }
+
+    uses
+}
+
+/// Sample an edge in the direction of its curve
+///
+/// Returns `segments + 1` points, evenly spaced in curve coordinates.
+pub fn sample_edge(edge: &Edge<3>, segments: usize) -> Vec<Point<3>> {
+    let curve = edge.curve();
+    let [start, end] = edge_range(edge);
+
+    (0..=segments)
+        .map(|i| {
+            let t = start + (end - start) * i as f64 / segments as f64;
+            curve.point_from_curve_coords(Point::from([t]))
+        })
+        .collect()
+}
+
+/// Compute the range of curve coordinates that an edge covers
+fn edge_range(edge: &Edge<3>) -> [Scalar; 2] {
+    match &edge.vertices {
+        Some(vertices) => vertices.clone().map(|vertex| vertex.local().t),
+        None => match edge.curve() {
+            Curve::Circle(_) | Curve::Ellipse(_) => {
+                [Scalar::ZERO, Scalar::from_f64(TAU)]
+            }
+            Curve::Nurbs(curve) => curve.domain(),
+            // A line can't connect to itself, so this edge is invalid.
+            // There's no meaningful range to sample.
+            Curve::Line(_) => [Scalar::ZERO, Scalar::ONE],
+        },
+    }
+}
+
+/// Compute the signed area of a polygon
+///
+/// The area is positive, if the polygon is counter-clockwise.
+fn signed_area(points: &[Point<2>]) -> Scalar {
+    let mut area = Scalar::ZERO;
+
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += a.u * b.v - b.u * a.v;
+    }
+
+    area / 2.
+}
+
+/// The use of an edge by a face
+struct EdgeUse {
+    face: Handle<Face>,
+    edge: Handle<Edge<3>>,
+    curve: Curve<3>,
+
+    /// The range of curve coordinates that the edge covers
+    range: [Scalar; 2],
+
+    /// The vertices of the edge, sorted, if it has any
+    vertices: Option<[Handle<Vertex>; 2]>,
+
+    /// The point in the middle of the edge
+    midpoint: Point<3>,
+
+    /// The direction in which the face traverses the edge, at its middle
+    tangent: Vector<3>,
+}
+
+impl EdgeUse {
+    /// Determine whether two uses refer to the same edge
+    ///
+    /// Neighboring faces don't necessarily refer to the same edge object, if
+    /// they were built independently. Edges that connect the same vertices are
+    /// considered identical, if they run through the same point in their
+    /// middle. Edges without vertices are considered identical, if they have
+    /// the same curve, regardless of its direction.
+    fn is_same_edge(&self, other: &Self, min_distance: Scalar) -> bool {
+        if self.edge == other.edge {
+            return true;
+        }
+
+        if self.vertices != other.vertices {
+            return false;
+        }
+
+        if self.vertices.is_some() {
+            (self.midpoint - other.midpoint).magnitude() < min_distance
+        } else {
+            self.curve == other.curve
+                || self.curve == other.curve.clone().reverse()
+        }
+    }
+
+    /// Determine whether a segment lies on the edge
+    ///
+    /// This is the case, if both of its points are on the edge. The segment is
+    /// expected to approximate the edge, so the part of the edge between its
+    /// points is not checked.
+    fn covers(&self, segment: [Point<3>; 2], min_distance: Scalar) -> bool {
+        segment.into_iter().all(|point| {
+            let t = self.curve.point_to_curve_coords(point).local().t;
+            let on_curve = (self.curve.point_from_curve_coords([t]) - point)
+                .magnitude()
+                < min_distance;
+
+            // The coordinates of closed curves are only unique up to a full
+            // turn.
+            let candidates = match self.curve {
+                Curve::Circle(_) | Curve::Ellipse(_) => {
+                    let turn = Scalar::from_f64(TAU);
+                    vec![t - turn, t, t + turn]
+                }
+                Curve::Line(_) | Curve::Nurbs(_) => vec![t],
+            };
+
+            let [a, b] = self.range;
+            let [min, max] = if a <= b { [a, b] } else { [b, a] };
+
+            on_curve
+                && candidates
+                    .into_iter()
+                    .any(|t| t >= min - min_distance && t <= max + min_distance)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{
+        debug::DebugInfo,
+        mesh::{ManifoldIssues, Mesh},
+    };
+    use fj_math::{Point, Scalar, Triangle, Vector};
+
+    use crate::{
+        algorithms::{sweep_shape, triangulate, Tolerance},
+        geometry::Surface,
+        shape::{Handle, Shape, ShellEdge, ValidationError},
+        topology::{Cycle, Edge, Face, Shell},
+    };
+
+    #[test]
+    fn closed_shell() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        Shell::builder(&mut shape).build_cube_from_edge_size(Scalar::ONE)?;
+
+        shape.validate_manifold()?;
+        triangulate_shape(&shape)?.validate_manifold()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_shell() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        let mut faces = cube_faces(&mut shape)?;
+
+        faces.pop();
+        shape.insert(Shell::new(faces))?;
+
+        let issues = manifold_issues(&shape);
+        assert_eq!(issues.open_edges.len(), 4);
+        assert!(issues.non_manifold_edges.is_empty());
+        assert!(issues.inconsistent_orientations.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_manifold_edge() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        let mut faces = cube_faces(&mut shape)?;
+
+        // A fin that sticks out of the cube's bottom edge along the x axis.
+        let fin = Face::builder(Surface::xy_plane(), &mut shape)
+            .with_exterior_polygon([
+                [-0.5, -0.5, -0.5],
+                [0.5, -0.5, -0.5],
+                [0., -1.5, -0.5],
+            ])
+            .build()?;
+        faces.push(fin);
+        shape.insert(Shell::new(faces))?;
+
+        let issues = manifold_issues(&shape);
+        assert_eq!(issues.non_manifold_edges.len(), 3);
+        assert_eq!(issues.open_edges.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn inconsistent_orientation() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        let mut faces = cube_faces(&mut shape)?;
+
+        let face = faces.remove(0).get();
+        let surface = shape.insert(face.surface().reverse())?;
+        let flipped = shape.insert(Face::new(
+            surface,
+            face.brep().exteriors.as_handle(),
+            face.brep().interiors.as_handle(),
+            face.brep().color,
+        ))?;
+        faces.push(flipped.clone());
+        shape.insert(Shell::new(faces))?;
+
+        let issues = manifold_issues(&shape);
+        assert!(issues.open_edges.is_empty());
+        assert!(issues.non_manifold_edges.is_empty());
+        assert_eq!(issues.inconsistent_orientations.len(), 4);
+        assert!(issues
+            .inconsistent_orientations
+            .iter()
+            .all(|faces| faces.contains(&flipped)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mesh() {
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        let tetrahedron = [[a, c, b], [a, b, d], [b, c, d], [c, a, d]];
+
+        let mut mesh = Mesh::new();
+        for triangle in tetrahedron {
+            mesh.push_triangle(triangle, [255, 0, 0, 255]);
+        }
+        assert!(mesh.validate_manifold().is_ok());
+
+        let mut mesh = Mesh::new();
+        for triangle in &tetrahedron[..3] {
+            mesh.push_triangle(*triangle, [255, 0, 0, 255]);
+        }
+        mesh.push_triangle([c, d, a], [255, 0, 0, 255]);
+
+        let issues = mesh.validate_manifold().unwrap_err();
+        assert!(issues.open_edges.is_empty());
+        assert!(issues.non_manifold_edges.is_empty());
+        assert_eq!(issues.inconsistent_orientations, [[0, 3], [1, 3], [2, 3]]);
+    }
+
+    #[test]
+    fn swept_cylinder() -> anyhow::Result<()> {
+        // Sweeping downwards must result in a valid shape too.
+        cylinder(-2.)?.validate_manifold()?;
+
+        let shape = cylinder(2.)?;
+
+        // The side face is in triangle representation. Its boundary must be
+        // matched to the circles of the top and bottom faces.
+        let shell = shape.shells().next().unwrap().get();
+        assert!(shell.faces().any(|face| matches!(face, Face::Triangles(_))));
+        shape.validate_manifold()?;
+
+        // Without its top or bottom, the cylinder is open along the side face.
+        let mut faces = shell.faces;
+        let cap = faces
+            .iter()
+            .rposition(|face| matches!(face.get(), Face::Face(_)))
+            .unwrap();
+        faces.remove(cap);
+
+        let mut open = shape.clone();
+        open.insert(Shell::new(faces))?;
+
+        let issues = manifold_issues(&open);
+        assert!(!issues.open_edges.is_empty());
+        assert!(issues
+            .open_edges
+            .iter()
+            .all(|edge| matches!(edge, ShellEdge::Segment(_))));
+        assert!(issues.non_manifold_edges.is_empty());
+        assert!(issues.inconsistent_orientations.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_mesh() -> anyhow::Result<()> {
+        let a = Point::from([0., 0., 0.]);
+        let b = Point::from([1., 0., 0.]);
+        let c = Point::from([0., 1., 0.]);
+        let d = Point::from([0., 0., 1.]);
+
+        let tetrahedron = [[a, c, b], [a, b, d], [b, c, d], [c, a, d]];
+        let mesh = |triangles: &[[Point<3>; 3]]| -> anyhow::Result<Shape> {
+            let mut shape = Shape::new();
+            let face = shape.insert(Face::Triangles(
+                triangles
+                    .iter()
+                    .map(|&points| {
+                        (Triangle::from_points(points), [255, 0, 0, 255])
+                    })
+                    .collect(),
+            ))?;
+            shape.insert(Shell::new([face]))?;
+
+            Ok(shape)
+        };
+
+        mesh(&tetrahedron)?.validate_manifold()?;
+
+        let issues = manifold_issues(&mesh(&tetrahedron[..3])?);
+        assert_eq!(
+            issues.open_edges,
+            [
+                ShellEdge::Segment([a, d]),
+                ShellEdge::Segment([a, c]),
+                ShellEdge::Segment([d, c]),
+            ]
+        );
+        assert!(issues.non_manifold_edges.is_empty());
+        assert!(issues.inconsistent_orientations.is_empty());
+
+        let mut flipped = tetrahedron;
+        flipped[3] = [c, d, a];
+        let issues = manifold_issues(&mesh(&flipped)?);
+        assert!(issues.open_edges.is_empty());
+        assert_eq!(issues.inconsistent_orientations.len(), 1);
+
+        Ok(())
+    }
+
+    fn cylinder(height: f64) -> anyhow::Result<Shape> {
+        let mut sketch = Shape::new();
+        let circle = Edge::builder(&mut sketch).build_circle(Scalar::ONE)?;
+        let cycle = sketch.insert(Cycle::new([circle]))?;
+        let surface = sketch.insert(Surface::xy_plane())?;
+        sketch.insert(Face::new(surface, [cycle], [], [255, 0, 0, 255]))?;
+
+        let shape = sweep_shape(
+            sketch,
+            Vector::from([0., 0., height]),
+            Tolerance::from_scalar(0.01)?,
+            [255, 0, 0, 255],
+        )?;
+
+        Ok(shape)
+    }
+
+    fn cube_faces(shape: &mut Shape) -> anyhow::Result<Vec<Handle<Face>>> {
+        let mut cube = Shape::new();
+        let shell =
+            Shell::builder(&mut cube).build_cube_from_edge_size(Scalar::ONE)?;
+
+        let mut faces = Vec::new();
+        for face in shell.get().faces {
+            faces.push(shape.merge(face.get())?);
+        }
+
+        Ok(faces)
+    }
+
+    fn manifold_issues(
+        shape: &Shape,
+    ) -> ManifoldIssues<ShellEdge, Handle<Face>> {
+        match shape.validate_manifold() {
+            Err(ValidationError::Manifold(issues)) => issues,
+            result => panic!("Expected manifold issues, got {:?}", result),
+        }
+    }
+
+    fn triangulate_shape(shape: &Shape) -> anyhow::Result<Mesh<Point<3>>> {
+        let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
+        let mesh = triangulate(shape.clone(), tolerance, &mut DebugInfo::new());
+
+        Ok(mesh)
+    }
+}
@@ -1,9 +1,16 @@
 mod geometric;
+mod manifold;
+mod solid;
 mod structural;
 mod uniqueness;
 
+pub use fj_interop::mesh::ManifoldIssues;
+
 pub use self::{
-    geometric::GeometricIssues, structural::StructuralIssues,
+    geometric::{CycleOverlap, CycleSelfIntersection, GeometricIssues},
+    manifold::ShellEdge,
+    solid::SolidIssues,
+    structural::StructuralIssues,
     uniqueness::UniquenessIssues,
 };
 
@@ -11,17 +18,106 @@ use fj_math::{Point, Scalar};
 
 use crate::{
     geometry::{Curve, Surface},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
+};
+
+use super::{
+    stores::{Store, Stores},
+    Handle, Object,
 };
 
-use super::{stores::Stores, Handle, Object};
+/// The validation that is done, when objects are inserted into a [`Shape`]
+///
+/// [`Shape`]: super::Shape
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ValidationLevel {
+    /// No validation is done
+    ///
+    /// Only use this, if the validity of the shape is guaranteed by other
+    /// means.
+    Off,
+
+    /// Only structural validation is done
+    ///
+    /// This is much cheaper than full validation, as it doesn't need to
+    /// compare an object against all other objects in the shape.
+    Structural,
+
+    /// All validation is done
+    Full,
+
+    /// No validation is done on insertion
+    ///
+    /// Full validation is deferred until [`Shape::validate_all`] is called.
+    ///
+    /// [`Shape::validate_all`]: super::Shape::validate_all
+    Deferred,
+}
+
+impl ValidationLevel {
+    /// Indicate whether objects are validated when they are inserted
+    pub fn validates_on_insert(&self) -> bool {
+        matches!(self, Self::Structural | Self::Full)
+    }
+}
+
+impl Default for ValidationLevel {
+    fn default() -> Self {
+        Self::Full
+    }
+}
+
+/// Configuration of the validation of a [`Shape`]
+///
+/// [`Shape`]: super::Shape
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ValidationConfig {
+    /// The validation that is done when objects are inserted
+    pub level: ValidationLevel,
+
+    /// The minimum distance between distinct objects
+    ///
+    /// Used for vertex validation, to determine whether vertices are unique.
+    pub distinct_min_distance: Scalar,
+
+    /// The maximum distance between objects considered identical
+    ///
+    /// Used for geometric validation.
+    pub identical_max_distance: Scalar,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            level: ValidationLevel::default(),
+
+            // This should really come from `Self::DEFAULT_MIN_DISTANCE`, or a
+            // similarly named constant. Unfortunately `Scalar::from_f64` can't
+            // be `const` yet.
+            distinct_min_distance: Scalar::from_f64(5e-7), // 0.5 µm
+
+            // This value was chosen pretty arbitrarily. Seems small enough to
+            // catch errors. If it turns out it's too small (because it produces
+            // false positives due to floating-point accuracy issues), we can
+            // adjust it.
+            //
+            // This should be defined in an associated constant, so API users
+            // can see what the default is. Unfortunately, `Scalar::from_f64`
+            // can't be `const` yet.
+            identical_max_distance: Scalar::from_f64(5e-14),
+        }
+    }
+}
 
 pub trait Validate {
+    /// Validate the object
+    ///
+    /// Structural validation is always done. Everything else is only done, if
+    /// the validation level of `config` is [`ValidationLevel::Full`].
     fn validate(
         &self,
         handle: Option<&Handle<Self>>,
-        min_distance: Scalar,
-        max_distance: Scalar,
+        config: &ValidationConfig,
         stores: &Stores,
     ) -> Result<(), ValidationError>
     where
@@ -32,8 +128,7 @@ impl Validate for Point<3> {
     fn validate(
         &self,
         _: Option<&Handle<Self>>,
-        _: Scalar,
-        _: Scalar,
+        _: &ValidationConfig,
         _: &Stores,
     ) -> Result<(), ValidationError> {
         Ok(())
@@ -44,8 +139,7 @@ impl Validate for Curve<3> {
     fn validate(
         &self,
         _: Option<&Handle<Self>>,
-        _: Scalar,
-        _: Scalar,
+        _: &ValidationConfig,
         _: &Stores,
     ) -> Result<(), ValidationError> {
         Ok(())
@@ -56,8 +150,7 @@ impl Validate for Surface {
     fn validate(
         &self,
         _: Option<&Handle<Self>>,
-        _: Scalar,
-        _: Scalar,
+        _: &ValidationConfig,
         _: &Stores,
     ) -> Result<(), ValidationError> {
         Ok(())
@@ -74,12 +167,18 @@ impl Validate for Vertex {
     fn validate(
         &self,
         handle: Option<&Handle<Self>>,
-        min_distance: Scalar,
-        _: Scalar,
+        config: &ValidationConfig,
         stores: &Stores,
     ) -> Result<(), ValidationError> {
         structural::validate_vertex(self, stores)?;
-        uniqueness::validate_vertex(self, handle, min_distance, stores)?;
+        if config.level == ValidationLevel::Full {
+            uniqueness::validate_vertex(
+                self,
+                handle,
+                config.distinct_min_distance,
+                stores,
+            )?;
+        }
 
         Ok(())
     }
@@ -89,11 +188,12 @@ impl Validate for Edge<3> {
     fn validate(
         &self,
         _: Option<&Handle<Self>>,
-        _: Scalar,
-        max_distance: Scalar,
+        config: &ValidationConfig,
         stores: &Stores,
     ) -> Result<(), ValidationError> {
-        geometric::validate_edge(self, max_distance)?;
+        if config.level == ValidationLevel::Full {
+            geometric::validate_edge(self, config.identical_max_distance)?;
+        }
         structural::validate_edge(self, stores)?;
 
         Ok(())
@@ -105,15 +205,18 @@ impl Validate for Cycle<3> {
     ///
     /// # Implementation note
     ///
+    /// Whether the cycle intersects itself can only be determined in the
+    /// surface of a face, so that is validated as part of the face. Edges that
+    /// fold back onto their neighbor count as an intersection, as do vertices
+    /// that connect more than two edges of the cycle.
+    ///
     /// The validation of the cycle should be extended to cover more cases:
     /// - That those edges form a cycle.
-    /// - That the cycle is not self-overlapping.
     /// - That there exists no duplicate cycle, with the same edges.
     fn validate(
         &self,
         _: Option<&Handle<Self>>,
-        _: Scalar,
-        _: Scalar,
+        _: &ValidationConfig,
         stores: &Stores,
     ) -> Result<(), ValidationError> {
         structural::validate_cycle(self, stores)?;
@@ -125,15 +228,97 @@ impl Validate for Face {
     fn validate(
         &self,
         _: Option<&Handle<Self>>,
-        _: Scalar,
-        _: Scalar,
+        config: &ValidationConfig,
         stores: &Stores,
     ) -> Result<(), ValidationError> {
         structural::validate_face(self, stores)?;
+        if config.level == ValidationLevel::Full {
+            geometric::validate_face(self)?;
+        }
+        Ok(())
+    }
+}
+
+impl Validate for Shell {
+    fn validate(
+        &self,
+        _: Option<&Handle<Self>>,
+        _: &ValidationConfig,
+        stores: &Stores,
+    ) -> Result<(), ValidationError> {
+        structural::validate_shell(self, stores)?;
+        Ok(())
+    }
+}
+
+impl Validate for Solid {
+    fn validate(
+        &self,
+        _: Option<&Handle<Self>>,
+        _: &ValidationConfig,
+        stores: &Stores,
+    ) -> Result<(), ValidationError> {
+        structural::validate_solid(self, stores)?;
         Ok(())
     }
 }
 
+/// Validate all objects in the provided stores
+///
+/// Validating every single object is certainly not ideal from a performance
+/// perspective, but it will do for now.
+pub fn validate_stores(
+    stores: &Stores,
+    config: &ValidationConfig,
+) -> Result<(), ValidationError> {
+    validate_store(&stores.points, config, stores)?;
+    validate_store(&stores.curves, config, stores)?;
+    validate_store(&stores.surfaces, config, stores)?;
+    validate_store(&stores.vertices, config, stores)?;
+    validate_store(&stores.edges, config, stores)?;
+    validate_store(&stores.cycles, config, stores)?;
+    validate_store(&stores.faces, config, stores)?;
+    validate_store(&stores.shells, config, stores)?;
+    validate_store(&stores.solids, config, stores)?;
+
+    Ok(())
+}
+
+fn validate_store<T: Object>(
+    store: &Store<T>,
+    config: &ValidationConfig,
+    stores: &Stores,
+) -> Result<(), ValidationError> {
+    for object in store.iter() {
+        object.get().validate(Some(&object), config, stores)?;
+    }
+
+    Ok(())
+}
+
+/// Validate that a shell is a closed manifold
+///
+/// See [`Shape::validate_manifold`].
+///
+/// [`Shape::validate_manifold`]: super::Shape::validate_manifold
+pub fn validate_manifold(
+    shell: &Shell,
+    min_distance: Scalar,
+) -> Result<(), ValidationError> {
+    manifold::validate_shell(shell, min_distance)?;
+    Ok(())
+}
+
+/// Validate that the shells of a solid bound it from the correct side
+///
+/// See [`Shape::validate_manifold`].
+///
+/// [`Shape::validate_manifold`]: super::Shape::validate_manifold
+pub fn validate_solid(solid: &Solid) -> Result<(), ValidationError> {
+    solid::validate_solid(solid)?;
+    Ok(())
+}
+
 /// Returned by the various `add_` methods of the [`Shape`] API
 pub type ValidationResult<T> = Result<Handle<T>, ValidationError>;
 
@@ -148,6 +333,22 @@ pub enum ValidationError {
     #[error("Geometric validation failed")]
     Geometric(#[from] GeometricIssues),
 
+    /// Manifold validation failed
+    ///
+    /// Manifold validation checks, that the shells of a shape are closed. Every
+    /// edge must be shared by exactly two faces of a shell, and those faces
+    /// must be oriented consistently.
+    #[error("Manifold validation failed")]
+    Manifold(#[from] ManifoldIssues<ShellEdge, Handle<Face>>),
+
+    /// Solid validation failed
+    ///
+    /// Solid validation checks, that the shells of a solid bound it from the
+    /// correct side. The faces of the exterior shell must point outward, those
+    /// of the interior shells into the cavities they define.
+    #[error("Solid validation failed")]
+    Solid(#[from] SolidIssues),
+
     /// Structural validation failed
     ///
     /// Structural validation verifies, that all the object that an object
@@ -221,4 +422,25 @@ impl ValidationError {
 
         false
     }
+
+    /// Indicate whether validation found a missing face
+    #[cfg(test)]
+    pub fn missing_face(&self, face: &Handle<Face>) -> bool {
+        if let Self::Structural(StructuralIssues { missing_faces, .. }) = self {
+            return missing_faces.contains(face);
+        }
+
+        false
+    }
+
+    /// Indicate whether validation found a missing shell
+    #[cfg(test)]
+    pub fn missing_shell(&self, shell: &Handle<Shell>) -> bool {
+        if let Self::Structural(StructuralIssues { missing_shells, .. }) = self
+        {
+            return missing_shells.contains(shell);
+        }
+
+        false
+    }
 }
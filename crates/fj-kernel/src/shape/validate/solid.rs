@@ -0,0 +1,187 @@
+use std::fmt;
+
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_math::{Aabb, Point, Scalar};
+
+use crate::{
+    algorithms::{triangulate_face, Tolerance},
+    shape::Handle,
+    topology::{Face, Shell, Solid},
+};
+
+use super::manifold::sample_edge;
+
+/// The tolerance used to approximate shells, relative to their size
+///
+/// Only the sign of the enclosed volume is of interest, so a coarse
+/// approximation is good enough.
+const RELATIVE_TOLERANCE: f64 = 0.001;
+
+pub fn validate_solid(solid: &Solid) -> Result<(), SolidIssues> {
+    let mut inverted_shells = Vec::new();
+
+    if signed_volume(&solid.exterior()) <= Scalar::ZERO {
+        inverted_shells.push(solid.exterior.clone());
+    }
+    for (shell, handle) in solid.interiors().zip(&solid.interiors) {
+        if signed_volume(&shell) >= Scalar::ZERO {
+            inverted_shells.push(handle.clone());
+        }
+    }
+
+    if !inverted_shells.is_empty() {
+        return Err(SolidIssues { inverted_shells });
+    }
+
+    Ok(())
+}
+
+/// Compute the volume that a closed shell encloses
+///
+/// The volume is positive, if the faces of the shell point outward, and
+/// negative, if they point inward.
+fn signed_volume(shell: &Shell) -> Scalar {
+    let mut points = Vec::new();
+    for face in shell.faces() {
+        match face {
+            Face::Face(face) => {
+                for cycle in face.all_cycles() {
+                    for edge in cycle.edges() {
+                        points.extend(sample_edge(&edge, 2));
+                    }
+                }
+            }
+            Face::Triangles(triangles) => {
+                for (triangle, _) in triangles {
+                    points.extend(triangle.points());
+                }
+            }
+        }
+    }
+
+    let size = Aabb::<3>::from_points(points).size().magnitude();
+    let tolerance = match Tolerance::from_scalar(size * RELATIVE_TOLERANCE) {
+        Ok(tolerance) => tolerance,
+        // The shell doesn't have any extent, so it can't enclose a volume.
+        Err(_) => return Scalar::ZERO,
+    };
+
+    let mut mesh = Mesh::new();
+    for face in shell.faces() {
+        triangulate_face(&face, tolerance, &mut DebugInfo::new(), &mut mesh);
+    }
+
+    // Each triangle spans a tetrahedron with the origin. The signed volumes of
+    // those add up to the volume enclosed by the mesh.
+    let mut volume = Scalar::ZERO;
+    for triangle in mesh.triangles() {
+        let [a, b, c] = triangle.points.map(|point| point - Point::origin());
+        volume += a.dot(&b.cross(&c)) / 6.;
+    }
+
+    volume
+}
+
+/// Issues with the shells of a solid, found during validation
+///
+/// Used by [`ValidationError`].
+///
+/// [`ValidationError`]: super::ValidationError
+#[derive(Debug, Default, thiserror::Error)]
+pub struct SolidIssues {
+    /// Shells that bound the solid from the wrong side
+    ///
+    /// The faces of these shells point into the solid instead of away from
+    /// it.
+    pub inverted_shells: Vec<Handle<Shell>>,
+}
+
+impl fmt::Display for SolidIssues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Solid issues found:")?;
+
+        if !self.inverted_shells.is_empty() {
+            writeln!(f, "- Inverted shells:")?;
+
+            for shell in &self.inverted_shells {
+                writeln!(f, "  - {:?}", shell.get())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        shape::{Handle, Shape, ValidationError},
+        topology::{Face, Shell, Solid},
+    };
+
+    #[test]
+    fn exterior_shell() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        let cube = Shell::builder(&mut shape)
+            .build_cube_from_edge_size(Scalar::from(2.))?;
+        shape.insert(Solid::new(cube, []))?;
+        shape.validate_manifold()?;
+
+        let mut shape = Shape::new();
+        let cube = Shell::builder(&mut shape)
+            .build_cube_from_edge_size(Scalar::from(2.))?;
+        let inverted = flip(&cube, &mut shape)?;
+        shape.insert(Solid::new(inverted.clone(), []))?;
+        assert_eq!(inverted_shells(&shape), [inverted]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn interior_shell() -> anyhow::Result<()> {
+        let mut shape = Shape::new();
+        let exterior = Shell::builder(&mut shape)
+            .build_cube_from_edge_size(Scalar::from(2.))?;
+        let interior = Shell::builder(&mut shape)
+            .build_cube_from_edge_size(Scalar::ONE)?;
+        let cavity = flip(&interior, &mut shape)?;
+
+        let mut valid = shape.clone();
+        valid.insert(Solid::new(exterior.clone(), [cavity]))?;
+        valid.validate_manifold()?;
+
+        // The faces of the interior shell point away from the cavity.
+        shape.insert(Solid::new(exterior, [interior.clone()]))?;
+        assert_eq!(inverted_shells(&shape), [interior]);
+
+        Ok(())
+    }
+
+    /// Create a copy of a shell, whose faces point the other way
+    fn flip(
+        shell: &Handle<Shell>,
+        shape: &mut Shape,
+    ) -> anyhow::Result<Handle<Shell>> {
+        let mut faces = Vec::new();
+        for face in shell.get().faces() {
+            let surface = shape.insert(face.surface().reverse())?;
+            faces.push(shape.insert(Face::new(
+                surface,
+                face.brep().exteriors.as_handle(),
+                face.brep().interiors.as_handle(),
+                face.brep().color,
+            ))?);
+        }
+
+        Ok(shape.insert(Shell::new(faces))?)
+    }
+
+    fn inverted_shells(shape: &Shape) -> Vec<Handle<Shell>> {
+        match shape.validate_manifold() {
+            Err(ValidationError::Solid(issues)) => issues.inverted_shells,
+            result => panic!("Expected solid issues, got {:?}", result),
+        }
+    }
+}
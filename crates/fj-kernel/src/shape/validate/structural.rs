@@ -5,7 +5,7 @@ use fj_math::Point;
 use crate::{
     geometry::{Curve, Surface},
     shape::{stores::Stores, Handle},
-    topology::{Cycle, Edge, Face, Vertex},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
 };
 
 pub fn validate_vertex(
@@ -105,6 +105,48 @@ pub fn validate_face(
     Ok(())
 }
 
+pub fn validate_shell(
+    shell: &Shell,
+    stores: &Stores,
+) -> Result<(), StructuralIssues> {
+    let mut missing_faces = HashSet::new();
+    for face in &shell.faces {
+        if !stores.faces.contains(face) {
+            missing_faces.insert(face.clone());
+        }
+    }
+
+    if !missing_faces.is_empty() {
+        return Err(StructuralIssues {
+            missing_faces,
+            ..StructuralIssues::default()
+        });
+    }
+
+    Ok(())
+}
+
+pub fn validate_solid(
+    solid: &Solid,
+    stores: &Stores,
+) -> Result<(), StructuralIssues> {
+    let mut missing_shells = HashSet::new();
+    for shell in solid.all_shells() {
+        if !stores.shells.contains(&shell) {
+            missing_shells.insert(shell);
+        }
+    }
+
+    if !missing_shells.is_empty() {
+        return Err(StructuralIssues {
+            missing_shells,
+            ..StructuralIssues::default()
+        });
+    }
+
+    Ok(())
+}
+
 /// Structural issues found during validation
 ///
 /// Used by [`ValidationError`].
@@ -127,6 +169,12 @@ pub struct StructuralIssues {
 
     /// Missing cycles found in face validation
     pub missing_cycles: HashSet<Handle<Cycle<3>>>,
+
+    /// Missing faces found in shell validation
+    pub missing_faces: HashSet<Handle<Face>>,
+
+    /// Missing shells found in solid validation
+    pub missing_shells: HashSet<Handle<Shell>>,
 }
 
 impl fmt::Display for StructuralIssues {
@@ -163,6 +211,20 @@ impl fmt::Display for StructuralIssues {
                 writeln!(f, "  - {:?}", cycle.get())?;
             }
         }
+        if !self.missing_faces.is_empty() {
+            writeln!(f, "- Missing faces:")?;
+
+            for face in &self.missing_faces {
+                writeln!(f, "  - {:?}", face.get())?;
+            }
+        }
+        if !self.missing_shells.is_empty() {
+            writeln!(f, "- Missing shells:")?;
+
+            for shell in &self.missing_shells {
+                writeln!(f, "  - {:?}", shell.get())?;
+            }
+        }
 
         Ok(())
     }
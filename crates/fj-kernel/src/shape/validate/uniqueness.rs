@@ -13,7 +13,7 @@ pub fn validate_vertex(
     min_distance: Scalar,
     stores: &Stores,
 ) -> Result<(), UniquenessIssues> {
-    for existing in stores.vertices.iter() {
+    for existing in stores.vertices.within(vertex.point(), min_distance) {
         if Some(&existing) == handle {
             continue;
         }
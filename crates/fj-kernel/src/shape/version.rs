@@ -0,0 +1,209 @@
+use std::any::Any;
+
+use fj_math::Point;
+use im::ordmap::DiffItem;
+
+use crate::{
+    geometry::{Curve, Surface},
+    topology::{Cycle, Edge, Face, Shell, Solid, Vertex},
+};
+
+use super::{
+    stores::{Handle, Objects, Store, Stores},
+    Object,
+};
+
+/// A version of a [`Shape`]
+///
+/// Captures the objects of a shape at a specific point in time, and stays the
+/// same, no matter how the shape is changed afterwards. Creating a version is
+/// cheap, as it shares its structure with the shape.
+///
+/// Returned by [`Shape::version`]. Can be passed to [`Shape::restore`], to
+/// undo all changes made to the shape since the version was created.
+///
+/// [`Shape`]: super::Shape
+/// [`Shape::version`]: super::Shape::version
+/// [`Shape::restore`]: super::Shape::restore
+#[derive(Clone, Debug)]
+pub struct ShapeVersion {
+    points: Snapshot<Point<3>>,
+    curves: Snapshot<Curve<3>>,
+    surfaces: Snapshot<Surface>,
+
+    vertices: Snapshot<Vertex>,
+    edges: Snapshot<Edge<3>>,
+    cycles: Snapshot<Cycle<3>>,
+    faces: Snapshot<Face>,
+    shells: Snapshot<Shell>,
+    solids: Snapshot<Solid>,
+}
+
+impl ShapeVersion {
+    pub(super) fn new(stores: &Stores) -> Self {
+        Self {
+            points: Snapshot::new(&stores.points),
+            curves: Snapshot::new(&stores.curves),
+            surfaces: Snapshot::new(&stores.surfaces),
+
+            vertices: Snapshot::new(&stores.vertices),
+            edges: Snapshot::new(&stores.edges),
+            cycles: Snapshot::new(&stores.cycles),
+            faces: Snapshot::new(&stores.faces),
+            shells: Snapshot::new(&stores.shells),
+            solids: Snapshot::new(&stores.solids),
+        }
+    }
+
+    /// Indicate whether this version was created from the provided stores
+    pub(super) fn is_version_of(&self, stores: &Stores) -> bool {
+        // All stores of a shape are created together, so checking one of them
+        // is enough.
+        self.points.store == stores.points
+    }
+
+    pub(super) fn restore(&self, stores: &mut Stores) {
+        stores.points.restore(self.points.objects.clone());
+        stores.curves.restore(self.curves.objects.clone());
+        stores.surfaces.restore(self.surfaces.objects.clone());
+
+        stores.vertices.restore(self.vertices.objects.clone());
+        stores.edges.restore(self.edges.objects.clone());
+        stores.cycles.restore(self.cycles.objects.clone());
+        stores.faces.restore(self.faces.objects.clone());
+        stores.shells.restore(self.shells.objects.clone());
+        stores.solids.restore(self.solids.objects.clone());
+
+        stores.rebuild_indices();
+    }
+
+    /// Access an object, as it was in this version
+    ///
+    /// Returns `None`, if the object didn't exist in this version, or if the
+    /// handle refers to an object from another shape.
+    ///
+    /// Objects refer to other objects through handles, and those always
+    /// access the current state of the shape. To access a referenced object as
+    /// it was in this version, pass its handle to this method too.
+    pub fn get<T: Object>(&self, handle: &Handle<T>) -> Option<T> {
+        let snapshot = self.snapshot::<T>();
+
+        if handle.store() != &snapshot.store {
+            return None;
+        }
+
+        snapshot.objects.get(&handle.key()).cloned()
+    }
+
+    /// Iterate over all objects of a specific type, as they were in this version
+    ///
+    /// The objects are returned in the order in which they were inserted into
+    /// the shape. The handles can be used with the shape, as long as the
+    /// object hasn't been removed from it.
+    pub fn objects<T: Object>(&self) -> impl Iterator<Item = (Handle<T>, T)> {
+        let Snapshot { store, objects } = self.snapshot::<T>().clone();
+
+        objects
+            .into_iter()
+            .map(move |(key, object)| (Handle::new(key, store.clone()), object))
+    }
+
+    /// Determine how objects of a specific type changed in a newer version
+    ///
+    /// Only the parts of both versions that aren't shared between them are
+    /// compared. This makes the diff cheap, if the versions are similar.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `newer` is not a version of the same shape.
+    pub fn diff<T: Object>(&self, newer: &ShapeVersion) -> ObjectsDiff<T> {
+        let old = self.snapshot::<T>();
+        let new = newer.snapshot::<T>();
+
+        assert!(
+            old.store == new.store,
+            "Can't diff versions of different shapes"
+        );
+
+        let mut diff = ObjectsDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            updated: Vec::new(),
+        };
+
+        let handle = |&key| Handle::new(key, new.store.clone());
+        for item in old.objects.diff(&new.objects) {
+            match item {
+                DiffItem::Add(key, _) => diff.added.push(handle(key)),
+                DiffItem::Remove(key, _) => diff.removed.push(handle(key)),
+                DiffItem::Update { new: (key, _), .. } => {
+                    diff.updated.push(handle(key))
+                }
+            }
+        }
+
+        diff
+    }
+
+    fn snapshot<T: Object>(&self) -> &Snapshot<T> {
+        let snapshots: [&dyn Any; 9] = [
+            &self.points,
+            &self.curves,
+            &self.surfaces,
+            &self.vertices,
+            &self.edges,
+            &self.cycles,
+            &self.faces,
+            &self.shells,
+            &self.solids,
+        ];
+
+        snapshots
+            .into_iter()
+            .find_map(|snapshot| snapshot.downcast_ref::<Snapshot<T>>())
+            // Can't panic, as `T` is bound by `Object`, and there's a snapshot
+            // for all types of objects above.
+            .expect("Invalid object type")
+    }
+}
+
+/// How objects of a specific type changed between two versions of a shape
+///
+/// Returned by [`ShapeVersion::diff`]. Handles to removed objects can't be
+/// used with the shape anymore, but can still be passed to
+/// [`ShapeVersion::get`], to access the object in the older version.
+#[derive(Clone)]
+pub struct ObjectsDiff<T: Object> {
+    /// The objects that were added in the newer version
+    pub added: Vec<Handle<T>>,
+
+    /// The objects that were removed in the newer version
+    pub removed: Vec<Handle<T>>,
+
+    /// The objects that were changed in the newer version
+    pub updated: Vec<Handle<T>>,
+}
+
+impl<T: Object> ObjectsDiff<T> {
+    /// Indicate whether no objects changed between the two versions
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.updated.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Snapshot<T: Object> {
+    store: Store<T>,
+    objects: Objects<T>,
+}
+
+impl<T: Object> Snapshot<T> {
+    fn new(store: &Store<T>) -> Self {
+        Self {
+            store: store.clone(),
+            objects: store.snapshot(),
+        }
+    }
+}
@@ -1,11 +1,11 @@
-use fj_math::{Circle, Line, Point, Scalar, Vector};
+use fj_math::{Circle, Ellipse, Line, NurbsCurve, Point, Scalar, Vector};
 
 use crate::{
     geometry::{Curve, Surface},
     shape::{Handle, LocalForm, Shape, ValidationResult},
 };
 
-use super::{Cycle, Edge, Face, Vertex};
+use super::{Cycle, Edge, Face, Shell, Solid, Vertex};
 
 /// API for building a [`Vertex`]
 #[must_use]
@@ -58,6 +58,114 @@ impl<'r> EdgeBuilder<'r> {
         Ok(edge)
     }
 
+    /// Build an ellipse from its radii along the x and y axes
+    pub fn build_ellipse(
+        self,
+        radii: [Scalar; 2],
+    ) -> ValidationResult<Edge<3>> {
+        let [x, y] = radii;
+
+        let curve = self.shape.insert(Curve::Ellipse(Ellipse {
+            center: Point::origin(),
+            a: Vector::from([x, Scalar::ZERO, Scalar::ZERO]),
+            b: Vector::from([Scalar::ZERO, y, Scalar::ZERO]),
+        }))?;
+        let edge = self.shape.insert(Edge::new(curve, None))?;
+
+        Ok(edge)
+    }
+
+    /// Build an arc of a circle between two points
+    ///
+    /// The arc starts at the first point and follows the direction of the
+    /// circle, until it reaches the second one. Both points are expected to be
+    /// on the circle. If they are identical, the edge covers the whole circle
+    /// and has no vertices.
+    pub fn build_arc_from_points(
+        self,
+        circle: Circle<3>,
+        points: [impl Into<Point<3>>; 2],
+    ) -> ValidationResult<Edge<3>> {
+        let [a, b] = points.map(Into::into);
+
+        let vertices = if a == b {
+            None
+        } else {
+            let start = circle.point_to_circle_coords(a).t;
+            let mut end = circle.point_to_circle_coords(b).t;
+
+            // The local coordinates of the vertices must increase along the
+            // edge, even if the arc crosses the start of the circle.
+            if end <= start {
+                end += Scalar::PI * 2.;
+            }
+
+            let a = Vertex::builder(self.shape).build_from_point(a)?;
+            let b = Vertex::builder(self.shape).build_from_point(b)?;
+
+            Some([
+                LocalForm::new(Point::from([start]), a),
+                LocalForm::new(Point::from([end]), b),
+            ])
+        };
+
+        let curve = self.shape.insert(Curve::Circle(circle))?;
+        let edge = self.shape.insert(Edge::new(curve, vertices))?;
+
+        Ok(edge)
+    }
+
+    /// Build a B-spline from its control points
+    ///
+    /// The B-spline has a clamped, uniform knot vector, meaning it starts at
+    /// the first and ends at the last control point. If those are identical,
+    /// the edge is closed and has no vertices.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if there are not more control points than `degree`.
+    pub fn build_bspline_from_points(
+        self,
+        degree: usize,
+        control_points: impl IntoIterator<Item = impl Into<Point<3>>>,
+    ) -> ValidationResult<Edge<3>> {
+        let curve = NurbsCurve::from_control_points(degree, control_points);
+        self.build_nurbs(curve)
+    }
+
+    /// Build an edge from a NURBS curve
+    ///
+    /// The edge covers the whole domain of the curve. If the curve is closed,
+    /// the edge has no vertices. Otherwise, its vertices are the first and last
+    /// control points of the curve, meaning the curve's knot vector must be
+    /// clamped.
+    pub fn build_nurbs(
+        self,
+        curve: NurbsCurve<3>,
+    ) -> ValidationResult<Edge<3>> {
+        let vertices = if curve.is_closed() {
+            None
+        } else {
+            let [start, end] = curve.domain();
+
+            let a = curve.control_points[0];
+            let b = curve.control_points[curve.control_points.len() - 1];
+
+            let a = Vertex::builder(self.shape).build_from_point(a)?;
+            let b = Vertex::builder(self.shape).build_from_point(b)?;
+
+            Some([
+                LocalForm::new(Point::from([start]), a),
+                LocalForm::new(Point::from([end]), b),
+            ])
+        };
+
+        let curve = self.shape.insert(Curve::Nurbs(curve))?;
+        let edge = self.shape.insert(Edge::new(curve, vertices))?;
+
+        Ok(edge)
+    }
+
     /// Build a line segment from two points
     pub fn build_line_segment_from_points(
         self,
@@ -211,3 +319,85 @@ impl<'r> FaceBuilder<'r> {
         ))
     }
 }
+
+/// API for building a [`Shell`]
+#[must_use]
+pub struct ShellBuilder<'r> {
+    shape: &'r mut Shape,
+}
+
+impl<'r> ShellBuilder<'r> {
+    /// Construct a new instance of `ShellBuilder`
+    pub fn new(shape: &'r mut Shape) -> Self {
+        Self { shape }
+    }
+
+    /// Build a cube from the length of its edges
+    ///
+    /// The cube is centered on the origin, and its edges are aligned with the
+    /// axes of the coordinate system.
+    pub fn build_cube_from_edge_size(
+        self,
+        edge_size: Scalar,
+    ) -> ValidationResult<Shell> {
+        let h = edge_size / 2.;
+
+        // The corners of each side, counter-clockwise when viewed from the
+        // outside. This makes the face's surface point outward.
+        let sides = [
+            [[-h, -h, -h], [-h, h, -h], [h, h, -h], [h, -h, -h]],
+            [[-h, -h, h], [h, -h, h], [h, h, h], [-h, h, h]],
+            [[-h, -h, -h], [h, -h, -h], [h, -h, h], [-h, -h, h]],
+            [[-h, h, -h], [-h, h, h], [h, h, h], [h, h, -h]],
+            [[-h, -h, -h], [-h, -h, h], [-h, h, h], [-h, h, -h]],
+            [[h, -h, -h], [h, h, -h], [h, h, h], [h, -h, h]],
+        ];
+
+        let mut faces = Vec::new();
+        for points in sides {
+            let points = points.map(Point::from);
+            let surface =
+                Surface::plane_from_points([points[0], points[1], points[3]]);
+
+            let face = Face::builder(surface, self.shape)
+                .with_exterior_polygon(points)
+                .build()?;
+            faces.push(face);
+        }
+
+        self.shape.insert(Shell::new(faces))
+    }
+}
+
+/// API for building a [`Solid`]
+#[must_use]
+pub struct SolidBuilder<'r> {
+    shape: &'r mut Shape,
+}
+
+impl<'r> SolidBuilder<'r> {
+    /// Construct a new instance of `SolidBuilder`
+    pub fn new(shape: &'r mut Shape) -> Self {
+        Self { shape }
+    }
+
+    /// Build a solid from a shell, without any cavities
+    pub fn build_from_shell(
+        self,
+        exterior: Handle<Shell>,
+    ) -> ValidationResult<Solid> {
+        self.shape.insert(Solid::new(exterior, Vec::new()))
+    }
+
+    /// Build a cube from the length of its edges
+    ///
+    /// See [`ShellBuilder::build_cube_from_edge_size`].
+    pub fn build_cube_from_edge_size(
+        self,
+        edge_size: Scalar,
+    ) -> ValidationResult<Solid> {
+        let shell =
+            Shell::builder(self.shape).build_cube_from_edge_size(edge_size)?;
+        Self::new(self.shape).build_from_shell(shell)
+    }
+}
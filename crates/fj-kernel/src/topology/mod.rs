@@ -20,12 +20,19 @@ mod builder;
 mod cycle;
 mod edge;
 mod face;
+mod shell;
+mod solid;
 mod vertex;
 
 pub use self::{
-    builder::{CycleBuilder, EdgeBuilder, FaceBuilder, VertexBuilder},
+    builder::{
+        CycleBuilder, EdgeBuilder, FaceBuilder, ShellBuilder, SolidBuilder,
+        VertexBuilder,
+    },
     cycle::Cycle,
     edge::Edge,
     face::{CyclesInFace, Face},
+    shell::Shell,
+    solid::Solid,
     vertex::Vertex,
 };
@@ -0,0 +1,65 @@
+use std::hash::{Hash, Hasher};
+
+use crate::shape::{Handle, Shape};
+
+use super::{Face, ShellBuilder};
+
+/// A shell of a shape
+///
+/// A shell is a set of faces that bound a region of space. The surfaces of
+/// those faces are oriented such, that their normals point away from that
+/// region.
+///
+/// # Equality
+///
+/// Please refer to [`crate::kernel::topology`] for documentation on the
+/// equality of topological objects.
+///
+/// # Validation
+///
+/// A shell that is part of a [`Shape`] must be structurally sound. That means
+/// the faces it refers to, must be part of the same shape.
+///
+/// Whether the faces actually form a closed shell is only known once the shell
+/// is complete, so that isn't validated on insertion. Use
+/// [`Shape::validate_manifold`] to check it.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd)]
+pub struct Shell {
+    /// The faces that make up the shell
+    pub faces: Vec<Handle<Face>>,
+}
+
+impl Shell {
+    /// Construct a `Shell`
+    pub fn new(faces: impl IntoIterator<Item = Handle<Face>>) -> Self {
+        let faces = faces.into_iter().collect();
+        Self { faces }
+    }
+
+    /// Build a shell using the [`ShellBuilder`] API
+    pub fn builder(shape: &mut Shape) -> ShellBuilder {
+        ShellBuilder::new(shape)
+    }
+
+    /// Access the faces that this shell refers to
+    ///
+    /// This is a convenience method that saves the caller from dealing with the
+    /// [`Handle`]s.
+    pub fn faces(&self) -> impl Iterator<Item = Face> + '_ {
+        self.faces.iter().map(|handle| handle.get())
+    }
+}
+
+impl PartialEq for Shell {
+    fn eq(&self, other: &Self) -> bool {
+        self.faces().eq(other.faces())
+    }
+}
+
+impl Hash for Shell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for face in self.faces() {
+            face.hash(state);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::hash::{Hash, Hasher};
+
+use crate::shape::{Handle, Shape};
+
+use super::{Shell, SolidBuilder};
+
+/// A solid of a shape
+///
+/// A solid is the region of space that is bounded by its exterior shell, minus
+/// the regions bounded by its interior shells. Each interior shell defines a
+/// cavity within the solid.
+///
+/// The faces of all shells point away from the solid. For the interior shells,
+/// that means they point into the cavity they define.
+///
+/// # Equality
+///
+/// Please refer to [`crate::kernel::topology`] for documentation on the
+/// equality of topological objects.
+///
+/// # Validation
+///
+/// A solid that is part of a [`Shape`] must be structurally sound. That means
+/// the shells it refers to, must be part of the same shape.
+///
+/// [`Shape::validate_manifold`] checks, that its shells are closed and face
+/// away from the solid.
+#[derive(Clone, Debug, Eq, Ord, PartialOrd)]
+pub struct Solid {
+    /// The shell that bounds the solid on the outside
+    pub exterior: Handle<Shell>,
+
+    /// The shells that bound the solid on the inside
+    ///
+    /// Each of these shells defines a cavity in the solid.
+    pub interiors: Vec<Handle<Shell>>,
+}
+
+impl Solid {
+    /// Construct a `Solid`
+    pub fn new(
+        exterior: Handle<Shell>,
+        interiors: impl IntoIterator<Item = Handle<Shell>>,
+    ) -> Self {
+        let interiors = interiors.into_iter().collect();
+
+        Self {
+            exterior,
+            interiors,
+        }
+    }
+
+    /// Build a solid using the [`SolidBuilder`] API
+    pub fn builder(shape: &mut Shape) -> SolidBuilder {
+        SolidBuilder::new(shape)
+    }
+
+    /// Access the exterior shell that the solid refers to
+    ///
+    /// This is a convenience method that saves the caller from dealing with the
+    /// [`Handle`].
+    pub fn exterior(&self) -> Shell {
+        self.exterior.get()
+    }
+
+    /// Access the interior shells that the solid refers to
+    ///
+    /// This is a convenience method that saves the caller from dealing with the
+    /// [`Handle`]s.
+    pub fn interiors(&self) -> impl Iterator<Item = Shell> + '_ {
+        self.interiors.iter().map(|handle| handle.get())
+    }
+
+    /// Access handles to all shells that the solid refers to
+    ///
+    /// The exterior shell comes first, followed by the interior shells.
+    pub fn all_shells(&self) -> impl Iterator<Item = Handle<Shell>> + '_ {
+        [self.exterior.clone()]
+            .into_iter()
+            .chain(self.interiors.iter().cloned())
+    }
+}
+
+impl PartialEq for Solid {
+    fn eq(&self, other: &Self) -> bool {
+        self.exterior() == other.exterior()
+            && self.interiors().eq(other.interiors())
+    }
+}
+
+impl Hash for Solid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.exterior().hash(state);
+        for shell in self.interiors() {
+            shell.hash(state);
+        }
+    }
+}
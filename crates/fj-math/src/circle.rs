@@ -47,8 +47,11 @@ impl<const D: usize> Circle<D> {
         &self,
         point: impl Into<Point<D>>,
     ) -> Point<1> {
-        let vector = (point.into() - self.center).to_uv();
-        let atan = Scalar::atan2(vector.v, vector.u);
+        // `a` and `b` are perpendicular and of equal length, so projecting onto
+        // them yields the cosine and sine of the angle, scaled by the same
+        // factor.
+        let vector = point.into() - self.center;
+        let atan = Scalar::atan2(vector.dot(&self.b), vector.dot(&self.a));
         let coord = if atan >= Scalar::ZERO {
             atan
         } else {
@@ -110,4 +113,23 @@ mod tests {
             Point::from([FRAC_PI_2 * 3.]),
         );
     }
+
+    #[test]
+    fn point_to_circle_coords_reversed() {
+        let circle = Circle {
+            center: Point::from([0., 0., 0.]),
+            a: Vector::from([0., 1., 0.]),
+            b: Vector::from([0., 0., 1.]),
+        }
+        .reverse();
+
+        assert_eq!(
+            circle.point_to_circle_coords([0., 1., 0.]),
+            Point::from([0.]),
+        );
+        assert_eq!(
+            circle.point_to_circle_coords([0., 0., -1.]),
+            Point::from([FRAC_PI_2]),
+        );
+    }
 }
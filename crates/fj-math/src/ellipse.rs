@@ -0,0 +1,170 @@
+use crate::{Point, Scalar, Vector};
+
+/// An n-dimensional ellipse
+///
+/// The dimensionality of the ellipse is defined by the const generic `D`
+/// parameter.
+///
+/// The ellipse is defined by its center and two conjugate semi-diameters, `a`
+/// and `b`. Unlike the principal semi-axes of the ellipse, these don't need to
+/// be perpendicular. This has the advantage, that the image of an ellipse under
+/// any affine transform is simply the ellipse defined by the transformed center
+/// and semi-diameters, and that curve coordinates are preserved by such a
+/// transform.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Ellipse<const D: usize> {
+    /// The center point of the ellipse
+    pub center: Point<D>,
+
+    /// A vector from the center to the starting point of the ellipse
+    ///
+    /// Please also refer to the documentation of `b`.
+    pub a: Vector<D>,
+
+    /// A second semi-diameter, conjugate to `a`
+    ///
+    /// The point at curve coordinate `t` is `center + a * cos(t) + b * sin(t)`.
+    /// `a` and `b` must be linearly independent. Code working with ellipses
+    /// might assume that this condition is met.
+    pub b: Vector<D>,
+}
+
+impl<const D: usize> Ellipse<D> {
+    /// Create a new instance that is reversed
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Compute the lengths of the principal semi-axes of the ellipse
+    ///
+    /// Returns the lengths of the semi-major and the semi-minor axis, in that
+    /// order.
+    pub fn semi_axes(&self) -> [Scalar; 2] {
+        // The ellipse is the image of the unit circle under the linear map
+        // `[a b]`. The lengths of its semi-axes are the singular values of that
+        // map, which are the square roots of the eigenvalues of its Gram
+        // matrix.
+        let aa = self.a.dot(&self.a);
+        let ab = self.a.dot(&self.b);
+        let bb = self.b.dot(&self.b);
+
+        let mean = (aa + bb) / 2.;
+        let diff = (aa - bb) / 2.;
+        let root = (diff * diff + ab * ab).sqrt();
+
+        let major = (mean + root).sqrt();
+        let minor = (mean - root).max(Scalar::ZERO).sqrt();
+
+        [major, minor]
+    }
+
+    /// Convert a `D`-dimensional point to ellipse coordinates
+    ///
+    /// Converts the provided point into ellipse coordinates between `0.`
+    /// (inclusive) and `PI * 2.` (exclusive).
+    ///
+    /// Projects the point into the plane of the ellipse, and then onto the
+    /// ellipse, along a line through the center. This is done to make this
+    /// method robust against floating point accuracy issues.
+    ///
+    /// Callers are advised to be careful about the points they pass, as the
+    /// point not being on the curve, intentional or not, will not result in an
+    /// error.
+    pub fn point_to_ellipse_coords(
+        &self,
+        point: impl Into<Point<D>>,
+    ) -> Point<1> {
+        let vector = point.into() - self.center;
+
+        // Express the vector in terms of `a` and `b`, by solving the normal
+        // equations of the least-squares problem.
+        let aa = self.a.dot(&self.a);
+        let ab = self.a.dot(&self.b);
+        let bb = self.b.dot(&self.b);
+        let va = vector.dot(&self.a);
+        let vb = vector.dot(&self.b);
+
+        let det = aa * bb - ab * ab;
+        let cos = (bb * va - ab * vb) / det;
+        let sin = (aa * vb - ab * va) / det;
+
+        let atan = Scalar::atan2(sin, cos);
+        let coord = if atan >= Scalar::ZERO {
+            atan
+        } else {
+            atan + Scalar::PI * 2.
+        };
+        Point::from([coord])
+    }
+
+    /// Convert a point in ellipse coordinates into a `D`-dimensional point
+    pub fn point_from_ellipse_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        self.center + self.vector_from_ellipse_coords(point.into().coords)
+    }
+
+    /// Convert a vector in ellipse coordinates into a `D`-dimensional point
+    pub fn vector_from_ellipse_coords(
+        &self,
+        vector: impl Into<Vector<1>>,
+    ) -> Vector<D> {
+        let angle = vector.into().t;
+        let (sin, cos) = angle.sin_cos();
+
+        self.a * cos + self.b * sin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar, Vector};
+
+    use super::Ellipse;
+
+    #[test]
+    fn point_to_ellipse_coords() {
+        let ellipse = Ellipse {
+            center: Point::from([1., 2., 3.]),
+            a: Vector::from([2., 0., 0.]),
+            b: Vector::from([1., 1., 0.]),
+        };
+
+        for t in [0., FRAC_PI_4, FRAC_PI_2, PI, FRAC_PI_2 * 3.] {
+            let point = ellipse.point_from_ellipse_coords([t]);
+            assert_abs_diff_eq!(
+                ellipse.point_to_ellipse_coords(point),
+                Point::from([t]),
+                epsilon = 1e-12,
+            );
+        }
+    }
+
+    #[test]
+    fn semi_axes() {
+        let ellipse = Ellipse {
+            center: Point::from([0., 0.]),
+            a: Vector::from([2., 0.]),
+            b: Vector::from([0., 1.]),
+        };
+        assert_eq!(ellipse.semi_axes(), [Scalar::from(2.), Scalar::ONE]);
+
+        // Same ellipse, different conjugate semi-diameters.
+        let t = Scalar::from(0.3);
+        let ellipse = Ellipse {
+            a: ellipse.vector_from_ellipse_coords([t]),
+            b: ellipse.vector_from_ellipse_coords([t + Scalar::PI / 2.]),
+            ..ellipse
+        };
+        let [major, minor] = ellipse.semi_axes();
+        assert_abs_diff_eq!(major, Scalar::from(2.), epsilon = 1e-12);
+        assert_abs_diff_eq!(minor, Scalar::ONE, epsilon = 1e-12);
+    }
+}
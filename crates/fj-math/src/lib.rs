@@ -38,7 +38,9 @@
 mod aabb;
 mod circle;
 mod coordinates;
+mod ellipse;
 mod line;
+mod nurbs;
 mod point;
 mod poly_chain;
 mod scalar;
@@ -51,7 +53,9 @@ pub use self::{
     aabb::Aabb,
     circle::Circle,
     coordinates::{Uv, Xyz, T},
+    ellipse::Ellipse,
     line::Line,
+    nurbs::{NurbsCurve, NurbsSurface},
     point::Point,
     poly_chain::PolyChain,
     scalar::Scalar,
@@ -0,0 +1,919 @@
+use crate::{Point, Scalar, Vector};
+
+/// An n-dimensional non-uniform rational B-spline (NURBS) curve
+///
+/// The dimensionality of the curve is defined by the const generic `D`
+/// parameter.
+///
+/// The curve is defined for curve coordinates within its [domain]. Evaluating
+/// it outside of that domain extrapolates the first or last polynomial segment.
+///
+/// [domain]: NurbsCurve::domain
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct NurbsCurve<const D: usize> {
+    /// The degree of the curve
+    pub degree: usize,
+
+    /// The control points of the curve
+    pub control_points: Vec<Point<D>>,
+
+    /// The weights of the control points
+    ///
+    /// There must be one weight per control point, and all weights must be
+    /// positive. If all weights are equal, the curve is a regular
+    /// (non-rational) B-spline.
+    pub weights: Vec<Scalar>,
+
+    /// The knot vector of the curve
+    ///
+    /// Must be non-decreasing, and its length must be the number of control
+    /// points plus the degree plus one. Code working with NURBS curves might
+    /// assume that these conditions are met.
+    pub knots: Vec<Scalar>,
+}
+
+impl<const D: usize> NurbsCurve<D> {
+    /// Construct a non-rational B-spline with a clamped, uniform knot vector
+    ///
+    /// The curve starts at the first and ends at the last control point. Its
+    /// domain is `0..=1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if there are not more control points than `degree`.
+    pub fn from_control_points(
+        degree: usize,
+        control_points: impl IntoIterator<Item = impl Into<Point<D>>>,
+    ) -> Self {
+        let control_points: Vec<_> =
+            control_points.into_iter().map(Into::into).collect();
+        assert!(
+            control_points.len() > degree,
+            "B-spline of degree {} needs at least {} control points",
+            degree,
+            degree + 1,
+        );
+
+        let weights = vec![Scalar::ONE; control_points.len()];
+        let knots = clamped_uniform_knots(degree, control_points.len());
+
+        Self {
+            degree,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Construct an exact representation of an elliptical or circular arc
+    ///
+    /// The arc consists of the points `center + a * cos(t) + b * sin(t)`, with
+    /// `t` going from zero to `angle`. `a` and `b` are conjugate semi-diameters,
+    /// same as for [`Ellipse`]. If they are perpendicular and of equal length,
+    /// the arc is circular.
+    ///
+    /// The arc is represented as a sequence of rational quadratic Bézier
+    /// curves, each spanning at most a quarter turn. Its domain is `0..=1`. If
+    /// `angle` is a full turn, the curve is closed.
+    ///
+    /// [`Ellipse`]: crate::Ellipse
+    pub fn arc(
+        center: impl Into<Point<D>>,
+        a: impl Into<Vector<D>>,
+        b: impl Into<Vector<D>>,
+        angle: impl Into<Scalar>,
+    ) -> Self {
+        let center = center.into();
+        let a = a.into();
+        let b = b.into();
+        let angle = angle.into();
+
+        let num_segments = (angle.abs() / (Scalar::PI / 2.))
+            .ceil()
+            .max(Scalar::ONE)
+            .into_u64();
+        let step = angle / num_segments as f64;
+        let point = |t: Scalar| {
+            let (sin, cos) = t.sin_cos();
+            center + a * cos + b * sin
+        };
+
+        // The middle control point of each segment is where the tangents at
+        // its ends intersect. Its weight is the cosine of half the angle that
+        // the segment spans.
+        let weight = (step / 2.).cos();
+
+        let mut control_points = vec![point(Scalar::ZERO)];
+        let mut weights = vec![Scalar::ONE];
+        let mut knots = vec![Scalar::ZERO; 3];
+
+        for i in 0..num_segments {
+            let start = step * i as f64;
+            let middle = start + step / 2.;
+            let end = start + step;
+
+            control_points.push(center + (point(middle) - center) / weight);
+            control_points.push(point(end));
+            weights.extend([weight, Scalar::ONE]);
+
+            let knot = Scalar::from_u64(i + 1) / num_segments as f64;
+            if i + 1 < num_segments {
+                knots.extend([knot, knot]);
+            } else {
+                knots.extend([knot; 3]);
+            }
+        }
+
+        // Make sure a full turn results in a closed curve, regardless of any
+        // floating point accuracy issues.
+        if angle.abs() == Scalar::PI * 2. {
+            let start = control_points[0];
+            control_points.pop();
+            control_points.push(start);
+        }
+
+        Self {
+            degree: 2,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Access the domain of the curve
+    ///
+    /// Returns the first and last curve coordinate of the curve.
+    pub fn domain(&self) -> [Scalar; 2] {
+        domain(self.degree, &self.knots, self.control_points.len())
+    }
+
+    /// Indicate whether the curve is closed
+    ///
+    /// A curve is considered closed, if its first and last control points and
+    /// weights are identical. For clamped knot vectors, this means the curve
+    /// ends where it starts.
+    pub fn is_closed(&self) -> bool {
+        self.control_points.first() == self.control_points.last()
+            && self.weights.first() == self.weights.last()
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// The curve coordinates of the reversed curve are the negated curve
+    /// coordinates of the original curve.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.control_points.reverse();
+        self.weights.reverse();
+        self.knots = reverse_knots(&self.knots);
+        self
+    }
+
+    /// Convert a `D`-dimensional point to curve coordinates
+    ///
+    /// Returns the curve coordinates of the point on the curve that is closest
+    /// to the provided point, as far as it can be determined numerically. The
+    /// result is always within the domain of the curve.
+    ///
+    /// Callers are advised to be careful about the points they pass, as the
+    /// point not being on the curve, intentional or not, will not result in an
+    /// error.
+    pub fn point_to_curve_coords(
+        &self,
+        point: impl Into<Point<D>>,
+    ) -> Point<1> {
+        let point = point.into();
+        let [start, end] = self.domain();
+
+        // Find a good starting point by sampling the curve.
+        let num_samples = self.control_points.len() * SAMPLES_PER_SEGMENT;
+        let mut t = (0..=num_samples)
+            .map(|i| start + (end - start) * (i as f64 / num_samples as f64))
+            .min_by_key(|&t| {
+                (self.point_from_curve_coords([t]) - point).magnitude()
+            })
+            .unwrap_or(start);
+
+        // And refine it using the Gauss-Newton method.
+        for _ in 0..MAX_ITERATIONS {
+            let (curve_point, derivative) = self.point_and_derivative(t);
+
+            let length = derivative.dot(&derivative);
+            if length == Scalar::ZERO {
+                break;
+            }
+
+            let delta = (point - curve_point).dot(&derivative) / length;
+            let next = (t + delta).max(start).min(end);
+
+            let converged = (next - t).abs() <= (end - start) * EPSILON;
+            t = next;
+
+            if converged {
+                break;
+            }
+        }
+
+        Point::from([t])
+    }
+
+    /// Convert a point in curve coordinates into a `D`-dimensional point
+    pub fn point_from_curve_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        let t = point.into().t;
+        let (point, weight) = evaluate(
+            self.degree,
+            &self.knots,
+            &self.weighted_points(),
+            &self.weights,
+            t,
+        );
+
+        Point {
+            coords: point / weight,
+        }
+    }
+
+    /// Compute the derivative of the curve at the given curve coordinate
+    pub fn derivative(&self, point: impl Into<Point<1>>) -> Vector<D> {
+        let (_, derivative) = self.point_and_derivative(point.into().t);
+        derivative
+    }
+
+    fn point_and_derivative(&self, t: Scalar) -> (Point<D>, Vector<D>) {
+        point_and_derivative(
+            self.degree,
+            &self.knots,
+            &self.weighted_points(),
+            &self.weights,
+            t,
+        )
+    }
+
+    fn weighted_points(&self) -> Vec<Vector<D>> {
+        weighted_points(&self.control_points, &self.weights)
+    }
+}
+
+/// A non-uniform rational B-spline (NURBS) surface
+///
+/// The surface is defined by a grid of control points. The first index of the
+/// grid refers to the u direction of the surface, the second index to the v
+/// direction.
+///
+/// The surface is defined for surface coordinates within its [domain].
+///
+/// [domain]: NurbsSurface::domain
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct NurbsSurface {
+    /// The degrees of the surface in the u and v directions
+    pub degree: [usize; 2],
+
+    /// The grid of control points of the surface
+    ///
+    /// All rows must have the same length.
+    pub control_points: Vec<Vec<Point<3>>>,
+
+    /// The weights of the control points
+    ///
+    /// Must have the same shape as `control_points`, and all weights must be
+    /// positive.
+    pub weights: Vec<Vec<Scalar>>,
+
+    /// The knot vectors of the surface in the u and v directions
+    ///
+    /// The same conditions as for [`NurbsCurve::knots`] apply to each of them.
+    pub knots: [Vec<Scalar>; 2],
+}
+
+impl NurbsSurface {
+    /// Construct a non-rational B-spline surface with clamped, uniform knots
+    ///
+    /// The domain of the surface is `0..=1` in both directions.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the rows of `control_points` are not of equal length, or if
+    /// there are not more control points than the degree in either direction.
+    pub fn from_control_points(
+        degree: [usize; 2],
+        control_points: Vec<Vec<Point<3>>>,
+    ) -> Self {
+        let [degree_u, degree_v] = degree;
+
+        let num_u = control_points.len();
+        let num_v = control_points.first().map(Vec::len).unwrap_or(0);
+
+        assert!(
+            control_points.iter().all(|row| row.len() == num_v),
+            "Rows of control points must have equal length"
+        );
+        assert!(
+            num_u > degree_u && num_v > degree_v,
+            "Not enough control points for B-spline surface of degree {:?}",
+            degree,
+        );
+
+        let weights = vec![vec![Scalar::ONE; num_v]; num_u];
+        let knots = [
+            clamped_uniform_knots(degree_u, num_u),
+            clamped_uniform_knots(degree_v, num_v),
+        ];
+
+        Self {
+            degree,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Access the domain of the surface
+    ///
+    /// Returns the first and last surface coordinate in the u and the v
+    /// direction, in that order.
+    pub fn domain(&self) -> [[Scalar; 2]; 2] {
+        let num_u = self.control_points.len();
+        let num_v = self.control_points.first().map(Vec::len).unwrap_or(0);
+
+        [
+            domain(self.degree[0], &self.knots[0], num_u),
+            domain(self.degree[1], &self.knots[1], num_v),
+        ]
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// Reverses the u direction of the surface, which flips its normal. The u
+    /// coordinates of the reversed surface are the negated u coordinates of the
+    /// original surface.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.control_points.reverse();
+        self.weights.reverse();
+        self.knots[0] = reverse_knots(&self.knots[0]);
+        self
+    }
+
+    /// Convert a point in model coordinates to surface coordinates
+    ///
+    /// Returns the surface coordinates of the point on the surface that is
+    /// closest to the provided point, as far as it can be determined
+    /// numerically. The result is always within the domain of the surface.
+    ///
+    /// Callers are advised to be careful about the points they pass, as the
+    /// point not being on the surface, intentional or not, will not result in
+    /// an error.
+    pub fn point_to_surface_coords(
+        &self,
+        point: impl Into<Point<3>>,
+    ) -> Point<2> {
+        let point = point.into();
+        let [[start_u, end_u], [start_v, end_v]] = self.domain();
+
+        // Find a good starting point by sampling the surface.
+        let num_u = self.control_points.len() * SAMPLES_PER_SEGMENT;
+        let num_v = self.control_points.first().map(Vec::len).unwrap_or(0)
+            * SAMPLES_PER_SEGMENT;
+
+        let mut samples = Vec::new();
+        for i in 0..=num_u {
+            for j in 0..=num_v {
+                let u = start_u + (end_u - start_u) * (i as f64 / num_u as f64);
+                let v = start_v + (end_v - start_v) * (j as f64 / num_v as f64);
+                samples.push([u, v]);
+            }
+        }
+        let [mut u, mut v] = samples
+            .into_iter()
+            .min_by_key(|&uv| {
+                (self.point_from_surface_coords(uv) - point).magnitude()
+            })
+            .unwrap_or([start_u, start_v]);
+
+        // And refine it using the Gauss-Newton method.
+        for _ in 0..MAX_ITERATIONS {
+            let (surface_point, [du, dv]) = self.point_and_derivatives([u, v]);
+            let residual = point - surface_point;
+
+            // Solve the normal equations for the update.
+            let [uu, uv, vv] = [du.dot(&du), du.dot(&dv), dv.dot(&dv)];
+            let [ru, rv] = [residual.dot(&du), residual.dot(&dv)];
+
+            let det = uu * vv - uv * uv;
+            if det == Scalar::ZERO {
+                break;
+            }
+
+            let delta_u = (vv * ru - uv * rv) / det;
+            let delta_v = (uu * rv - uv * ru) / det;
+
+            let next_u = (u + delta_u).max(start_u).min(end_u);
+            let next_v = (v + delta_v).max(start_v).min(end_v);
+
+            let converged = (next_u - u).abs() <= (end_u - start_u) * EPSILON
+                && (next_v - v).abs() <= (end_v - start_v) * EPSILON;
+            u = next_u;
+            v = next_v;
+
+            if converged {
+                break;
+            }
+        }
+
+        Point::from([u, v])
+    }
+
+    /// Convert a point in surface coordinates to model coordinates
+    pub fn point_from_surface_coords(
+        &self,
+        point: impl Into<Point<2>>,
+    ) -> Point<3> {
+        let point = point.into();
+        let (point, weight) = self.evaluate_homogeneous(point.u, point.v);
+
+        Point {
+            coords: point / weight,
+        }
+    }
+
+    /// Compute the partial derivatives of the surface at the given point
+    ///
+    /// Returns the derivatives in the u and v direction, in that order.
+    pub fn derivatives(&self, point: impl Into<Point<2>>) -> [Vector<3>; 2] {
+        let point = point.into();
+        let (_, derivatives) = self.point_and_derivatives([point.u, point.v]);
+        derivatives
+    }
+
+    /// Compute the isoparametric curve at the given v coordinate
+    ///
+    /// Returns the curve that runs along the u direction of the surface. Its
+    /// curve coordinates are the u coordinates of the surface.
+    pub fn curve_at_v(&self, v: impl Into<Scalar>) -> NurbsCurve<3> {
+        let (points, weights) = self.evaluate_rows(v.into());
+        curve_from_homogeneous(
+            self.degree[0],
+            self.knots[0].clone(),
+            points,
+            weights,
+        )
+    }
+
+    /// Compute the isoparametric curve at the given u coordinate
+    ///
+    /// Returns the curve that runs along the v direction of the surface. Its
+    /// curve coordinates are the v coordinates of the surface.
+    pub fn curve_at_u(&self, u: impl Into<Scalar>) -> NurbsCurve<3> {
+        let (points, weights) = self.evaluate_columns(u.into());
+        curve_from_homogeneous(
+            self.degree[1],
+            self.knots[1].clone(),
+            points,
+            weights,
+        )
+    }
+
+    fn point_and_derivatives(
+        &self,
+        [u, v]: [Scalar; 2],
+    ) -> (Point<3>, [Vector<3>; 2]) {
+        // Evaluate every row in v, which leaves us with a curve in u.
+        let (points, weights) = self.evaluate_rows(v);
+        let (point, du) = point_and_derivative(
+            self.degree[0],
+            &self.knots[0],
+            &points,
+            &weights,
+            u,
+        );
+
+        // Evaluate every column in u, which leaves us with a curve in v.
+        let (points, weights) = self.evaluate_columns(u);
+        let (_, dv) = point_and_derivative(
+            self.degree[1],
+            &self.knots[1],
+            &points,
+            &weights,
+            v,
+        );
+
+        (point, [du, dv])
+    }
+
+    fn evaluate_homogeneous(
+        &self,
+        u: Scalar,
+        v: Scalar,
+    ) -> (Vector<3>, Scalar) {
+        let (points, weights) = self.evaluate_rows(v);
+        evaluate(self.degree[0], &self.knots[0], &points, &weights, u)
+    }
+
+    fn evaluate_columns(&self, u: Scalar) -> (Vec<Vector<3>>, Vec<Scalar>) {
+        let num_v = self.control_points.first().map(Vec::len).unwrap_or(0);
+
+        (0..num_v)
+            .map(|j| {
+                let points: Vec<_> = self
+                    .control_points
+                    .iter()
+                    .zip(&self.weights)
+                    .map(|(row, weights)| row[j].coords * weights[j])
+                    .collect();
+                let weights: Vec<_> =
+                    self.weights.iter().map(|weights| weights[j]).collect();
+
+                evaluate(self.degree[0], &self.knots[0], &points, &weights, u)
+            })
+            .unzip()
+    }
+
+    fn evaluate_rows(&self, v: Scalar) -> (Vec<Vector<3>>, Vec<Scalar>) {
+        self.control_points
+            .iter()
+            .zip(&self.weights)
+            .map(|(points, weights)| {
+                evaluate(
+                    self.degree[1],
+                    &self.knots[1],
+                    &weighted_points(points, weights),
+                    weights,
+                    v,
+                )
+            })
+            .unzip()
+    }
+}
+
+/// The number of samples per control point used to find a starting point
+const SAMPLES_PER_SEGMENT: usize = 8;
+
+/// The maximum number of iterations when converting points to curve coordinates
+const MAX_ITERATIONS: usize = 32;
+
+/// The relative precision when converting points to curve coordinates
+const EPSILON: f64 = 1e-12;
+
+fn clamped_uniform_knots(
+    degree: usize,
+    num_control_points: usize,
+) -> Vec<Scalar> {
+    let num_segments = num_control_points - degree;
+
+    let mut knots = Vec::new();
+    knots.extend((0..degree).map(|_| Scalar::ZERO));
+    knots.extend(
+        (0..=num_segments)
+            .map(|i| Scalar::from_f64(i as f64 / num_segments as f64)),
+    );
+    knots.extend((0..degree).map(|_| Scalar::ONE));
+
+    knots
+}
+
+fn reverse_knots(knots: &[Scalar]) -> Vec<Scalar> {
+    knots.iter().rev().map(|&knot| -knot).collect()
+}
+
+fn domain(
+    degree: usize,
+    knots: &[Scalar],
+    num_control_points: usize,
+) -> [Scalar; 2] {
+    [knots[degree], knots[num_control_points]]
+}
+
+fn curve_from_homogeneous(
+    degree: usize,
+    knots: Vec<Scalar>,
+    points: Vec<Vector<3>>,
+    weights: Vec<Scalar>,
+) -> NurbsCurve<3> {
+    let control_points = points
+        .into_iter()
+        .zip(&weights)
+        .map(|(point, &weight)| Point {
+            coords: point / weight,
+        })
+        .collect();
+
+    NurbsCurve {
+        degree,
+        control_points,
+        weights,
+        knots,
+    }
+}
+
+fn weighted_points<const D: usize>(
+    points: &[Point<D>],
+    weights: &[Scalar],
+) -> Vec<Vector<D>> {
+    points
+        .iter()
+        .zip(weights)
+        .map(|(point, &weight)| point.coords * weight)
+        .collect()
+}
+
+/// Find the index of the knot span that contains `t`
+///
+/// Values outside of the domain are assigned to the first or last non-empty
+/// span of the domain.
+fn find_span(
+    degree: usize,
+    knots: &[Scalar],
+    num_control_points: usize,
+    t: Scalar,
+) -> usize {
+    let first = degree;
+    let last = num_control_points - 1;
+
+    if t >= knots[last + 1] {
+        // Find the last non-empty span.
+        return (first..=last)
+            .rev()
+            .find(|&k| knots[k] < knots[k + 1])
+            .unwrap_or(last);
+    }
+
+    (first..=last)
+        .find(|&k| t < knots[k + 1] && knots[k] < knots[k + 1])
+        .unwrap_or(last)
+}
+
+/// Evaluate a B-spline in homogeneous coordinates, using de Boor's algorithm
+fn evaluate<const D: usize>(
+    degree: usize,
+    knots: &[Scalar],
+    points: &[Vector<D>],
+    weights: &[Scalar],
+    t: Scalar,
+) -> (Vector<D>, Scalar) {
+    let k = find_span(degree, knots, points.len(), t);
+
+    let mut d: Vec<_> = (0..=degree)
+        .map(|j| (points[j + k - degree], weights[j + k - degree]))
+        .collect();
+
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let left = knots[j + k - degree];
+            let right = knots[j + 1 + k - r];
+            let alpha = (t - left) / (right - left);
+
+            let (point_a, weight_a) = d[j - 1];
+            let (point_b, weight_b) = d[j];
+
+            d[j] = (
+                point_a * (Scalar::ONE - alpha) + point_b * alpha,
+                weight_a * (Scalar::ONE - alpha) + weight_b * alpha,
+            );
+        }
+    }
+
+    d[degree]
+}
+
+/// Evaluate a rational B-spline and its derivative
+fn point_and_derivative<const D: usize>(
+    degree: usize,
+    knots: &[Scalar],
+    points: &[Vector<D>],
+    weights: &[Scalar],
+    t: Scalar,
+) -> (Point<D>, Vector<D>) {
+    let (point, weight) = evaluate(degree, knots, points, weights, t);
+    let point = point / weight;
+
+    if degree == 0 {
+        return (Point { coords: point }, Vector::from([Scalar::ZERO; D]));
+    }
+
+    // The derivative of a B-spline is a B-spline of one degree less, with the
+    // first and last knot removed.
+    let factor = |i: usize| {
+        let span = knots[i + degree + 1] - knots[i + 1];
+        if span == Scalar::ZERO {
+            Scalar::ZERO
+        } else {
+            Scalar::from_u64(degree as u64) / span
+        }
+    };
+    let derivative_points: Vec<_> = points
+        .windows(2)
+        .enumerate()
+        .map(|(i, p)| (p[1] - p[0]) * factor(i))
+        .collect();
+    let derivative_weights: Vec<_> = weights
+        .windows(2)
+        .enumerate()
+        .map(|(i, w)| (w[1] - w[0]) * factor(i))
+        .collect();
+
+    let (derivative_point, derivative_weight) = evaluate(
+        degree - 1,
+        &knots[1..knots.len() - 1],
+        &derivative_points,
+        &derivative_weights,
+        t,
+    );
+
+    // Quotient rule
+    let derivative = (derivative_point - point * derivative_weight) / weight;
+
+    (Point { coords: point }, derivative)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar, Vector};
+
+    use super::{NurbsCurve, NurbsSurface};
+
+    #[test]
+    fn point_from_curve_coords() {
+        // A quadratic Bézier curve.
+        let curve =
+            NurbsCurve::from_control_points(2, [[0., 0.], [1., 2.], [2., 0.]]);
+
+        assert_eq!(curve.point_from_curve_coords([0.]), Point::from([0., 0.]));
+        assert_eq!(curve.point_from_curve_coords([0.5]), Point::from([1., 1.]));
+        assert_eq!(curve.point_from_curve_coords([1.]), Point::from([2., 0.]));
+    }
+
+    #[test]
+    fn rational_circle() {
+        // A quarter circle, as a rational quadratic Bézier curve.
+        let curve = NurbsCurve {
+            degree: 2,
+            control_points: vec![
+                Point::from([1., 0.]),
+                Point::from([1., 1.]),
+                Point::from([0., 1.]),
+            ],
+            weights: vec![
+                Scalar::ONE,
+                Scalar::from_f64(1. / 2f64.sqrt()),
+                Scalar::ONE,
+            ],
+            knots: [0., 0., 0., 1., 1., 1.].map(Scalar::from_f64).to_vec(),
+        };
+
+        for i in 0..=10 {
+            let point = curve.point_from_curve_coords([i as f64 / 10.]);
+            assert_abs_diff_eq!(
+                point.coords.magnitude(),
+                Scalar::ONE,
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn arc() {
+        let center = Point::from([1., 2.]);
+        let radius = 3.;
+
+        for angle in [1., -2., 5., Scalar::PI.into_f64() * 2.] {
+            let curve =
+                NurbsCurve::arc(center, [radius, 0.], [0., radius], angle);
+
+            for i in 0..=20 {
+                let point = curve.point_from_curve_coords([i as f64 / 20.]);
+                assert_abs_diff_eq!(
+                    (point - center).magnitude(),
+                    Scalar::from(radius),
+                    epsilon = 1e-12
+                );
+            }
+
+            let end = curve.point_from_curve_coords([1.]);
+            let (sin, cos) = angle.sin_cos();
+            assert_abs_diff_eq!(
+                end,
+                center + Vector::from([cos, sin]) * radius,
+                epsilon = 1e-12
+            );
+        }
+
+        let full =
+            NurbsCurve::arc([0., 0.], [1., 0.], [0., 1.], Scalar::PI * 2.);
+        assert!(full.is_closed());
+    }
+
+    #[test]
+    fn derivative() {
+        let curve = NurbsCurve::from_control_points(
+            3,
+            [[0., 0.], [1., 2.], [2., -1.], [3., 1.], [4., 0.]],
+        );
+
+        let t = Scalar::from_f64(0.3);
+        let h = Scalar::from_f64(1e-6);
+
+        let a = curve.point_from_curve_coords([t - h]);
+        let b = curve.point_from_curve_coords([t + h]);
+        let numeric = (b - a) / (h * 2.);
+
+        assert_abs_diff_eq!(curve.derivative([t]), numeric, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn point_to_curve_coords() {
+        let curve = NurbsCurve::from_control_points(
+            3,
+            [
+                [0., 0., 0.],
+                [1., 2., 0.],
+                [2., -1., 1.],
+                [3., 1., 0.],
+                [4., 0., 0.],
+            ],
+        );
+
+        for t in [0., 0.1, 0.25, 0.5, 0.9, 1.] {
+            let point = curve.point_from_curve_coords([t]);
+            assert_abs_diff_eq!(
+                curve.point_to_curve_coords(point),
+                Point::from([t]),
+                epsilon = 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        let curve = NurbsCurve::from_control_points(
+            3,
+            [[0., 0.], [1., 2.], [2., -1.], [3., 1.], [4., 0.]],
+        );
+        let reversed = curve.clone().reverse();
+
+        assert_eq!(reversed.domain(), [-Scalar::ONE, Scalar::ZERO]);
+
+        for t in [0., 0.3, 0.7, 1.] {
+            assert_abs_diff_eq!(
+                reversed.point_from_curve_coords([-t]),
+                curve.point_from_curve_coords([t]),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn surface() {
+        // A bilinear patch that's twisted in the middle.
+        let surface = NurbsSurface::from_control_points(
+            [2, 1],
+            vec![
+                vec![Point::from([0., 0., 0.]), Point::from([0., 1., 0.])],
+                vec![Point::from([1., 0., 1.]), Point::from([1., 1., -1.])],
+                vec![Point::from([2., 0., 0.]), Point::from([2., 1., 0.])],
+            ],
+        );
+
+        assert_eq!(
+            surface.point_from_surface_coords([0.5, 0.5]),
+            Point::from([1., 0.5, 0.]),
+        );
+
+        for uv in [[0., 0.], [0.3, 0.6], [0.5, 0.5], [1., 0.2]] {
+            let point = surface.point_from_surface_coords(uv);
+            assert_abs_diff_eq!(
+                surface.point_to_surface_coords(point),
+                Point::from(uv),
+                epsilon = 1e-9
+            );
+        }
+
+        let [du, dv] = surface.derivatives([0., 0.]);
+        assert_abs_diff_eq!(du, Vector::from([2., 0., 2.]), epsilon = 1e-12);
+        assert_abs_diff_eq!(dv, Vector::from([0., 1., 0.]), epsilon = 1e-12);
+
+        let curve = surface.curve_at_v(0.6);
+        assert_abs_diff_eq!(
+            curve.point_from_curve_coords([0.3]),
+            surface.point_from_surface_coords([0.3, 0.6]),
+            epsilon = 1e-12
+        );
+        let curve = surface.curve_at_u(0.3);
+        assert_abs_diff_eq!(
+            curve.point_from_curve_coords([0.6]),
+            surface.point_from_surface_coords([0.3, 0.6]),
+            epsilon = 1e-12
+        );
+
+        let reversed = surface.clone().reverse();
+        assert_abs_diff_eq!(
+            reversed.point_from_surface_coords([-0.3, 0.6]),
+            surface.point_from_surface_coords([0.3, 0.6]),
+            epsilon = 1e-12
+        );
+    }
+}
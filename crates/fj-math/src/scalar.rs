@@ -71,6 +71,11 @@ impl Scalar {
         self.0.max(other.0).into()
     }
 
+    /// Compute the minimum of this and another scalar
+    pub fn min(self, other: Self) -> Self {
+        self.0.min(other.0).into()
+    }
+
     /// Compute the smallest integer larger than or equal to this scalar
     pub fn ceil(self) -> Self {
         self.0.ceil().into()
@@ -81,6 +86,11 @@ impl Scalar {
         self.0.round().into()
     }
 
+    /// Compute the square root
+    pub fn sqrt(self) -> Self {
+        self.0.sqrt().into()
+    }
+
     /// Compute the cosine
     pub fn cos(self) -> Self {
         self.0.cos().into()
@@ -2,7 +2,7 @@ use std::ops;
 
 use nalgebra::Perspective3;
 
-use crate::{Circle, Line, Scalar};
+use crate::{Circle, Ellipse, Line, NurbsCurve, NurbsSurface, Scalar};
 
 use super::{Aabb, Point, Segment, Triangle, Vector};
 
@@ -40,6 +40,42 @@ impl Transform {
         ))
     }
 
+    /// Construct a scaling
+    ///
+    /// Each component of `factors` defines the scale factor along the
+    /// respective axis. A non-uniform scaling might turn circles into ellipses.
+    pub fn scale(factors: impl Into<Vector<3>>) -> Self {
+        let factors = factors.into();
+
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            nalgebra::OMatrix::new_nonuniform_scaling(&factors.to_na()),
+        ))
+    }
+
+    /// Construct a reflection
+    ///
+    /// Reflects about the plane that passes through the origin and is
+    /// perpendicular to `normal`. The length of `normal` is irrelevant.
+    pub fn reflection(normal: impl Into<Vector<3>>) -> Self {
+        let normal = normal.into().normalize().to_na();
+
+        let linear =
+            nalgebra::Matrix3::identity() - normal * normal.transpose() * 2.;
+
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            linear.to_homogeneous(),
+        ))
+    }
+
+    /// Indicate whether the transform reverses orientation
+    ///
+    /// This is the case, if the transform contains a reflection. Surfaces and
+    /// cycles transformed with such a transform end up facing the wrong way,
+    /// unless they are explicitly reversed.
+    pub fn reverses_orientation(&self) -> bool {
+        self.0.matrix().fixed_slice::<3, 3>(0, 0).determinant() < 0.
+    }
+
     /// Transform the given point
     pub fn transform_point(&self, point: &Point<3>) -> Point<3> {
         Point::from(self.0.transform_point(&point.to_na()))
@@ -88,6 +124,55 @@ impl Transform {
         }
     }
 
+    /// Transform the given ellipse
+    pub fn transform_ellipse(&self, ellipse: &Ellipse<3>) -> Ellipse<3> {
+        Ellipse {
+            center: self.transform_point(&ellipse.center),
+            a: self.transform_vector(&ellipse.a),
+            b: self.transform_vector(&ellipse.b),
+        }
+    }
+
+    /// Transform the given NURBS curve
+    ///
+    /// NURBS curves are invariant under affine transforms, so transforming the
+    /// control points is sufficient.
+    pub fn transform_nurbs_curve(
+        &self,
+        curve: &NurbsCurve<3>,
+    ) -> NurbsCurve<3> {
+        NurbsCurve {
+            control_points: curve
+                .control_points
+                .iter()
+                .map(|point| self.transform_point(point))
+                .collect(),
+            ..curve.clone()
+        }
+    }
+
+    /// Transform the given NURBS surface
+    ///
+    /// NURBS surfaces are invariant under affine transforms, so transforming
+    /// the control points is sufficient.
+    pub fn transform_nurbs_surface(
+        &self,
+        surface: &NurbsSurface,
+    ) -> NurbsSurface {
+        NurbsSurface {
+            control_points: surface
+                .control_points
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|point| self.transform_point(point))
+                        .collect()
+                })
+                .collect(),
+            ..surface.clone()
+        }
+    }
+
     /// Inverse transform
     pub fn inverse(&self) -> Transform {
         Self(self.0.inverse())
@@ -120,11 +205,14 @@ impl Transform {
     }
 
     /// Transform the given axis-aligned bounding box
+    ///
+    /// All vertices of the bounding box are transformed, and the result is the
+    /// bounding box that contains all of them. This makes sure the result is
+    /// correct for rotations and reflections too.
     pub fn transform_aabb(&self, aabb: &Aabb<3>) -> Aabb<3> {
-        Aabb {
-            min: self.transform_point(&aabb.min),
-            max: self.transform_point(&aabb.max),
-        }
+        Aabb::<3>::from_points(
+            aabb.vertices().map(|vertex| self.transform_point(&vertex)),
+        )
     }
 
     /// Exposes the data of this Transform as a slice of f64.
@@ -145,7 +233,7 @@ impl ops::Mul<Self> for Transform {
 mod tests {
     use approx::assert_abs_diff_eq;
 
-    use crate::{Line, Point, Scalar, Vector};
+    use crate::{Aabb, Line, Point, Scalar, Vector};
 
     use super::Transform;
 
@@ -169,4 +257,58 @@ mod tests {
             epsilon = 1e-8,
         );
     }
+
+    #[test]
+    fn reflection() {
+        let transform = Transform::reflection([1., 0., 0.]);
+
+        assert_eq!(
+            transform.transform_point(&Point::from([1., 2., 3.])),
+            Point::from([-1., 2., 3.]),
+        );
+        assert!(transform.reverses_orientation());
+    }
+
+    #[test]
+    fn reverses_orientation() {
+        let transform = Transform::translation([1., 2., 3.])
+            * Transform::rotation(Vector::unit_z() * (Scalar::PI / 2.));
+        assert!(!transform.reverses_orientation());
+
+        let transform = transform * Transform::reflection([1., 1., 0.]);
+        assert!(transform.reverses_orientation());
+    }
+
+    #[test]
+    fn scale() {
+        let transform = Transform::scale([1., 2., 3.]);
+
+        assert_eq!(
+            transform.transform_point(&Point::from([1., 1., 1.])),
+            Point::from([1., 2., 3.]),
+        );
+        assert!(!transform.reverses_orientation());
+    }
+
+    #[test]
+    fn transform_aabb() {
+        let aabb = Aabb {
+            min: Point::from([0., 0., 0.]),
+            max: Point::from([2., 1., 1.]),
+        };
+
+        let transform =
+            Transform::rotation(Vector::unit_z() * (Scalar::PI / 2.));
+
+        assert_abs_diff_eq!(
+            transform.transform_aabb(&aabb).min,
+            Point::from([-1., 0., 0.]),
+            epsilon = 1e-8,
+        );
+        assert_abs_diff_eq!(
+            transform.transform_aabb(&aabb).max,
+            Point::from([0., 2., 1.]),
+            epsilon = 1e-8,
+        );
+    }
 }
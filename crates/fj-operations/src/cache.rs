@@ -0,0 +1,684 @@
+//! Caching of shapes across multiple runs of the shape processor
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    mem,
+    sync::{Mutex, MutexGuard},
+};
+
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_kernel::{
+    algorithms::{triangulate, Tolerance},
+    shape::{Shape, ValidationConfig, ValidationError},
+};
+use fj_math::Point;
+
+use crate::ToShape;
+
+/// Caches the shapes and meshes created from the nodes of an [`fj::Shape`]
+///
+/// Each node is identified by a [`Key`], a digest of its structure. The key
+/// covers the node itself, all nodes below it, as well as the tolerance and the
+/// validation configuration. When a model is reloaded, only the nodes that have
+/// changed, and those above them, need to be converted again.
+///
+/// Entries that were not used while processing a shape are removed afterwards,
+/// so the cache only ever contains what is needed to process the latest
+/// version of a model.
+#[derive(Default)]
+pub struct ShapeCache {
+    entries: Mutex<HashMap<Key, Entry>>,
+
+    /// The digests of the nodes that are currently being processed
+    ///
+    /// Nodes are identified by their address, which is only unique while they
+    /// are borrowed. Digests are therefore only kept for the duration of the
+    /// outermost call that needed them.
+    digests: Mutex<HashMap<NodeId, Digest>>,
+}
+
+impl ShapeCache {
+    /// Construct an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Convert a node into a shape, reusing the cached shape if possible
+    ///
+    /// The returned shape may be shared with the cache, and with the shapes of
+    /// other nodes. It must not be modified. Use [`Shape::clone_shape`] to
+    /// create a copy that can be modified.
+    pub(crate) fn to_shape<'r>(
+        &self,
+        node: impl Into<Node<'r>>,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        let node = node.into();
+        let (key, _scope) = self.key(node, config, tolerance);
+
+        if let Some(shape) = self.use_entry(&key, |entry| entry.shape.clone()) {
+            return Ok(shape);
+        }
+
+        let shape = node.to_shape(config, tolerance, self, debug_info)?;
+
+        let children = node
+            .children()
+            .into_iter()
+            .map(|child| self.key(child, config, tolerance).0)
+            .collect();
+        self.entries().insert(
+            key,
+            Entry {
+                shape: shape.clone(),
+                mesh: None,
+                children,
+                used: true,
+            },
+        );
+
+        Ok(shape)
+    }
+
+    /// Convert multiple independent nodes into shapes
+    ///
+    /// Works like [`ShapeCache::to_shape`]. If the `parallel` feature is
+    /// enabled, the nodes are converted in parallel. The result is the same
+    /// either way, and the shapes are returned in the order of the nodes.
+    pub(crate) fn to_shapes<'r, N>(
+        &self,
+        nodes: Vec<N>,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Vec<Shape>, ValidationError>
+    where
+        N: Into<Node<'r>> + Send,
+    {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            let results: Vec<_> = nodes
+                .into_par_iter()
+                .map(|node| {
+                    let mut debug_info = DebugInfo::new();
+                    let shape =
+                        self.to_shape(node, config, tolerance, &mut debug_info);
+                    (shape, debug_info)
+                })
+                .collect();
+
+            let mut shapes = Vec::new();
+            for (shape, node_debug_info) in results {
+                debug_info
+                    .triangle_edge_checks
+                    .extend(node_debug_info.triangle_edge_checks);
+                shapes.push(shape?);
+            }
+
+            Ok(shapes)
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            nodes
+                .into_iter()
+                .map(|node| self.to_shape(node, config, tolerance, debug_info))
+                .collect()
+        }
+    }
+
+    /// Triangulate the shape of a node, reusing cached meshes if possible
+    ///
+    /// The node must have been converted using [`ShapeCache::to_shape`]
+    /// before.
+    ///
+    /// The faces of a group are the faces of its members, so the mesh of a
+    /// group is assembled from their meshes. Changing one member of a group
+    /// only requires that member to be triangulated again.
+    pub(crate) fn triangulate<'r>(
+        &self,
+        node: impl Into<Node<'r>>,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Mesh<Point<3>> {
+        let node = node.into();
+
+        if let Node::Shape3d(fj::Shape3d::Group(group)) = node {
+            let mut mesh =
+                self.triangulate(&group.a, config, tolerance, debug_info);
+            let b = self.triangulate(&group.b, config, tolerance, debug_info);
+
+            for triangle in b.triangles() {
+                mesh.push_triangle(triangle.points, triangle.color);
+            }
+
+            return mesh;
+        }
+
+        let (key, _scope) = self.key(node, config, tolerance);
+        let (shape, mesh) = self
+            .use_entry(&key, |entry| (entry.shape.clone(), entry.mesh.clone()))
+            // Can't panic, unless this method is used incorrectly.
+            .expect("Node must be converted to shape before triangulating");
+
+        if let Some((mesh, mesh_debug_info)) = mesh {
+            debug_info
+                .triangle_edge_checks
+                .extend(mesh_debug_info.triangle_edge_checks);
+            return mesh;
+        }
+
+        let mut mesh_debug_info = DebugInfo::new();
+        let mesh = triangulate(shape, tolerance, &mut mesh_debug_info);
+
+        debug_info
+            .triangle_edge_checks
+            .extend(mesh_debug_info.triangle_edge_checks.iter().cloned());
+        if let Some(entry) = self.entries().get_mut(&key) {
+            entry.mesh = Some((mesh.clone(), mesh_debug_info));
+        }
+
+        mesh
+    }
+
+    /// Remove all entries that haven't been used since the last call
+    pub(crate) fn remove_unused(&self) {
+        let mut entries = self.entries();
+
+        entries.retain(|_, entry| entry.used);
+        for entry in entries.values_mut() {
+            entry.used = false;
+        }
+    }
+
+    /// Mark an entry and all entries of the nodes below it as used
+    ///
+    /// Returns `None`, if there's no entry for `key`.
+    fn use_entry<R>(
+        &self,
+        key: &Key,
+        f: impl FnOnce(&Entry) -> R,
+    ) -> Option<R> {
+        let mut entries = self.entries();
+
+        let result = f(entries.get(key)?);
+
+        let mut keys = vec![*key];
+        while let Some(key) = keys.pop() {
+            if let Some(entry) = entries.get_mut(&key) {
+                entry.used = true;
+                keys.extend(entry.children.iter().copied());
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Compute the key of a node
+    ///
+    /// If the digest of the node isn't known yet, the digests of the node and
+    /// all nodes below it are computed, and kept until the returned
+    /// [`DigestScope`] is dropped. Any calls for those nodes in the meantime
+    /// can reuse them.
+    fn key(
+        &self,
+        node: Node,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+    ) -> (Key, DigestScope<'_>) {
+        let mut scope = DigestScope {
+            cache: self,
+            nodes: Vec::new(),
+        };
+
+        let known = self.digests().get(&node.id()).copied();
+        let digest = known.unwrap_or_else(|| {
+            let mut digests = HashMap::new();
+            let digest = node.digest(&mut digests);
+
+            scope.nodes.extend(digests.keys().copied());
+            self.digests().extend(digests);
+
+            digest
+        });
+
+        let mut state = DigestWriter::new();
+        digest.hash(&mut state);
+        config.hash(&mut state);
+        tolerance.hash(&mut state);
+
+        (Key(state.digest()), scope)
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<Key, Entry>> {
+        // Can't panic, unless another thread panicked while holding the lock,
+        // in which case we don't care about the cache anymore.
+        self.entries.lock().expect("Shape cache is poisoned")
+    }
+
+    fn digests(&self) -> MutexGuard<'_, HashMap<NodeId, Digest>> {
+        // Can't panic, for the same reason as above.
+        self.digests.lock().expect("Shape cache is poisoned")
+    }
+}
+
+/// Removes the digests computed by [`ShapeCache::key`], once dropped
+struct DigestScope<'r> {
+    cache: &'r ShapeCache,
+    nodes: Vec<NodeId>,
+}
+
+impl Drop for DigestScope<'_> {
+    fn drop(&mut self) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut digests = self.cache.digests();
+        for node in &self.nodes {
+            digests.remove(node);
+        }
+    }
+}
+
+struct Entry {
+    shape: Shape,
+    mesh: Option<(Mesh<Point<3>>, DebugInfo)>,
+
+    /// The keys of the nodes directly below the node of this entry
+    children: Vec<Key>,
+
+    /// Whether the entry has been used since the last cleanup
+    used: bool,
+}
+
+/// A node of an [`fj::Shape`]
+#[derive(Clone, Copy)]
+pub(crate) enum Node<'r> {
+    Shape2d(&'r fj::Shape2d),
+    Shape3d(&'r fj::Shape3d),
+}
+
+/// Identifies a node in the cache
+///
+/// The key is a digest of everything that the conversion of a node depends
+/// on. Two nodes with the same key result in the same shape.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Key(Digest);
+
+/// A digest of the structure of a node, including all nodes below it
+///
+/// At 128 bits, it is large enough that two different nodes won't end up with
+/// the same digest in practice.
+type Digest = [u64; 2];
+
+/// Identifies a node by its address
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+enum NodeId {
+    Shape2d(usize),
+    Shape3d(usize),
+}
+
+/// Computes a [`Digest`] from two independently seeded hashers
+struct DigestWriter([DefaultHasher; 2]);
+
+impl DigestWriter {
+    fn new() -> Self {
+        let mut seeded = DefaultHasher::new();
+        seeded.write_u8(1);
+
+        Self([DefaultHasher::new(), seeded])
+    }
+
+    fn digest(&self) -> Digest {
+        let [a, b] = &self.0;
+        [a.finish(), b.finish()]
+    }
+}
+
+impl Hasher for DigestWriter {
+    fn finish(&self) -> u64 {
+        self.0[0].finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for state in &mut self.0 {
+            state.write(bytes);
+        }
+    }
+}
+
+impl<'r> Node<'r> {
+    fn id(self) -> NodeId {
+        match self {
+            Self::Shape2d(shape) => {
+                NodeId::Shape2d(shape as *const fj::Shape2d as usize)
+            }
+            Self::Shape3d(shape) => {
+                NodeId::Shape3d(shape as *const fj::Shape3d as usize)
+            }
+        }
+    }
+
+    /// Compute the digest of the node, bottom-up
+    ///
+    /// The digest covers the fields of the node and the digests of its
+    /// children. The digests of all nodes are stored in `digests`.
+    fn digest(self, digests: &mut HashMap<NodeId, Digest>) -> Digest {
+        let mut state = DigestWriter::new();
+
+        match self {
+            Self::Shape2d(shape) => hash_2d(shape, &mut state),
+            Self::Shape3d(shape) => hash_3d(shape, &mut state),
+        }
+        for child in self.children() {
+            child.digest(digests).hash(&mut state);
+        }
+
+        let digest = state.digest();
+        digests.insert(self.id(), digest);
+
+        digest
+    }
+
+    fn to_shape(
+        self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        cache: &ShapeCache,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        match self {
+            Self::Shape2d(shape) => {
+                shape.to_shape(config, tolerance, cache, debug_info)
+            }
+            Self::Shape3d(shape) => {
+                shape.to_shape(config, tolerance, cache, debug_info)
+            }
+        }
+    }
+
+    fn children(self) -> Vec<Node<'r>> {
+        match self {
+            Self::Shape2d(fj::Shape2d::Difference(difference)) => {
+                let mut children = vec![difference.shape().into()];
+                children.extend(difference.holes().iter().map(Node::from));
+                children
+            }
+            Self::Shape2d(_) => Vec::new(),
+            Self::Shape3d(shape) => match shape {
+                fj::Shape3d::Group(group) => {
+                    vec![(&group.a).into(), (&group.b).into()]
+                }
+                fj::Shape3d::LinearPattern(pattern) => {
+                    vec![(&pattern.shape).into()]
+                }
+                fj::Shape3d::Mesh(_) => Vec::new(),
+                fj::Shape3d::Mirror(mirror) => vec![(&mirror.shape).into()],
+                fj::Shape3d::PolarPattern(pattern) => {
+                    vec![(&pattern.shape).into()]
+                }
+                fj::Shape3d::Sweep(sweep) => vec![sweep.shape().into()],
+                fj::Shape3d::Transform(transform) => {
+                    vec![(&transform.shape).into()]
+                }
+            },
+        }
+    }
+}
+
+impl<'r> From<&'r fj::Shape> for Node<'r> {
+    fn from(shape: &'r fj::Shape) -> Self {
+        match shape {
+            fj::Shape::Shape2d(shape) => Self::Shape2d(shape),
+            fj::Shape::Shape3d(shape) => Self::Shape3d(shape),
+        }
+    }
+}
+
+impl<'r> From<&'r fj::Shape2d> for Node<'r> {
+    fn from(shape: &'r fj::Shape2d) -> Self {
+        Self::Shape2d(shape)
+    }
+}
+
+impl<'r> From<&'r fj::Shape3d> for Node<'r> {
+    fn from(shape: &'r fj::Shape3d) -> Self {
+        Self::Shape3d(shape)
+    }
+}
+
+// The shapes from the `fj` crate don't implement `Hash`, as they contain
+// floating-point numbers. Those are hashed by their bit patterns here. Numbers
+// that are equal, but have different bit patterns (like `0.0` and `-0.0`) just
+// result in a cache miss.
+//
+// Sequences of varying length are prefixed by their length, so the data of a
+// node can't be mistaken for that of another node.
+//
+// Only the fields of a node itself are hashed here. Its children are covered by
+// their digests, which are hashed in `Node::digest`.
+
+fn hash_2d(shape: &fj::Shape2d, state: &mut impl Hasher) {
+    mem::discriminant(shape).hash(state);
+
+    match shape {
+        fj::Shape2d::Circle(circle) => {
+            hash_f64s([circle.radius()], state);
+            circle.color().hash(state);
+        }
+        fj::Shape2d::Difference(difference) => {
+            difference.holes().len().hash(state);
+        }
+        fj::Shape2d::Ellipse(ellipse) => {
+            hash_f64s(ellipse.radii(), state);
+            ellipse.color().hash(state);
+        }
+        fj::Shape2d::Sketch(sketch) => {
+            let points = sketch.to_points();
+            points.len().hash(state);
+            for point in points {
+                hash_f64s(point, state);
+            }
+
+            let segments = sketch.to_segments();
+            segments.len().hash(state);
+            for segment in segments {
+                hash_segment(&segment, state);
+            }
+            sketch.color().hash(state);
+        }
+        fj::Shape2d::Text(text) => {
+            text.text().hash(state);
+            text.font().as_bytes().hash(state);
+            hash_f64s([text.size()], state);
+            text.color().hash(state);
+        }
+    }
+}
+
+fn hash_3d(shape: &fj::Shape3d, state: &mut impl Hasher) {
+    mem::discriminant(shape).hash(state);
+
+    match shape {
+        fj::Shape3d::Group(_) => {}
+        fj::Shape3d::LinearPattern(pattern) => {
+            hash_f64s(pattern.offset, state);
+            pattern.count.hash(state);
+        }
+        fj::Shape3d::Mesh(mesh) => {
+            let triangles = mesh.triangles();
+            triangles.len().hash(state);
+            for triangle in triangles {
+                for &point in triangle {
+                    hash_f64s(point, state);
+                }
+            }
+            mesh.color().hash(state);
+            mesh.is_reference().hash(state);
+        }
+        fj::Shape3d::Mirror(mirror) => {
+            hash_f64s(mirror.normal, state);
+            hash_f64s(mirror.origin, state);
+        }
+        fj::Shape3d::PolarPattern(pattern) => {
+            hash_f64s(pattern.axis, state);
+            hash_f64s([pattern.angle.rad()], state);
+            pattern.count.hash(state);
+        }
+        fj::Shape3d::Sweep(sweep) => {
+            hash_f64s(sweep.path(), state);
+        }
+        fj::Shape3d::Transform(transform) => {
+            hash_f64s(transform.scale, state);
+            hash_f64s(transform.axis, state);
+            hash_f64s([transform.angle.rad()], state);
+            hash_f64s(transform.offset, state);
+        }
+    }
+}
+
+fn hash_segment(segment: &fj::SketchSegment, state: &mut impl Hasher) {
+    mem::discriminant(segment).hash(state);
+
+    // The points of the segment are already covered by the points of the
+    // sketch. Only what's not a point needs to be hashed here.
+    match segment {
+        fj::SketchSegment::Arc {
+            counterclockwise, ..
+        } => counterclockwise.hash(state),
+        fj::SketchSegment::BSpline {
+            degree,
+            control_points,
+        } => {
+            degree.hash(state);
+            control_points.len().hash(state);
+        }
+        fj::SketchSegment::Line { .. }
+        | fj::SketchSegment::CubicBezier { .. } => {}
+    }
+}
+
+fn hash_f64s<const D: usize>(values: [f64; D], state: &mut impl Hasher) {
+    for value in values {
+        value.to_bits().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::debug::DebugInfo;
+    use fj_kernel::{
+        algorithms::Tolerance,
+        shape::{Shape, ValidationConfig, ValidationLevel},
+    };
+
+    use super::{Node, ShapeCache};
+
+    #[test]
+    fn reuse_unchanged_nodes() {
+        let cache = ShapeCache::new();
+        let config = ValidationConfig::default();
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        let a = prism(1., 1.);
+        let first = group(&a, &prism(2., 1.));
+        let shape_a = to_shape(&cache, &a, &config, tolerance);
+        to_shape(&cache, &first, &config, tolerance);
+        cache.remove_unused();
+
+        // Changing one member of the group doesn't affect the other one.
+        let second = group(&a, &prism(3., 1.));
+        to_shape(&cache, &second, &config, tolerance);
+        cache.remove_unused();
+
+        assert!(shares_faces(
+            &to_shape(&cache, &a, &config, tolerance),
+            &shape_a
+        ));
+
+        // Digests are only kept while the nodes are being processed.
+        assert!(cache.digests().is_empty());
+
+        let (second, _) = cache.key(Node::from(&second), &config, tolerance);
+        let (first, _) = cache.key(Node::from(&first), &config, tolerance);
+
+        let entries = cache.entries();
+        assert!(entries.contains_key(&second));
+        assert!(!entries.contains_key(&first));
+    }
+
+    #[test]
+    fn invalidate_changed_nodes() {
+        let cache = ShapeCache::new();
+        let config = ValidationConfig::default();
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        let shape = to_shape(&cache, &prism(1., 1.), &config, tolerance);
+        assert!(shares_faces(
+            &to_shape(&cache, &prism(1., 1.), &config, tolerance),
+            &shape
+        ));
+
+        // Changed parameter
+        assert!(!shares_faces(
+            &to_shape(&cache, &prism(1., 2.), &config, tolerance),
+            &shape
+        ));
+
+        // Changed tolerance
+        let changed_tolerance = Tolerance::from_scalar(0.2).unwrap();
+        assert!(!shares_faces(
+            &to_shape(&cache, &prism(1., 1.), &config, changed_tolerance),
+            &shape
+        ));
+
+        // Changed validation configuration
+        let changed_config = ValidationConfig {
+            level: ValidationLevel::Structural,
+            ..config
+        };
+        assert!(!shares_faces(
+            &to_shape(&cache, &prism(1., 1.), &changed_config, tolerance),
+            &shape
+        ));
+    }
+
+    fn to_shape(
+        cache: &ShapeCache,
+        shape: &fj::Shape3d,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+    ) -> Shape {
+        cache
+            .to_shape(shape, config, tolerance, &mut DebugInfo::new())
+            .unwrap()
+    }
+
+    /// Faces are only equal, if they are the same object
+    ///
+    /// Two shapes share their faces, if one was taken from the cache.
+    fn shares_faces(a: &Shape, b: &Shape) -> bool {
+        a.faces().eq(b.faces())
+    }
+
+    fn prism(size: f64, height: f64) -> fj::Shape3d {
+        let sketch =
+            fj::Sketch::from_points(vec![[0., 0.], [size, 0.], [0., size]]);
+        fj::Sweep::from_path(sketch.into(), [0., 0., height]).into()
+    }
+
+    fn group(a: &fj::Shape3d, b: &fj::Shape3d) -> fj::Shape3d {
+        fj::Group {
+            a: a.clone(),
+            b: b.clone(),
+        }
+        .into()
+    }
+}
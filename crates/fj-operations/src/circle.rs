@@ -2,20 +2,22 @@ use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::Tolerance,
     geometry::Surface,
-    shape::{Shape, ValidationError},
+    shape::{Shape, ValidationConfig, ValidationError},
     topology::{Cycle, Edge, Face},
 };
 use fj_math::{Aabb, Point, Scalar};
 
-use super::ToShape;
+use super::{cache::ShapeCache, ToShape};
 
 impl ToShape for fj::Circle {
     fn to_shape(
         &self,
+        config: &ValidationConfig,
         _: Tolerance,
+        _: &ShapeCache,
         _: &mut DebugInfo,
     ) -> Result<Shape, ValidationError> {
-        let mut shape = Shape::new();
+        let mut shape = Shape::new().with_validation_config(*config);
 
         // Circles have just a single round edge with no vertices. So none need
         // to be added here.
@@ -1,33 +1,43 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::Tolerance,
-    shape::{Handle, LocalForm, Shape, ValidationError, ValidationResult},
+    shape::{
+        Handle, LocalForm, Shape, ValidationConfig, ValidationError,
+        ValidationResult,
+    },
     topology::{Cycle, Edge, Face},
 };
 use fj_math::Aabb;
 
-use super::ToShape;
+use super::{cache::ShapeCache, ToShape};
 
 impl ToShape for fj::Difference2d {
     fn to_shape(
         &self,
+        config: &ValidationConfig,
         tolerance: Tolerance,
+        cache: &ShapeCache,
         debug_info: &mut DebugInfo,
     ) -> Result<Shape, ValidationError> {
-        // This method assumes that `b` is fully contained within `a`:
+        // This method assumes that the holes are fully contained within the
+        // shape, and don't overlap each other:
         // https://github.com/hannobraun/Fornjot/issues/92
 
-        let mut difference = Shape::new();
+        let mut difference = Shape::new().with_validation_config(*config);
 
         let mut exteriors = Vec::new();
         let mut interiors = Vec::new();
 
-        // Can be cleaned up, once `each_ref` and `try_map` are stable:
-        // - https://doc.rust-lang.org/std/primitive.array.html#method.each_ref
-        // - https://doc.rust-lang.org/std/primitive.array.html#method.try_map
-        let [a, b] = self.shapes();
-        let [a, b] = [a, b].map(|shape| shape.to_shape(tolerance, debug_info));
-        let [a, b] = [a?, b?];
+        let mut nodes = vec![self.shape()];
+        nodes.extend(self.holes());
+
+        let mut shapes = cache
+            .to_shapes(nodes, config, tolerance, debug_info)?
+            .into_iter();
+
+        // Can't panic. There's always one shape, the one the holes are
+        // subtracted from.
+        let a = shapes.next().expect("Expected shape to subtract from");
 
         if let Some(face) = a.faces().next() {
             // If there's at least one face to subtract from, we can proceed.
@@ -50,26 +60,30 @@ impl ToShape for fj::Difference2d {
                     exteriors.push(cycle);
                 }
                 for cycle in face.interiors.as_handle() {
+                    // These are interiors already, so they already have the
+                    // right orientation.
                     let cycle =
-                        add_cycle(cycle.clone(), &mut difference, true)?;
+                        add_cycle(cycle.clone(), &mut difference, false)?;
                     interiors.push(cycle);
                 }
             }
 
-            for face in b.faces() {
-                let face = face.get();
-                let face = face.brep();
-
-                assert_eq!(
-                    surface.get(),
-                    face.surface(),
-                    "Trying to subtract faces with different surfaces.",
-                );
-
-                for cycle in face.exteriors.as_handle() {
-                    let cycle =
-                        add_cycle(cycle.clone(), &mut difference, true)?;
-                    interiors.push(cycle);
+            for b in shapes {
+                for face in b.faces() {
+                    let face = face.get();
+                    let face = face.brep();
+
+                    assert_eq!(
+                        surface.get(),
+                        face.surface(),
+                        "Trying to subtract faces with different surfaces.",
+                    );
+
+                    for cycle in face.exteriors.as_handle() {
+                        let cycle =
+                            add_cycle(cycle.clone(), &mut difference, true)?;
+                        interiors.push(cycle);
+                    }
                 }
             }
 
@@ -81,6 +95,10 @@ impl ToShape for fj::Difference2d {
             ))?;
         }
 
+        // Merging the cycles might have made some of the curves we inserted
+        // redundant. Don't leave them lying around.
+        difference.gc();
+
         Ok(difference)
     }
 
@@ -88,7 +106,7 @@ impl ToShape for fj::Difference2d {
         // This is a conservative estimate of the bounding box: It's never going
         // to be bigger than the bounding box of the original shape that another
         // is being subtracted from.
-        self.shapes()[0].bounding_volume()
+        self.shape().bounding_volume()
     }
 }
 
@@ -128,3 +146,97 @@ fn add_cycle(
 
     Ok(cycle)
 }
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::debug::DebugInfo;
+    use fj_kernel::{
+        algorithms::{CycleApprox, Tolerance},
+        shape::{Shape, ValidationConfig},
+        topology::Cycle,
+    };
+    use fj_math::Scalar;
+
+    use crate::{cache::ShapeCache, ToShape};
+
+    #[test]
+    fn difference_with_multiple_holes() {
+        let difference = fj::Difference2d::from_shape_and_holes(
+            rectangle([0., 0.], [10., 5.]),
+            vec![rectangle([1., 1.], [3., 3.]), rectangle([6., 1.], [8., 3.])],
+        );
+
+        let shape = to_shape(&difference);
+        let faces: Vec<_> = shape.faces().collect();
+        assert_eq!(faces.len(), 1);
+
+        let face = faces[0].get();
+        let exteriors: Vec<_> = face.exteriors().collect();
+        let interiors: Vec<_> = face.interiors().collect();
+        assert_eq!(exteriors.len(), 1);
+        assert_eq!(interiors.len(), 2);
+
+        assert_eq!(signed_area(&exteriors[0]), Scalar::from(50.));
+        for interior in &interiors {
+            assert_eq!(signed_area(interior), Scalar::from(-4.));
+        }
+    }
+
+    #[test]
+    fn nested_difference() {
+        // Subtracting from a difference must keep its holes as they are, not
+        // subtract them a second time.
+        let inner = fj::Difference2d::from_shapes([
+            rectangle([0., 0.], [10., 5.]),
+            rectangle([1., 1.], [3., 3.]),
+        ]);
+        let difference = fj::Difference2d::from_shapes([
+            inner.into(),
+            rectangle([6., 1.], [8., 3.]),
+        ]);
+
+        let shape = to_shape(&difference);
+        let face = shape.faces().next().unwrap().get();
+
+        let interiors: Vec<_> = face.interiors().collect();
+        assert_eq!(interiors.len(), 2);
+        for interior in &interiors {
+            assert_eq!(signed_area(interior), Scalar::from(-4.));
+        }
+    }
+
+    fn rectangle([x0, y0]: [f64; 2], [x1, y1]: [f64; 2]) -> fj::Shape2d {
+        fj::Sketch::from_points(vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]])
+            .into()
+    }
+
+    fn to_shape(shape: &impl ToShape) -> Shape {
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        shape
+            .to_shape(
+                &ValidationConfig::default(),
+                tolerance,
+                &ShapeCache::new(),
+                &mut DebugInfo::new(),
+            )
+            .unwrap()
+    }
+
+    /// The area enclosed by a cycle in the xy-plane
+    ///
+    /// Positive, if the cycle is counter-clockwise.
+    fn signed_area(cycle: &Cycle<3>) -> Scalar {
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+        let points = CycleApprox::new(cycle, tolerance).points;
+
+        let mut area = Scalar::ZERO;
+        for segment in points.windows(2) {
+            let [a, b] =
+                [segment[0], segment[1]].map(|point| point.canonical());
+            area += a.x * b.y - b.x * a.y;
+        }
+
+        area / 2.
+    }
+}
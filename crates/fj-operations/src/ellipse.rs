@@ -0,0 +1,44 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::Tolerance,
+    geometry::Surface,
+    shape::{Shape, ValidationConfig, ValidationError},
+    topology::{Cycle, Edge, Face},
+};
+use fj_math::{Aabb, Point, Scalar};
+
+use super::{cache::ShapeCache, ToShape};
+
+impl ToShape for fj::Ellipse {
+    fn to_shape(
+        &self,
+        config: &ValidationConfig,
+        _: Tolerance,
+        _: &ShapeCache,
+        _: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        let mut shape = Shape::new().with_validation_config(*config);
+
+        // Like circles, ellipses have just a single round edge with no
+        // vertices.
+
+        let edge = Edge::builder(&mut shape)
+            .build_ellipse(self.radii().map(Scalar::from_f64))?;
+        shape.insert(Cycle::new(vec![edge]))?;
+
+        let cycles = shape.cycles();
+        let surface = shape.insert(Surface::xy_plane())?;
+        shape.insert(Face::new(surface, cycles, Vec::new(), self.color()))?;
+
+        Ok(shape)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let [x, y] = self.radii();
+
+        Aabb {
+            min: Point::from([-x, -y, 0.0]),
+            max: Point::from([x, y, 0.0]),
+        }
+    }
+}
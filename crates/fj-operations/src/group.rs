@@ -1,25 +1,32 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::Tolerance,
-    shape::{Shape, ValidationError},
+    shape::{Shape, ValidationConfig, ValidationError},
 };
 use fj_math::Aabb;
 
-use super::ToShape;
+use super::{cache::ShapeCache, ToShape};
 
 impl ToShape for fj::Group {
     fn to_shape(
         &self,
+        config: &ValidationConfig,
         tolerance: Tolerance,
+        cache: &ShapeCache,
         debug_info: &mut DebugInfo,
     ) -> Result<Shape, ValidationError> {
-        let mut shape = Shape::new();
+        let mut shape = Shape::new().with_validation_config(*config);
 
-        let a = self.a.to_shape(tolerance, debug_info)?;
-        let b = self.b.to_shape(tolerance, debug_info)?;
+        let members = cache.to_shapes(
+            vec![&self.a, &self.b],
+            config,
+            tolerance,
+            debug_info,
+        )?;
 
-        shape.merge_shape(&a)?;
-        shape.merge_shape(&b)?;
+        for member in members {
+            shape.merge_shape(&member)?;
+        }
 
         Ok(shape)
     }
@@ -16,28 +16,42 @@
 
 #![warn(missing_docs)]
 
+pub mod cache;
 pub mod shape_processor;
 
 mod circle;
 mod difference_2d;
+mod ellipse;
 mod group;
+mod mesh;
+mod mirror;
+mod pattern;
 mod sketch;
 mod sweep;
+mod text;
 mod transform;
 
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::Tolerance,
-    shape::{Shape, ValidationError},
+    shape::{Shape, ValidationConfig, ValidationError},
 };
 use fj_math::Aabb;
 
+use self::cache::ShapeCache;
+
 /// Implemented for all operations from the [`fj`] crate
 pub trait ToShape {
     /// Compute the boundary representation of the shape
+    ///
+    /// All shapes created in the process are validated according to `config`.
+    /// The shapes of child nodes are taken from `cache`, if they haven't
+    /// changed since they were last converted.
     fn to_shape(
         &self,
+        config: &ValidationConfig,
         tolerance: Tolerance,
+        cache: &ShapeCache,
         debug_info: &mut DebugInfo,
     ) -> Result<Shape, ValidationError>;
 
@@ -67,7 +81,9 @@ macro_rules! dispatch {
                     match self {
                         Self::Circle(shape) => shape.$method($($arg_name,)*),
                         Self::Difference(shape) => shape.$method($($arg_name,)*),
+                        Self::Ellipse(shape) => shape.$method($($arg_name,)*),
                         Self::Sketch(shape) => shape.$method($($arg_name,)*),
+                        Self::Text(shape) => shape.$method($($arg_name,)*),
                     }
                 }
             )*
@@ -78,6 +94,10 @@ macro_rules! dispatch {
                 fn $method(&self, $($arg_name: $arg_ty,)*) -> $ret {
                     match self {
                         Self::Group(shape) => shape.$method($($arg_name,)*),
+                        Self::LinearPattern(shape) => shape.$method($($arg_name,)*),
+                        Self::Mesh(shape) => shape.$method($($arg_name,)*),
+                        Self::Mirror(shape) => shape.$method($($arg_name,)*),
+                        Self::PolarPattern(shape) => shape.$method($($arg_name,)*),
                         Self::Sweep(shape) => shape.$method($($arg_name,)*),
                         Self::Transform(shape) => shape.$method($($arg_name,)*),
                     }
@@ -89,7 +109,9 @@ macro_rules! dispatch {
 
 dispatch! {
     to_shape(
+        config: &ValidationConfig,
         tolerance: Tolerance,
+        cache: &ShapeCache,
         debug_info: &mut DebugInfo,
     ) -> Result<Shape, ValidationError>;
     bounding_volume() -> Aabb<3>;
@@ -0,0 +1,52 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Shape, ValidationConfig, ValidationError},
+    topology::Face,
+};
+use fj_math::{Aabb, Point, Scalar, Triangle};
+
+use super::{cache::ShapeCache, ToShape};
+
+impl ToShape for fj::Mesh {
+    fn to_shape(
+        &self,
+        config: &ValidationConfig,
+        _: Tolerance,
+        _: &ShapeCache,
+        _: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        let mut shape = Shape::new().with_validation_config(*config);
+
+        let triangles = self
+            .to_triangles()
+            .into_iter()
+            .map(|points| points.map(Point::from))
+            // Meshes from files can contain degenerate triangles. They don't
+            // contribute anything to the shape, and `Triangle` can't represent
+            // them, so they are skipped.
+            .filter(|&[a, b, c]| {
+                (b - a).cross(&(c - a)).magnitude() != Scalar::ZERO
+            })
+            .map(|points| (Triangle::from_points(points), self.color()))
+            .collect();
+
+        shape.insert(Face::Triangles(triangles))?;
+
+        Ok(shape)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let points: Vec<_> =
+            self.to_triangles().into_iter().flatten().collect();
+
+        if points.is_empty() {
+            return Aabb {
+                min: Point::origin(),
+                max: Point::origin(),
+            };
+        }
+
+        Aabb::<3>::from_points(points)
+    }
+}
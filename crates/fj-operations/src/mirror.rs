@@ -0,0 +1,41 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{transform_shape, Tolerance},
+    shape::{Shape, ValidationConfig, ValidationError},
+};
+use fj_math::{Aabb, Transform, Vector};
+
+use super::{cache::ShapeCache, ToShape};
+
+impl ToShape for fj::Mirror {
+    fn to_shape(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        cache: &ShapeCache,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        // The shape might be shared with the cache, so it can't be transformed
+        // in place.
+        let (mut shape, _) = cache
+            .to_shape(&self.shape, config, tolerance, debug_info)?
+            .clone_shape();
+        let transform = transform(self);
+
+        transform_shape(&mut shape, &transform)?;
+
+        Ok(shape)
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        transform(self).transform_aabb(&self.shape.bounding_volume())
+    }
+}
+
+fn transform(mirror: &fj::Mirror) -> Transform {
+    let origin = Vector::from(mirror.origin);
+
+    Transform::translation(origin)
+        * Transform::reflection(mirror.normal)
+        * Transform::translation(-origin)
+}
@@ -0,0 +1,420 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{transform_shape, Tolerance},
+    shape::{Shape, ValidationConfig, ValidationError},
+};
+use fj_math::{Aabb, Point, Transform, Vector};
+
+use super::{cache::ShapeCache, shape_processor::InvalidPattern, ToShape};
+
+impl ToShape for fj::LinearPattern {
+    fn to_shape(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        cache: &ShapeCache,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        let original =
+            cache.to_shape(&self.shape, config, tolerance, debug_info)?;
+        instantiate(&original, linear_transforms(self))
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        bounding_volume(&self.shape, linear_transforms(self))
+    }
+}
+
+impl ToShape for fj::PolarPattern {
+    fn to_shape(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        cache: &ShapeCache,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        let original =
+            cache.to_shape(&self.shape, config, tolerance, debug_info)?;
+        instantiate(&original, polar_transforms(self))
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        bounding_volume(&self.shape, polar_transforms(self))
+    }
+}
+
+/// Check that a linear pattern can be evaluated
+pub(crate) fn check_linear(
+    pattern: &fj::LinearPattern,
+) -> Result<(), InvalidPattern> {
+    if pattern.count == 0 {
+        return Err(InvalidPattern::NoCopies);
+    }
+    if !pattern.offset.iter().all(|value| value.is_finite()) {
+        return Err(InvalidPattern::NotFinite);
+    }
+
+    Ok(())
+}
+
+/// Check that a polar pattern can be evaluated
+pub(crate) fn check_polar(
+    pattern: &fj::PolarPattern,
+) -> Result<(), InvalidPattern> {
+    polar_axis(pattern).map(|_| ())
+}
+
+/// Compute the transforms of a linear pattern
+///
+/// Invalid patterns, as reported by [`check_linear`], have no copies.
+fn linear_transforms(
+    pattern: &fj::LinearPattern,
+) -> impl Iterator<Item = Transform> {
+    let (offset, count) = match check_linear(pattern) {
+        Ok(()) => (Vector::from(pattern.offset), pattern.count),
+        Err(_) => (Vector::from([0.; 3]), 0),
+    };
+
+    (0..count).map(move |i| Transform::translation(offset * i as f64))
+}
+
+/// Compute the transforms of a polar pattern
+///
+/// Invalid patterns, as reported by [`check_polar`], have no copies.
+fn polar_transforms(
+    pattern: &fj::PolarPattern,
+) -> impl Iterator<Item = Transform> {
+    let (axis, count) = match polar_axis(pattern) {
+        Ok(axis) => (axis, pattern.count),
+        Err(_) => (Vector::unit_z(), 0),
+    };
+    let angle = pattern.angle.rad();
+
+    (0..count).map(move |i| Transform::rotation(axis * (angle * i as f64)))
+}
+
+/// Compute the normalized axis of a polar pattern
+///
+/// This is done with the raw components, as the pattern's parameters must be
+/// checked before they can be converted to [`fj_math`] types.
+fn polar_axis(pattern: &fj::PolarPattern) -> Result<Vector<3>, InvalidPattern> {
+    if pattern.count == 0 {
+        return Err(InvalidPattern::NoCopies);
+    }
+
+    let axis = pattern.axis;
+    if !axis.iter().all(|value| value.is_finite())
+        || !pattern.angle.rad().is_finite()
+    {
+        return Err(InvalidPattern::NotFinite);
+    }
+
+    // Scale the axis first, so computing its length can't underflow or
+    // overflow. Denormal components are too small to define a direction.
+    let max = axis.iter().fold(0., |max: f64, value| max.max(value.abs()));
+    if !max.is_normal() {
+        return Err(InvalidPattern::ZeroAxis);
+    }
+    let axis = axis.map(|value| value / max);
+    let length = axis.iter().map(|value| value * value).sum::<f64>().sqrt();
+
+    Ok(Vector::from(axis.map(|value| value / length)))
+}
+
+/// Create a copy of `original` for each of the transforms
+///
+/// The original shape has already been evaluated at this point, so each copy
+/// only needs to be transformed, not evaluated again.
+fn instantiate(
+    original: &Shape,
+    transforms: impl IntoIterator<Item = Transform>,
+) -> Result<Shape, ValidationError> {
+    let mut shape =
+        Shape::new().with_validation_config(original.validation_config());
+
+    for transform in transforms {
+        let (mut copy, _) = original.clone_shape();
+        transform_shape(&mut copy, &transform)?;
+
+        shape.merge_shape(&copy)?;
+    }
+
+    Ok(shape)
+}
+
+fn bounding_volume(
+    shape: &fj::Shape3d,
+    transforms: impl IntoIterator<Item = Transform>,
+) -> Aabb<3> {
+    let aabb = shape.bounding_volume();
+
+    transforms
+        .into_iter()
+        .map(|transform| transform.transform_aabb(&aabb))
+        .reduce(|a, b| a.merged(&b))
+        .unwrap_or(Aabb {
+            min: Point::origin(),
+            max: Point::origin(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::debug::DebugInfo;
+    use fj_kernel::{
+        algorithms::Tolerance,
+        shape::{Shape, ValidationConfig},
+    };
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::{
+        cache::ShapeCache,
+        shape_processor::{Error, InvalidPattern, ShapeProcessor},
+        ToShape,
+    };
+
+    #[test]
+    fn linear_pattern() {
+        let pattern = fj::LinearPattern {
+            shape: cube(),
+            offset: [2., 0., 0.],
+            count: 3,
+        };
+
+        let shape = to_shape(&pattern);
+        assert_eq!(shape.faces().count(), 3 * 6);
+
+        let expected = [[1., -0.5, 0.], [6., 0.5, 1.]];
+        assert_aabb(aabb_of(&shape), expected);
+        assert_aabb(pattern.bounding_volume(), expected);
+    }
+
+    #[test]
+    fn linear_pattern_with_one_copy() {
+        let pattern = fj::LinearPattern {
+            shape: cube(),
+            offset: [2., 0., 0.],
+            count: 1,
+        };
+
+        let shape = to_shape(&pattern);
+        assert_eq!(shape.faces().count(), 6);
+
+        let expected = [[1., -0.5, 0.], [2., 0.5, 1.]];
+        assert_aabb(aabb_of(&shape), expected);
+        assert_aabb(pattern.bounding_volume(), expected);
+    }
+
+    #[test]
+    fn polar_pattern() {
+        let pattern = fj::PolarPattern {
+            shape: cube(),
+            axis: [0., 0., 2.],
+            angle: fj::Angle::from_deg(90.),
+            count: 4,
+        };
+
+        let shape = to_shape(&pattern);
+        assert_eq!(shape.faces().count(), 4 * 6);
+
+        let expected = [[-2., -2., 0.], [2., 2., 1.]];
+        assert_aabb(aabb_of(&shape), expected);
+        assert_aabb(pattern.bounding_volume(), expected);
+    }
+
+    #[test]
+    fn polar_pattern_with_one_copy() {
+        let pattern = fj::PolarPattern {
+            shape: cube(),
+            axis: [0., 0., 1.],
+            angle: fj::Angle::from_deg(90.),
+            count: 1,
+        };
+
+        let shape = to_shape(&pattern);
+        assert_eq!(shape.faces().count(), 6);
+
+        let expected = [[1., -0.5, 0.], [2., 0.5, 1.]];
+        assert_aabb(aabb_of(&shape), expected);
+        assert_aabb(pattern.bounding_volume(), expected);
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        let processor = ShapeProcessor {
+            tolerance: None,
+            include_references: true,
+            validation_config: ValidationConfig::default(),
+            cache: ShapeCache::new(),
+        };
+
+        let linear = fj::LinearPattern {
+            shape: cube(),
+            offset: [2., 0., 0.],
+            count: 0,
+        };
+        let result = processor.process(&linear.into());
+        assert!(matches!(
+            result,
+            Err(Error::Pattern(InvalidPattern::NoCopies))
+        ));
+
+        let polar = fj::PolarPattern {
+            shape: cube(),
+            axis: [0., 0., 1.],
+            angle: fj::Angle::from_deg(90.),
+            count: 0,
+        };
+        let result = processor.process(&polar.into());
+        assert!(matches!(
+            result,
+            Err(Error::Pattern(InvalidPattern::NoCopies))
+        ));
+
+        let polar = fj::PolarPattern {
+            shape: cube(),
+            axis: [0., 0., 0.],
+            angle: fj::Angle::from_deg(90.),
+            count: 4,
+        };
+        let result = processor.process(&polar.into());
+        assert!(matches!(
+            result,
+            Err(Error::Pattern(InvalidPattern::ZeroAxis))
+        ));
+
+        for axis in [[0., f64::MIN_POSITIVE / 2., 0.], [-0., 0., -0.]] {
+            let polar = fj::PolarPattern {
+                shape: cube(),
+                axis,
+                angle: fj::Angle::from_deg(90.),
+                count: 4,
+            };
+            let result = processor.process(&polar.into());
+            assert!(matches!(
+                result,
+                Err(Error::Pattern(InvalidPattern::ZeroAxis))
+            ));
+        }
+
+        let not_finite: [fj::Shape; 4] = [
+            fj::LinearPattern {
+                shape: cube(),
+                offset: [f64::INFINITY, 0., 0.],
+                count: 2,
+            }
+            .into(),
+            fj::PolarPattern {
+                shape: cube(),
+                axis: [0., 0., f64::NAN],
+                angle: fj::Angle::from_deg(90.),
+                count: 4,
+            }
+            .into(),
+            fj::PolarPattern {
+                shape: cube(),
+                axis: [0., 0., 1.],
+                angle: fj::Angle::from_rad(f64::NAN),
+                count: 4,
+            }
+            .into(),
+            fj::PolarPattern {
+                shape: cube(),
+                axis: [0., 0., 1.],
+                angle: fj::Angle::from_rad(f64::INFINITY),
+                count: 4,
+            }
+            .into(),
+        ];
+        for shape in not_finite {
+            let result = processor.process(&shape);
+            assert!(matches!(
+                result,
+                Err(Error::Pattern(InvalidPattern::NotFinite))
+            ));
+        }
+
+        // Patterns are checked, even if only reference bodies are left.
+        let references_only = ShapeProcessor {
+            include_references: false,
+            ..processor
+        };
+        let polar = fj::PolarPattern {
+            shape: fj::Mesh::from_triangles(vec![[
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [0., 1., 0.],
+            ]])
+            .with_reference(true)
+            .into(),
+            axis: [0., 0., 0.],
+            angle: fj::Angle::from_deg(90.),
+            count: 4,
+        };
+        let result = references_only.process(&polar.into());
+        assert!(matches!(
+            result,
+            Err(Error::Pattern(InvalidPattern::ZeroAxis))
+        ));
+    }
+
+    #[test]
+    fn invalid_patterns_without_processor() {
+        // Invalid patterns have no copies, when they are evaluated directly.
+        let polar = fj::PolarPattern {
+            shape: cube(),
+            axis: [0., 0., 0.],
+            angle: fj::Angle::from_deg(90.),
+            count: 4,
+        };
+        assert_aabb(polar.bounding_volume(), [[0., 0., 0.], [0., 0., 0.]]);
+        assert_eq!(to_shape(&polar).faces().count(), 0);
+
+        let linear = fj::LinearPattern {
+            shape: cube(),
+            offset: [f64::NAN, 0., 0.],
+            count: 2,
+        };
+        assert_aabb(linear.bounding_volume(), [[0., 0., 0.], [0., 0., 0.]]);
+        assert_eq!(to_shape(&linear).faces().count(), 0);
+    }
+
+    /// A unit cube next to the z-axis, so rotated copies don't touch
+    fn cube() -> fj::Shape3d {
+        let sketch = fj::Sketch::from_points(vec![
+            [1., -0.5],
+            [2., -0.5],
+            [2., 0.5],
+            [1., 0.5],
+        ]);
+        fj::Sweep::from_path(sketch.into(), [0., 0., 1.]).into()
+    }
+
+    fn to_shape(shape: &impl ToShape) -> Shape {
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+
+        shape
+            .to_shape(
+                &ValidationConfig::default(),
+                tolerance,
+                &ShapeCache::new(),
+                &mut DebugInfo::new(),
+            )
+            .unwrap()
+    }
+
+    fn aabb_of(shape: &Shape) -> Aabb<3> {
+        Aabb::<3>::from_points(shape.points().values())
+    }
+
+    fn assert_aabb(aabb: Aabb<3>, [min, max]: [[f64; 3]; 2]) {
+        let epsilon = Scalar::from_f64(1e-9);
+
+        assert!(
+            (aabb.min - Point::from(min)).magnitude() < epsilon
+                && (aabb.max - Point::from(max)).magnitude() < epsilon,
+            "Unexpected bounding box: {:?}",
+            aabb
+        );
+    }
+}
@@ -1,26 +1,102 @@
 //! API for processing shapes
 
+use std::borrow::Cow;
+
 use fj_interop::{debug::DebugInfo, mesh::Mesh};
 use fj_kernel::{
-    algorithms::{triangulate, InvalidTolerance, Tolerance},
-    shape::ValidationError,
+    algorithms::{InvalidTolerance, Tolerance},
+    shape::{Shape, ValidationConfig, ValidationError, ValidationLevel},
 };
 use fj_math::{Aabb, Point, Scalar};
 
-use crate::ToShape as _;
+use crate::{cache::ShapeCache, pattern, ToShape as _};
 
 /// Processes an [`fj::Shape`] into a [`ProcessedShape`]
 pub struct ShapeProcessor {
     /// The tolerance value used for creating the triangle mesh
     pub tolerance: Option<Tolerance>,
+
+    /// Whether reference bodies are included in the processed shape
+    ///
+    /// Reference bodies are meshes that are marked as such, using
+    /// [`fj::Mesh::with_reference`]. They should be included when displaying a
+    /// model, but are usually excluded when exporting it.
+    pub include_references: bool,
+
+    /// The validation configuration used for creating the shape
+    ///
+    /// If the validation level is [`ValidationLevel::Deferred`], the shape is
+    /// fully validated once it has been created completely.
+    pub validation_config: ValidationConfig,
+
+    /// The cache that is used to reuse results between calls to `process`
+    ///
+    /// The parts of a shape that haven't changed since the last call to
+    /// [`ShapeProcessor::process`] are taken from the cache, instead of being
+    /// processed again.
+    pub cache: ShapeCache,
 }
 
 impl ShapeProcessor {
     /// Process an [`fj::Shape`] into [`ProcessedShape`]
     pub fn process(&self, shape: &fj::Shape) -> Result<ProcessedShape, Error> {
+        // This is done before reference bodies are removed, so invalid
+        // patterns are reported, even if nothing else is left of the shape.
+        check_patterns(shape)?;
+
+        let shape = if self.include_references {
+            Cow::Borrowed(shape)
+        } else {
+            match without_references(shape) {
+                Some(shape) => Cow::Owned(shape),
+                None => {
+                    return Ok(ProcessedShape {
+                        aabb: Aabb {
+                            min: Point::origin(),
+                            max: Point::origin(),
+                        },
+                        shape: Shape::new()
+                            .with_validation_config(self.validation_config),
+                        mesh: Mesh::new(),
+                        debug_info: DebugInfo::new(),
+                        tolerance: self.tolerance(&shape.bounding_volume())?,
+                    });
+                }
+            }
+        };
+
         let aabb = shape.bounding_volume();
 
-        let tolerance = match self.tolerance {
+        let tolerance = self.tolerance(&aabb)?;
+
+        let config = &self.validation_config;
+        let mut debug_info = DebugInfo::new();
+
+        let brep =
+            self.cache
+                .to_shape(&*shape, config, tolerance, &mut debug_info)?;
+        if config.level == ValidationLevel::Deferred {
+            brep.validate_all()?;
+        }
+
+        let mesh =
+            self.cache
+                .triangulate(&*shape, config, tolerance, &mut debug_info);
+
+        self.cache.remove_unused();
+
+        Ok(ProcessedShape {
+            aabb,
+            shape: brep,
+            mesh,
+            debug_info,
+            tolerance,
+        })
+    }
+
+    /// Determine the tolerance for a shape with the given bounding box
+    fn tolerance(&self, aabb: &Aabb<3>) -> Result<Tolerance, InvalidTolerance> {
+        match self.tolerance {
             None => {
                 // Compute a reasonable default for the tolerance value. To do
                 // this, we just look at the smallest non-zero extent of the
@@ -33,23 +109,112 @@ impl ShapeProcessor {
                 }
 
                 let tolerance = min_extent / Scalar::from_f64(1000.);
-                Tolerance::from_scalar(tolerance)?
+                Tolerance::from_scalar(tolerance)
             }
-            Some(user_defined_tolerance) => user_defined_tolerance,
-        };
+            Some(user_defined_tolerance) => Ok(user_defined_tolerance),
+        }
+    }
+}
 
-        let mut debug_info = DebugInfo::new();
-        let mesh = triangulate(
-            shape.to_shape(tolerance, &mut debug_info)?,
-            tolerance,
-            &mut debug_info,
-        );
+/// Remove all reference bodies from a shape
+///
+/// Returns `None`, if nothing is left of the shape.
+fn without_references(shape: &fj::Shape) -> Option<fj::Shape> {
+    match shape {
+        fj::Shape::Shape2d(_) => Some(shape.clone()),
+        fj::Shape::Shape3d(shape) => {
+            without_references_3d(shape).map(Into::into)
+        }
+    }
+}
 
-        Ok(ProcessedShape {
-            aabb,
-            mesh,
-            debug_info,
-        })
+fn without_references_3d(shape: &fj::Shape3d) -> Option<fj::Shape3d> {
+    match shape {
+        fj::Shape3d::Group(group) => {
+            let a = without_references_3d(&group.a);
+            let b = without_references_3d(&group.b);
+
+            match (a, b) {
+                (Some(a), Some(b)) => Some(fj::Group { a, b }.into()),
+                (Some(shape), None) | (None, Some(shape)) => Some(shape),
+                (None, None) => None,
+            }
+        }
+        fj::Shape3d::LinearPattern(pattern) => {
+            without_references_3d(&pattern.shape).map(|shape| {
+                fj::LinearPattern {
+                    shape,
+                    ..(**pattern).clone()
+                }
+                .into()
+            })
+        }
+        fj::Shape3d::Mesh(mesh) => {
+            if mesh.is_reference() {
+                None
+            } else {
+                Some(shape.clone())
+            }
+        }
+        fj::Shape3d::Mirror(mirror) => without_references_3d(&mirror.shape)
+            .map(|shape| {
+                fj::Mirror {
+                    shape,
+                    ..(**mirror).clone()
+                }
+                .into()
+            }),
+        fj::Shape3d::PolarPattern(pattern) => {
+            without_references_3d(&pattern.shape).map(|shape| {
+                fj::PolarPattern {
+                    shape,
+                    ..(**pattern).clone()
+                }
+                .into()
+            })
+        }
+        fj::Shape3d::Sweep(_) => Some(shape.clone()),
+        fj::Shape3d::Transform(transform) => {
+            without_references_3d(&transform.shape).map(|shape| {
+                fj::Transform {
+                    shape,
+                    ..(**transform).clone()
+                }
+                .into()
+            })
+        }
+    }
+}
+
+/// Check that all patterns within a shape can be evaluated
+///
+/// Invalid patterns would otherwise silently result in empty shapes.
+fn check_patterns(shape: &fj::Shape) -> Result<(), InvalidPattern> {
+    match shape {
+        fj::Shape::Shape2d(_) => Ok(()),
+        fj::Shape::Shape3d(shape) => check_patterns_3d(shape),
+    }
+}
+
+fn check_patterns_3d(shape: &fj::Shape3d) -> Result<(), InvalidPattern> {
+    match shape {
+        fj::Shape3d::Group(group) => {
+            check_patterns_3d(&group.a)?;
+            check_patterns_3d(&group.b)
+        }
+        fj::Shape3d::LinearPattern(pattern) => {
+            pattern::check_linear(pattern)?;
+            check_patterns_3d(&pattern.shape)
+        }
+        fj::Shape3d::Mesh(_) | fj::Shape3d::Sweep(_) => Ok(()),
+        fj::Shape3d::Mirror(mirror) => check_patterns_3d(&mirror.shape),
+        fj::Shape3d::PolarPattern(pattern) => {
+            pattern::check_polar(pattern)?;
+            check_patterns_3d(&pattern.shape)
+        }
+        fj::Shape3d::Transform(transform) => {
+            check_patterns_3d(&transform.shape)
+        }
     }
 }
 
@@ -60,11 +225,27 @@ pub struct ProcessedShape {
     /// The axis-aligned bounding box of the shape
     pub aabb: Aabb<3>,
 
+    /// The boundary representation of the shape
+    ///
+    /// This is the shape that was triangulated to create `mesh`. It is exact,
+    /// and can be used for exporting to formats that support boundary
+    /// representations.
+    ///
+    /// The shape is shared with the [`ShapeCache`] of the processor. Use
+    /// [`Shape::clone_shape`] to create a copy that can be modified.
+    pub shape: Shape,
+
     /// The triangle mesh that approximates the original shape
     pub mesh: Mesh<Point<3>>,
 
     /// The debug info generated while processing the shape
     pub debug_info: DebugInfo,
+
+    /// The tolerance that was used for creating the shape and the mesh
+    ///
+    /// If all of the original shape consisted of reference bodies, which were
+    /// left out, this is the tolerance that would have been used for them.
+    pub tolerance: Tolerance,
 }
 
 /// A shape processing error
@@ -77,4 +258,138 @@ pub enum Error {
     /// Model has zero size
     #[error("Model has an zero size")]
     Extent(#[from] InvalidTolerance),
+
+    /// Model contains an invalid pattern
+    #[error("Model contains an invalid pattern")]
+    Pattern(#[from] InvalidPattern),
+}
+
+/// An invalid [`fj::LinearPattern`] or [`fj::PolarPattern`]
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidPattern {
+    /// The pattern has a `count` of zero
+    #[error("Pattern must have at least one copy")]
+    NoCopies,
+
+    /// The axis of a polar pattern is too short to define a direction
+    #[error("Axis of polar pattern must not have zero length")]
+    ZeroAxis,
+
+    /// The offset, axis, or angle of the pattern is infinite or NaN
+    #[error("Pattern parameters must be finite")]
+    NotFinite,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_kernel::shape::ValidationConfig;
+
+    use crate::cache::ShapeCache;
+
+    use super::{without_references, ShapeProcessor};
+
+    #[test]
+    fn without_references_keeps_geometry() {
+        let shape: fj::Shape = fj::Group {
+            a: cube(),
+            b: fj::LinearPattern {
+                shape: mesh().with_reference(true).into(),
+                offset: [2., 0., 0.],
+                count: 2,
+            }
+            .into(),
+        }
+        .into();
+
+        // Only the cube is left, and the group that contained it is gone.
+        let shape = without_references(&shape).unwrap();
+        assert!(
+            matches!(shape, fj::Shape::Shape3d(fj::Shape3d::Sweep(_))),
+            "{:?}",
+            shape
+        );
+
+        let shape: fj::Shape = fj::Group {
+            a: cube(),
+            b: mesh().into(),
+        }
+        .into();
+        // Meshes that are not marked as reference bodies are kept.
+        let shape = without_references(&shape).unwrap();
+        match shape {
+            fj::Shape::Shape3d(fj::Shape3d::Group(group)) => {
+                assert!(matches!(group.a, fj::Shape3d::Sweep(_)));
+                match &group.b {
+                    fj::Shape3d::Mesh(kept) => {
+                        assert_eq!(kept.to_triangles(), mesh().to_triangles());
+                    }
+                    shape => panic!("Expected mesh, got {:?}", shape),
+                }
+            }
+            shape => panic!("Expected group, got {:?}", shape),
+        }
+    }
+
+    #[test]
+    fn without_references_drops_reference_only_shapes() {
+        let shape: fj::Shape = fj::Group {
+            a: mesh().with_reference(true).into(),
+            b: fj::LinearPattern {
+                shape: mesh().with_reference(true).into(),
+                offset: [2., 0., 0.],
+                count: 2,
+            }
+            .into(),
+        }
+        .into();
+
+        assert!(without_references(&shape).is_none());
+    }
+
+    #[test]
+    fn process_with_and_without_references() {
+        let shape: fj::Shape = fj::Group {
+            a: cube(),
+            b: mesh().with_reference(true).into(),
+        }
+        .into();
+
+        let processor = |include_references| ShapeProcessor {
+            tolerance: None,
+            include_references,
+            validation_config: ValidationConfig::default(),
+            cache: ShapeCache::new(),
+        };
+
+        let with = processor(true).process(&shape).unwrap();
+        let without = processor(false).process(&shape).unwrap();
+
+        assert_eq!(
+            with.mesh.triangles().count(),
+            without.mesh.triangles().count() + mesh().to_triangles().len()
+        );
+        assert_eq!(without.shape.faces().count(), 6);
+    }
+
+    fn cube() -> fj::Shape3d {
+        let sketch = fj::Sketch::from_points(vec![
+            [0., 0.],
+            [1., 0.],
+            [1., 1.],
+            [0., 1.],
+        ]);
+        fj::Sweep::from_path(sketch.into(), [0., 0., 1.]).into()
+    }
+
+    /// A tetrahedron, as it could have been imported from a file
+    fn mesh() -> fj::Mesh {
+        let [a, b, c, d] =
+            [[3., 0., 0.], [4., 0., 0.], [3., 1., 0.], [3., 0., 1.]];
+        fj::Mesh::from_triangles(vec![
+            [a, c, b],
+            [a, b, d],
+            [b, c, d],
+            [a, d, c],
+        ])
+    }
 }
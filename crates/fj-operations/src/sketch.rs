@@ -1,42 +1,232 @@
+use std::iter;
+
+use fj::SketchSegment;
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::Tolerance,
     geometry::Surface,
-    shape::{Shape, ValidationError},
-    topology::Face,
+    shape::{Shape, ValidationConfig, ValidationError},
+    topology::{Cycle, Edge, Face},
 };
-use fj_math::{Aabb, Point};
+use fj_math::{Aabb, Circle, Point, Vector};
 
-use super::ToShape;
+use super::{cache::ShapeCache, ToShape};
 
 impl ToShape for fj::Sketch {
     fn to_shape(
         &self,
+        config: &ValidationConfig,
         _: Tolerance,
+        _: &ShapeCache,
         _: &mut DebugInfo,
     ) -> Result<Shape, ValidationError> {
-        let mut shape = Shape::new();
+        let mut shape = Shape::new().with_validation_config(*config);
 
         let surface = Surface::xy_plane();
-        let points = self
-            .to_points()
-            .into_iter()
-            .map(Point::from)
-            .map(|point| surface.point_from_surface_coords(point));
+        let point = |point: [f64; 2]| {
+            surface.point_from_surface_coords(Point::from(point))
+        };
+
+        let mut edges = Vec::new();
+        if let Some(start) = self.start() {
+            let mut current = start;
+
+            for segment in self.to_segments() {
+                let builder = Edge::builder(&mut shape);
+
+                // Arcs are converted into circles, other curved segments into
+                // B-splines. Their approximation is left to the kernel, which
+                // takes the tolerance into account.
+                let (edge, end) = match segment {
+                    SketchSegment::Line { end } => {
+                        let edge = builder.build_line_segment_from_points(
+                            [current, end].map(point),
+                        )?;
+                        (edge, end)
+                    }
+                    SketchSegment::Arc {
+                        center,
+                        end,
+                        counterclockwise,
+                    } => {
+                        let edge = builder.build_arc_from_points(
+                            circle(center, current, counterclockwise),
+                            [current, end].map(point),
+                        )?;
+                        (edge, end)
+                    }
+                    SketchSegment::CubicBezier {
+                        controls: [b, c],
+                        end,
+                    } => {
+                        // A cubic Bézier curve is a B-spline of degree 3 with
+                        // 4 control points and a clamped, uniform knot vector.
+                        let edge = builder.build_bspline_from_points(
+                            3,
+                            [current, b, c, end].map(point),
+                        )?;
+                        (edge, end)
+                    }
+                    SketchSegment::BSpline {
+                        degree,
+                        control_points,
+                    } => {
+                        let end =
+                            control_points.last().copied().unwrap_or(current);
+                        let edge = builder.build_bspline_from_points(
+                            degree,
+                            iter::once(current)
+                                .chain(control_points)
+                                .map(point),
+                        )?;
+                        (edge, end)
+                    }
+                };
 
-        Face::builder(surface, &mut shape)
-            .with_exterior_polygon(points)
-            .build()?;
+                edges.push(edge);
+                current = end;
+            }
+
+            if current != start {
+                let edge = Edge::builder(&mut shape)
+                    .build_line_segment_from_points(
+                        [current, start].map(point),
+                    )?;
+                edges.push(edge);
+            }
+        }
+
+        let cycle = shape.insert(Cycle::new(edges))?;
+        let surface = shape.insert(surface)?;
+        shape.insert(Face::new(
+            surface,
+            vec![cycle],
+            Vec::new(),
+            self.color(),
+        ))?;
 
         Ok(shape)
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
-        Aabb::<3>::from_points(
-            self.to_points()
-                .into_iter()
-                .map(Point::from)
-                .map(Point::to_xyz),
-        )
+        // The curves lie within the convex hull of their control points, so
+        // including those results in a conservative bounding volume. Arcs lie
+        // within the bounding box of their full circle.
+        let mut points: Vec<_> =
+            self.to_points().into_iter().map(Point::from).collect();
+
+        if let Some(start) = self.start() {
+            let mut current = start;
+
+            for segment in self.to_segments() {
+                current = match segment {
+                    SketchSegment::Line { end }
+                    | SketchSegment::CubicBezier { end, .. } => end,
+                    SketchSegment::Arc { center, end, .. } => {
+                        let center = Point::from(center);
+                        let radius =
+                            (Point::from(current) - center).magnitude();
+                        let radius = Vector::from([radius, radius]);
+
+                        points.extend([center - radius, center + radius]);
+
+                        end
+                    }
+                    SketchSegment::BSpline { control_points, .. } => {
+                        control_points.last().copied().unwrap_or(current)
+                    }
+                };
+            }
+        }
+
+        Aabb::<3>::from_points(points.into_iter().map(Point::to_xyz))
+    }
+}
+
+/// Create the circle around `center` that an arc starting at `start` is on
+///
+/// The circle starts at `start`, and runs in the direction of the arc.
+fn circle(
+    center: [f64; 2],
+    start: [f64; 2],
+    counterclockwise: bool,
+) -> Circle<3> {
+    let center = Point::from(center);
+
+    let a = Point::from(start) - center;
+    let [x, y] = a.components;
+    let b = if counterclockwise {
+        Vector::from([-y, x])
+    } else {
+        Vector::from([y, -x])
+    };
+
+    Circle {
+        center: center.to_xyz(),
+        a: a.to_xyz(),
+        b: b.to_xyz(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::SketchSegment;
+    use fj_interop::debug::DebugInfo;
+    use fj_kernel::{
+        algorithms::{triangulate, Tolerance},
+        geometry::Curve,
+        shape::{Shape, ValidationConfig},
+    };
+    use fj_math::Scalar;
+
+    use crate::{cache::ShapeCache, ToShape};
+
+    #[test]
+    fn arc() {
+        // A half-disk. Its arc crosses the point where circle coordinates wrap
+        // around.
+        let sketch = fj::Sketch::from_segments(
+            [0., -1.],
+            vec![SketchSegment::Arc {
+                center: [0., 0.],
+                end: [0., 1.],
+                counterclockwise: true,
+            }],
+        );
+
+        let shape = to_shape(&sketch);
+        let circles = shape
+            .edges()
+            .filter(|edge| matches!(edge.get().curve(), Curve::Circle(_)))
+            .count();
+        assert_eq!(circles, 1);
+
+        let sweep = fj::Sweep::from_path(sketch.into(), [0., 0., 1.]);
+        let mesh =
+            triangulate(to_shape(&sweep), tolerance(), &mut DebugInfo::new());
+
+        let area = mesh.triangles().fold(Scalar::ZERO, |area, triangle| {
+            let [a, b, c] = triangle.points;
+            area + (b - a).cross(&(c - a)).magnitude() / 2.
+        });
+
+        // Top and bottom face, the curved side face, and the flat one.
+        let expected = Scalar::PI + Scalar::PI + Scalar::from(2.);
+        assert!((area - expected).abs() < Scalar::from(0.01));
+    }
+
+    fn tolerance() -> Tolerance {
+        Tolerance::from_scalar(0.001).unwrap()
+    }
+
+    fn to_shape(shape: &impl ToShape) -> Shape {
+        shape
+            .to_shape(
+                &ValidationConfig::default(),
+                tolerance(),
+                &ShapeCache::new(),
+                &mut DebugInfo::new(),
+            )
+            .unwrap()
     }
 }
@@ -1,20 +1,22 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::{sweep_shape, Tolerance},
-    shape::{Shape, ValidationError},
+    shape::{Shape, ValidationConfig, ValidationError},
 };
 use fj_math::{Aabb, Vector};
 
-use super::ToShape;
+use super::{cache::ShapeCache, ToShape};
 
 impl ToShape for fj::Sweep {
     fn to_shape(
         &self,
+        config: &ValidationConfig,
         tolerance: Tolerance,
+        cache: &ShapeCache,
         debug_info: &mut DebugInfo,
     ) -> Result<Shape, ValidationError> {
         sweep_shape(
-            self.shape().to_shape(tolerance, debug_info)?,
+            cache.to_shape(self.shape(), config, tolerance, debug_info)?,
             Vector::from(self.path()),
             tolerance,
             self.shape().color(),
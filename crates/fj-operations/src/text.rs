@@ -0,0 +1,350 @@
+use fj::SketchSegment;
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::Tolerance,
+    shape::{Shape, ValidationConfig, ValidationError},
+    topology::Face,
+};
+use fj_math::{Aabb, Point};
+use ttf_parser::GlyphId;
+
+use super::{cache::ShapeCache, ToShape};
+
+impl ToShape for fj::Text {
+    fn to_shape(
+        &self,
+        config: &ValidationConfig,
+        tolerance: Tolerance,
+        cache: &ShapeCache,
+        debug_info: &mut DebugInfo,
+    ) -> Result<Shape, ValidationError> {
+        let mut outlines = Vec::new();
+        layout(self, |font, glyph, offset| {
+            let scale = self.size() / f64::from(font.units_per_em());
+
+            let mut builder = OutlineBuilder::new(scale, offset);
+            font.outline_glyph(glyph, &mut builder);
+            outlines.extend(builder.outlines);
+        });
+
+        shape_from_outlines(
+            outlines,
+            self.color(),
+            config,
+            tolerance,
+            cache,
+            debug_info,
+        )
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let mut points = Vec::new();
+        layout(self, |font, glyph, [x, y]| {
+            if let Some(rect) = font.glyph_bounding_box(glyph) {
+                let scale = self.size() / f64::from(font.units_per_em());
+
+                for [u, v] in
+                    [[rect.x_min, rect.y_min], [rect.x_max, rect.y_max]]
+                {
+                    points.push(Point::from([
+                        x + f64::from(u) * scale,
+                        y + f64::from(v) * scale,
+                        0.,
+                    ]));
+                }
+            }
+        });
+
+        if points.is_empty() {
+            return Aabb {
+                min: Point::origin(),
+                max: Point::origin(),
+            };
+        }
+
+        Aabb::<3>::from_points(points)
+    }
+}
+
+/// Lay out the glyphs of the text
+///
+/// Calls `f` with the font, each glyph, and the position of the glyph's
+/// origin, in model units.
+fn layout(
+    text: &fj::Text,
+    mut f: impl FnMut(&ttf_parser::Face, GlyphId, [f64; 2]),
+) {
+    let font = ttf_parser::Face::from_slice(text.font().as_bytes(), 0)
+        .expect("Font has been validated on creation");
+
+    let scale = text.size() / f64::from(font.units_per_em());
+    let line_height =
+        f64::from(font.ascender() - font.descender() + font.line_gap()) * scale;
+
+    let mut x = 0.;
+    let mut y = 0.;
+
+    for c in text.text().chars() {
+        if c == '\n' {
+            x = 0.;
+            y -= line_height;
+            continue;
+        }
+
+        // Characters that are not covered by the font are displayed using its
+        // "missing glyph".
+        let glyph = font.glyph_index(c).unwrap_or(GlyphId(0));
+
+        f(&font, glyph, [x, y]);
+
+        let advance = font.glyph_hor_advance(glyph).unwrap_or(0);
+        x += f64::from(advance) * scale;
+    }
+}
+
+/// Create a shape from glyph outlines
+///
+/// Works out which outlines are holes in others, converts the resulting shapes,
+/// and gives all faces the color of the text.
+fn shape_from_outlines(
+    outlines: Vec<fj::Outline>,
+    color: [u8; 4],
+    config: &ValidationConfig,
+    tolerance: Tolerance,
+    cache: &ShapeCache,
+    debug_info: &mut DebugInfo,
+) -> Result<Shape, ValidationError> {
+    let mut shape = Shape::new().with_validation_config(*config);
+
+    for glyph_shape in fj::shapes_from_outlines(outlines) {
+        let glyph_shape =
+            glyph_shape.to_shape(config, tolerance, cache, debug_info)?;
+        shape.merge_shape(&glyph_shape)?;
+    }
+
+    shape
+        .update()
+        .update_all(|face: &mut Face| {
+            if let Face::Face(brep) = face {
+                brep.color = color;
+            }
+        })
+        .validate()?;
+
+    Ok(shape)
+}
+
+/// Collects the outlines of a glyph
+struct OutlineBuilder {
+    scale: f64,
+    offset: [f64; 2],
+
+    start: [f64; 2],
+    current: [f64; 2],
+    segments: Vec<SketchSegment>,
+
+    outlines: Vec<fj::Outline>,
+}
+
+impl OutlineBuilder {
+    fn new(scale: f64, offset: [f64; 2]) -> Self {
+        Self {
+            scale,
+            offset,
+
+            start: [0., 0.],
+            current: [0., 0.],
+            segments: Vec::new(),
+
+            outlines: Vec::new(),
+        }
+    }
+
+    fn point(&self, x: f32, y: f32) -> [f64; 2] {
+        let [offset_x, offset_y] = self.offset;
+
+        [
+            offset_x + f64::from(x) * self.scale,
+            offset_y + f64::from(y) * self.scale,
+        ]
+    }
+
+    fn push_segment(&mut self, points: &[[f64; 2]]) {
+        // Fonts can contain segments of zero length. Those would result in
+        // invalid edges, so they are skipped.
+        let end = points[points.len() - 1];
+        if self.current == end && points.iter().all(|&point| point == end) {
+            return;
+        }
+
+        let segment = match *points {
+            [end] => SketchSegment::Line { end },
+            [control, end] => SketchSegment::BSpline {
+                // A quadratic Bézier curve is a B-spline of degree 2 with 3
+                // control points and a clamped, uniform knot vector.
+                degree: 2,
+                control_points: vec![control, end],
+            },
+            [a, b, end] => SketchSegment::CubicBezier {
+                controls: [a, b],
+                end,
+            },
+            _ => unreachable!("Glyph outlines only contain up to cubic curves"),
+        };
+
+        self.current = end;
+        self.segments.push(segment);
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.point(x, y);
+        self.current = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.push_segment(&[point]);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let points = [self.point(x1, y1), self.point(x, y)];
+        self.push_segment(&points);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let points = [self.point(x1, y1), self.point(x2, y2), self.point(x, y)];
+        self.push_segment(&points);
+    }
+
+    fn close(&mut self) {
+        if self.current != self.start {
+            self.push_segment(&[self.start]);
+        }
+
+        let segments = std::mem::take(&mut self.segments);
+        if !segments.is_empty() {
+            self.outlines
+                .push(fj::Outline::from_segments(self.start, segments));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::debug::DebugInfo;
+    use fj_kernel::{
+        algorithms::Tolerance,
+        shape::{Shape, ValidationConfig},
+    };
+    use ttf_parser::OutlineBuilder as _;
+
+    use crate::cache::ShapeCache;
+
+    use super::{shape_from_outlines, OutlineBuilder};
+
+    // The glyphs here are simplified versions of real ones. Like in TrueType
+    // fonts, their outer contours are clockwise and their counters are
+    // counter-clockwise.
+
+    #[test]
+    fn glyph_with_counter() {
+        // An "o", with a round outer contour.
+        let shape = glyph(|builder| {
+            builder.move_to(5., 0.);
+            builder.quad_to(0., 0., 0., 5.);
+            builder.quad_to(0., 10., 5., 10.);
+            builder.quad_to(10., 10., 10., 5.);
+            builder.quad_to(10., 0., 5., 0.);
+            builder.close();
+
+            rectangle(builder, [3., 3.], [7., 7.]);
+        });
+
+        assert_eq!(interiors_per_face(&shape), [1]);
+    }
+
+    #[test]
+    fn glyph_with_two_counters() {
+        // A "B", made from straight lines.
+        let shape = glyph(|builder| {
+            builder.move_to(0., 0.);
+            builder.line_to(0., 10.);
+            builder.line_to(6., 10.);
+            builder.line_to(8., 8.);
+            builder.line_to(8., 6.);
+            builder.line_to(7., 5.);
+            builder.line_to(8., 4.);
+            builder.line_to(8., 2.);
+            builder.line_to(6., 0.);
+            builder.close();
+
+            rectangle(builder, [2., 2.], [6., 4.]);
+            rectangle(builder, [2., 6.], [6., 8.]);
+        });
+
+        assert_eq!(interiors_per_face(&shape), [2]);
+    }
+
+    #[test]
+    fn glyph_with_island() {
+        // A "◉", a ring with a separate dot inside.
+        let shape = glyph(|builder| {
+            builder.move_to(0., 0.);
+            builder.line_to(0., 10.);
+            builder.line_to(10., 10.);
+            builder.line_to(10., 0.);
+            builder.close();
+
+            rectangle(builder, [2., 2.], [8., 8.]);
+
+            builder.move_to(4., 4.);
+            builder.line_to(4., 6.);
+            builder.line_to(6., 6.);
+            builder.line_to(6., 4.);
+            builder.close();
+        });
+
+        assert_eq!(interiors_per_face(&shape), [0, 1]);
+    }
+
+    fn glyph(f: impl FnOnce(&mut OutlineBuilder)) -> Shape {
+        let mut builder = OutlineBuilder::new(1., [0., 0.]);
+        f(&mut builder);
+
+        shape_from_outlines(
+            builder.outlines,
+            [0, 0, 0, 255],
+            &ValidationConfig::default(),
+            Tolerance::from_scalar(0.1).unwrap(),
+            &ShapeCache::new(),
+            &mut DebugInfo::new(),
+        )
+        .unwrap()
+    }
+
+    /// Add a counter-clockwise rectangle
+    fn rectangle(
+        builder: &mut OutlineBuilder,
+        [x0, y0]: [f32; 2],
+        [x1, y1]: [f32; 2],
+    ) {
+        builder.move_to(x0, y0);
+        builder.line_to(x1, y0);
+        builder.line_to(x1, y1);
+        builder.line_to(x0, y1);
+        builder.close();
+    }
+
+    /// Count the interiors of each face, sorted
+    fn interiors_per_face(shape: &Shape) -> Vec<usize> {
+        let mut interiors: Vec<_> = shape
+            .faces()
+            .map(|face| face.get().interiors().count())
+            .collect();
+        interiors.sort_unstable();
+
+        interiors
+    }
+}
@@ -1,19 +1,25 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
     algorithms::{transform_shape, Tolerance},
-    shape::{Shape, ValidationError},
+    shape::{Shape, ValidationConfig, ValidationError},
 };
 use fj_math::{Aabb, Transform, Vector};
 
-use super::ToShape;
+use super::{cache::ShapeCache, ToShape};
 
 impl ToShape for fj::Transform {
     fn to_shape(
         &self,
+        config: &ValidationConfig,
         tolerance: Tolerance,
+        cache: &ShapeCache,
         debug_info: &mut DebugInfo,
     ) -> Result<Shape, ValidationError> {
-        let mut shape = self.shape.to_shape(tolerance, debug_info)?;
+        // The shape might be shared with the cache, so it can't be transformed
+        // in place.
+        let (mut shape, _) = cache
+            .to_shape(&self.shape, config, tolerance, debug_info)?
+            .clone_shape();
         let transform = transform(self);
 
         transform_shape(&mut shape, &transform)?;
@@ -30,4 +36,5 @@ fn transform(transform: &fj::Transform) -> Transform {
     let axis = Vector::from(transform.axis).normalize();
     Transform::translation(transform.offset)
         * Transform::rotation(axis * transform.angle.rad())
+        * Transform::scale(transform.scale)
 }
@@ -0,0 +1,535 @@
+use std::{fs, path::Path as FilePath};
+
+use crate::{
+    outline::distance, shapes_from_outlines, Outline, Shape2d, SketchSegment,
+};
+
+use super::{join, Affine, ImportError};
+
+/// Import the closed outlines of a DXF file
+///
+/// Supports the `LINE`, `ARC`, `CIRCLE`, `LWPOLYLINE`, and `POLYLINE` entities
+/// of ASCII DXF files. Other entities are ignored, as are the z coordinates of
+/// all entities.
+///
+/// DXF files represent outlines as separate lines and arcs. Those are joined
+/// into closed paths, wherever their end points meet. Anything that doesn't
+/// form a closed path is ignored.
+pub fn dxf(path: impl AsRef<FilePath>) -> Result<Vec<Shape2d>, ImportError> {
+    let data = fs::read(path)?;
+
+    // DXF files that were written by older software might not be valid UTF-8.
+    // All the information we need is ASCII, so a lossy conversion is fine.
+    let dxf = String::from_utf8_lossy(&data);
+
+    dxf_from_str(&dxf)
+}
+
+/// Import the closed outlines of DXF data
+///
+/// See [`dxf`] for more information.
+pub fn dxf_from_str(dxf: &str) -> Result<Vec<Shape2d>, ImportError> {
+    if dxf.starts_with("AutoCAD Binary DXF") {
+        return Err(ImportError::Dxf(
+            "Binary DXF files are not supported".to_owned(),
+        ));
+    }
+
+    let pairs = pairs(dxf)?;
+    let entities = entities(&pairs);
+
+    let mut pieces = Vec::new();
+    let mut i = 0;
+
+    while i < entities.len() {
+        let entity = &entities[i];
+        i += 1;
+
+        let transform = entity.transform();
+
+        let piece = match entity.kind {
+            "LINE" => line(entity)?,
+            "ARC" => arc(entity)?,
+            "CIRCLE" => circle(entity)?,
+            "LWPOLYLINE" => lwpolyline(entity)?,
+            "POLYLINE" => {
+                // The vertices of the polyline follow it as separate entities.
+                let mut vertices = Vec::new();
+                while i < entities.len() && entities[i].kind == "VERTEX" {
+                    vertices.push(&entities[i]);
+                    i += 1;
+                }
+
+                polyline(entity, &vertices)?
+            }
+            _ => None,
+        };
+
+        pieces.extend(piece.map(|piece| piece.transform(&transform)));
+    }
+
+    // The end points of lines and arcs are not guaranteed to match exactly.
+    // Accept a difference that is small, relative to the size of the drawing.
+    let extent = pieces
+        .iter()
+        .flat_map(|piece: &Outline| [piece.start, piece.end()])
+        .map(|[x, y]| x.abs().max(y.abs()))
+        .fold(1., f64::max);
+    let paths = join(pieces, 1e-6 * extent);
+
+    Ok(shapes_from_outlines(paths))
+}
+
+/// A group code and its value
+struct Pair<'r> {
+    /// The line number of the group code, for error messages
+    line: usize,
+    code: i32,
+    value: &'r str,
+}
+
+/// Split the file into pairs of group codes and values
+fn pairs(dxf: &str) -> Result<Vec<Pair<'_>>, ImportError> {
+    let mut pairs = Vec::new();
+    let mut lines = dxf.lines().enumerate();
+
+    while let Some((i, code)) = lines.next() {
+        let code = code.trim();
+
+        // Trailing empty lines are common.
+        if code.is_empty() {
+            continue;
+        }
+
+        let line = i + 1;
+        let code = code.parse().map_err(|_| {
+            ImportError::Dxf(format!(
+                "Invalid group code `{}` in line {}",
+                code, line
+            ))
+        })?;
+        let value = match lines.next() {
+            Some((_, value)) => value.trim(),
+            None => {
+                return Err(ImportError::Dxf(format!(
+                    "Missing value for group code in line {}",
+                    line
+                )));
+            }
+        };
+
+        pairs.push(Pair { line, code, value });
+    }
+
+    Ok(pairs)
+}
+
+/// An entity from the `ENTITIES` section
+struct Entity<'r> {
+    /// The line number of the entity's first group code, for error messages
+    line: usize,
+    kind: &'r str,
+    pairs: &'r [Pair<'r>],
+}
+
+impl<'r> Entity<'r> {
+    /// Find the first value with the given group code
+    fn get(&self, code: i32) -> Option<&'r Pair<'r>> {
+        self.pairs.iter().find(|pair| pair.code == code)
+    }
+
+    /// Find the first value with the given group code, as a number
+    fn number(&self, code: i32) -> Result<Option<f64>, ImportError> {
+        self.get(code).map(number).transpose()
+    }
+
+    /// Find the first value with the given group code, as a required number
+    fn required(&self, code: i32) -> Result<f64, ImportError> {
+        self.number(code)?.ok_or_else(|| {
+            ImportError::Dxf(format!(
+                "`{}` entity in line {} is missing group code {}",
+                self.kind, self.line, code
+            ))
+        })
+    }
+
+    /// Find the flags of the entity
+    fn flags(&self) -> Result<i64, ImportError> {
+        match self.get(70) {
+            Some(pair) => pair.value.parse().map_err(|_| invalid(pair)),
+            None => Ok(0),
+        }
+    }
+
+    /// Compute the transform from the entity's coordinate system
+    ///
+    /// The coordinates of arcs, circles and polylines are specified in an
+    /// object coordinate system, which is defined by the entity's extrusion
+    /// direction. Drawings in the xy plane can only have an extrusion direction
+    /// of either positive or negative z. The latter mirrors the x axis.
+    fn transform(&self) -> Affine {
+        let mirrored = !matches!(self.kind, "LINE")
+            && matches!(self.number(230), Ok(Some(z)) if z < 0.);
+
+        if mirrored {
+            Affine::new(-1., 0., 0., 1., 0., 0.)
+        } else {
+            Affine::identity()
+        }
+    }
+}
+
+/// Collect the entities from the `ENTITIES` section
+///
+/// Every entity starts with a pair with group code 0, which specifies the kind
+/// of the entity.
+fn entities<'r>(pairs: &'r [Pair<'r>]) -> Vec<Entity<'r>> {
+    let mut entities = Vec::new();
+
+    let section = pairs
+        .windows(2)
+        .position(|pairs| {
+            pairs[0].code == 0
+                && pairs[0].value == "SECTION"
+                && pairs[1].code == 2
+                && pairs[1].value == "ENTITIES"
+        })
+        .map(|i| &pairs[i + 2..]);
+
+    let mut pairs = match section {
+        Some(pairs) => pairs,
+        None => return entities,
+    };
+
+    while let Some(first) = pairs.first() {
+        let len = pairs[1..]
+            .iter()
+            .position(|pair| pair.code == 0)
+            .map_or(pairs.len(), |len| len + 1);
+
+        if first.code == 0 {
+            if first.value == "ENDSEC" {
+                break;
+            }
+
+            entities.push(Entity {
+                line: first.line,
+                kind: first.value,
+                pairs: &pairs[1..len],
+            });
+        }
+
+        pairs = &pairs[len..];
+    }
+
+    entities
+}
+
+fn line(entity: &Entity) -> Result<Option<Outline>, ImportError> {
+    let start = [entity.required(10)?, entity.required(20)?];
+    let end = [entity.required(11)?, entity.required(21)?];
+
+    if start == end {
+        return Ok(None);
+    }
+
+    let mut path = Outline::new(start);
+    path.segments.push(SketchSegment::Line { end });
+
+    Ok(Some(path))
+}
+
+fn arc(entity: &Entity) -> Result<Option<Outline>, ImportError> {
+    let center = [entity.required(10)?, entity.required(20)?];
+    let radius = entity.required(40)?;
+    let start_angle = entity.required(50)?.to_radians();
+    let end_angle = entity.required(51)?.to_radians();
+
+    if radius <= 0. {
+        return Ok(None);
+    }
+
+    let point = |angle: f64| {
+        let (sin, cos) = angle.sin_cos();
+        [center[0] + radius * cos, center[1] + radius * sin]
+    };
+
+    // Arcs in DXF files always go counter-clockwise.
+    let mut path = Outline::new(point(start_angle));
+    path.segments.push(SketchSegment::Arc {
+        center,
+        end: point(end_angle),
+        counterclockwise: true,
+    });
+
+    Ok(Some(path))
+}
+
+fn circle(entity: &Entity) -> Result<Option<Outline>, ImportError> {
+    let center = [entity.required(10)?, entity.required(20)?];
+    let radius = entity.required(40)?;
+
+    if radius <= 0. {
+        return Ok(None);
+    }
+
+    let start = [center[0] + radius, center[1]];
+
+    let mut path = Outline::new(start);
+    path.segments.push(SketchSegment::Arc {
+        center,
+        end: start,
+        counterclockwise: true,
+    });
+
+    Ok(Some(path))
+}
+
+fn lwpolyline(entity: &Entity) -> Result<Option<Outline>, ImportError> {
+    // The vertices are specified as a sequence of pairs. Each vertex starts
+    // with its x coordinate, followed by its y coordinate and optionally its
+    // bulge.
+    let mut vertices = Vec::new();
+    for pair in entity.pairs {
+        match pair.code {
+            10 => vertices.push(([number(pair)?, 0.], 0.)),
+            20 => {
+                let (point, _) = last_vertex(&mut vertices, pair)?;
+                point[1] = number(pair)?;
+            }
+            42 => {
+                let (_, bulge) = last_vertex(&mut vertices, pair)?;
+                *bulge = number(pair)?;
+            }
+            _ => {}
+        }
+    }
+
+    let closed = entity.flags()? & 1 != 0;
+
+    Ok(polyline_path(&vertices, closed))
+}
+
+fn polyline(
+    entity: &Entity,
+    vertices: &[&Entity],
+) -> Result<Option<Outline>, ImportError> {
+    let mut points = Vec::new();
+    for vertex in vertices {
+        let point = [vertex.required(10)?, vertex.required(20)?];
+        let bulge = vertex.number(42)?.unwrap_or(0.);
+
+        points.push((point, bulge));
+    }
+
+    let closed = entity.flags()? & 1 != 0;
+
+    Ok(polyline_path(&points, closed))
+}
+
+/// Create a path from the vertices of a polyline
+///
+/// Each vertex comes with the bulge of the segment that starts at it. The bulge
+/// is the tangent of a quarter of the segment's angle. Zero means the segment
+/// is straight, positive values mean it is a counter-clockwise arc.
+fn polyline_path(
+    vertices: &[([f64; 2], f64)],
+    closed: bool,
+) -> Option<Outline> {
+    let (start, _) = *vertices.first()?;
+    let mut path = Outline::new(start);
+
+    let mut segments: Vec<_> =
+        vertices.windows(2).map(|ab| (ab[0], ab[1].0)).collect();
+    if closed {
+        segments.push((vertices[vertices.len() - 1], start));
+    }
+
+    for ((a, bulge), b) in segments {
+        // Consecutive identical vertices don't contribute anything, but would
+        // result in invalid edges.
+        if a == b {
+            continue;
+        }
+
+        if bulge == 0. {
+            path.segments.push(SketchSegment::Line { end: b });
+            continue;
+        }
+
+        // Compute the center of the arc, which lies on the perpendicular
+        // bisector of the segment.
+        let length = distance(a, b);
+        let mid = [(a[0] + b[0]) / 2., (a[1] + b[1]) / 2.];
+        let left = [-(b[1] - a[1]) / length, (b[0] - a[0]) / length];
+        let offset = length * (1. - bulge * bulge) / (4. * bulge);
+
+        path.segments.push(SketchSegment::Arc {
+            center: [mid[0] + left[0] * offset, mid[1] + left[1] * offset],
+            end: b,
+            counterclockwise: bulge > 0.,
+        });
+    }
+
+    if path.segments.is_empty() {
+        return None;
+    }
+    if closed {
+        path.close(0.);
+    }
+
+    Some(path)
+}
+
+fn last_vertex<'v>(
+    vertices: &'v mut [([f64; 2], f64)],
+    pair: &Pair,
+) -> Result<&'v mut ([f64; 2], f64), ImportError> {
+    vertices.last_mut().ok_or_else(|| {
+        ImportError::Dxf(format!(
+            "Group code {} in line {} precedes first vertex",
+            pair.code, pair.line
+        ))
+    })
+}
+
+fn number(pair: &Pair) -> Result<f64, ImportError> {
+    pair.value.parse().map_err(|_| invalid(pair))
+}
+
+fn invalid(pair: &Pair) -> ImportError {
+    ImportError::Dxf(format!(
+        "Invalid value `{}` for group code {} in line {}",
+        pair.value,
+        pair.code,
+        pair.line + 1
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{import::ImportError, Shape2d, SketchSegment};
+
+    use super::dxf_from_str;
+
+    #[test]
+    fn rectangle_with_two_holes() {
+        let dxf = dxf([
+            polyline(&[[0., 0.], [10., 0.], [10., 5.], [0., 5.]]),
+            polyline(&[[1., 1.], [3., 1.], [3., 3.], [1., 3.]]),
+            polyline(&[[6., 1.], [6., 3.], [8., 3.], [8., 1.]]),
+        ]);
+
+        let shapes = dxf_from_str(&dxf).unwrap();
+        assert_eq!(shapes.len(), 1);
+
+        match &shapes[0] {
+            Shape2d::Difference(difference) => {
+                assert_eq!(difference.holes().len(), 2);
+            }
+            shape => panic!("Expected difference, got {:?}", shape),
+        }
+    }
+
+    #[test]
+    fn arc() {
+        // A half-disk, made from a line and an arc.
+        let dxf = dxf([
+            "0\nLINE\n10\n0\n20\n1\n11\n0\n21\n-1\n".to_owned(),
+            "0\nARC\n10\n0\n20\n0\n40\n1\n50\n270\n51\n90\n".to_owned(),
+        ]);
+
+        let shapes = dxf_from_str(&dxf).unwrap();
+        assert_eq!(shapes.len(), 1);
+
+        let segments = match &shapes[0] {
+            Shape2d::Sketch(sketch) => sketch.to_segments(),
+            shape => panic!("Expected sketch, got {:?}", shape),
+        };
+        assert_eq!(segments.len(), 2);
+
+        // The arc is imported as an arc, and the sketch is counter-clockwise.
+        let arcs: Vec<_> = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                SketchSegment::Arc {
+                    center,
+                    counterclockwise,
+                    ..
+                } => Some((*center, *counterclockwise)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(arcs, [([0., 0.], true)]);
+    }
+
+    #[test]
+    fn nested_island() {
+        let dxf = dxf([circle(10.), circle(5.), circle(2.)]);
+
+        let shapes = dxf_from_str(&dxf).unwrap();
+        assert_eq!(shapes.len(), 2);
+
+        // Entities are not necessarily imported in order.
+        for shape in &shapes {
+            match shape {
+                Shape2d::Difference(difference) => {
+                    assert_eq!(difference.holes().len(), 1);
+                }
+                Shape2d::Sketch(sketch) => {
+                    // A circle is a single arc that ends where it starts.
+                    assert_eq!(sketch.start(), Some([2., 0.]));
+                    assert_eq!(
+                        sketch.to_segments(),
+                        [SketchSegment::Arc {
+                            center: [0., 0.],
+                            end: [2., 0.],
+                            counterclockwise: true,
+                        }]
+                    );
+                }
+                shape => panic!("Unexpected shape: {:?}", shape),
+            }
+        }
+        assert!(
+            matches!(shapes[0], Shape2d::Difference(_))
+                != matches!(shapes[1], Shape2d::Difference(_))
+        );
+    }
+
+    #[test]
+    fn malformed_input() {
+        let invalid_number = dxf(["0\nCIRCLE\n10\nzero\n20\n0\n40\n1\n"]);
+        let missing_code = dxf(["0\nCIRCLE\n10\n0\n20\n0\n"]);
+        let missing_value = "0\nSECTION\n2\nENTITIES\n0";
+        let invalid_code = "0\nSECTION\nENTITIES\n2\n";
+
+        for dxf in [&invalid_number, &missing_code, missing_value, invalid_code]
+        {
+            assert!(matches!(dxf_from_str(dxf), Err(ImportError::Dxf(_))));
+        }
+    }
+
+    fn dxf(entities: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+        let mut dxf = "0\nSECTION\n2\nENTITIES\n".to_owned();
+        for entity in entities {
+            dxf.push_str(entity.as_ref());
+        }
+        dxf.push_str("0\nENDSEC\n0\nEOF\n");
+
+        dxf
+    }
+
+    fn polyline(points: &[[f64; 2]]) -> String {
+        let mut entity = "0\nLWPOLYLINE\n70\n1\n".to_owned();
+        for [x, y] in points {
+            entity.push_str(&format!("10\n{}\n20\n{}\n", x, y));
+        }
+
+        entity
+    }
+
+    fn circle(radius: f64) -> String {
+        format!("0\nCIRCLE\n10\n0\n20\n0\n40\n{}\n", radius)
+    }
+}
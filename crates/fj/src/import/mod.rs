@@ -0,0 +1,295 @@
+//! Import shapes from files
+//!
+//! 2D outlines can be imported from DXF and SVG files. Closed paths in those
+//! files become [`Sketch`]es. Paths that lie within other paths become holes in
+//! those, as described in [`shapes_from_outlines`]. Open paths are ignored.
+//!
+//! Triangle meshes can be imported from STL and 3MF files, as a [`Mesh`].
+//!
+//! Unless noted otherwise, the imported shapes use the coordinates of the file,
+//! without any conversion of units.
+//!
+//! [`Mesh`]: crate::Mesh
+//! [`Sketch`]: crate::Sketch
+//! [`shapes_from_outlines`]: crate::shapes_from_outlines
+
+mod dxf;
+mod stl;
+mod svg;
+mod threemf;
+
+pub use self::{
+    dxf::{dxf, dxf_from_str},
+    stl::{stl, stl_from_bytes},
+    svg::{svg, svg_from_str},
+    threemf::{threemf, threemf_from_bytes},
+};
+
+use std::{fmt, io};
+
+use crate::{
+    outline::{distance, end, Arc},
+    Outline, SketchSegment,
+};
+
+/// An error that can occur when importing a file
+#[derive(Debug)]
+pub enum ImportError {
+    /// The file could not be read
+    Io(io::Error),
+
+    /// The file is not valid XML
+    Xml(roxmltree::Error),
+
+    /// An SVG attribute could not be parsed
+    Svg(svgtypes::Error),
+
+    /// The file is not a valid DXF file
+    Dxf(String),
+
+    /// The file is not a valid STL file
+    Stl(String),
+
+    /// The file is not a valid 3MF archive
+    Zip(zip::result::ZipError),
+
+    /// The file is not a valid 3MF file
+    ThreeMf(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Error reading file: {}", err),
+            Self::Xml(err) => write!(f, "Error parsing XML: {}", err),
+            Self::Svg(err) => write!(f, "Error parsing SVG: {}", err),
+            Self::Dxf(err) => write!(f, "Error parsing DXF: {}", err),
+            Self::Stl(err) => write!(f, "Error parsing STL: {}", err),
+            Self::Zip(err) => write!(f, "Error reading 3MF archive: {}", err),
+            Self::ThreeMf(err) => write!(f, "Error parsing 3MF: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Xml(err) => Some(err),
+            Self::Svg(err) => Some(err),
+            Self::Zip(err) => Some(err),
+            Self::Dxf(_) | Self::Stl(_) | Self::ThreeMf(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ImportError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<roxmltree::Error> for ImportError {
+    fn from(err: roxmltree::Error) -> Self {
+        Self::Xml(err)
+    }
+}
+
+impl From<svgtypes::Error> for ImportError {
+    fn from(err: svgtypes::Error) -> Self {
+        Self::Svg(err)
+    }
+}
+
+impl From<zip::result::ZipError> for ImportError {
+    fn from(err: zip::result::ZipError) -> Self {
+        Self::Zip(err)
+    }
+}
+
+impl Outline {
+    /// Transform the outline
+    ///
+    /// Arcs are converted into Bézier curves, if the transform doesn't preserve
+    /// their circular shape.
+    fn transform(&self, transform: &Affine) -> Self {
+        let similarity = transform.is_similarity();
+
+        let mut segments = Vec::new();
+        let mut start = self.start;
+
+        for segment in &self.segments {
+            match segment.clone() {
+                SketchSegment::Line { end } => {
+                    segments.push(SketchSegment::Line {
+                        end: transform.apply(end),
+                    });
+                }
+                SketchSegment::Arc {
+                    center,
+                    end,
+                    counterclockwise,
+                } => {
+                    if similarity {
+                        segments.push(SketchSegment::Arc {
+                            center: transform.apply(center),
+                            end: transform.apply(end),
+                            counterclockwise: counterclockwise
+                                != (transform.determinant() < 0.),
+                        });
+                    } else {
+                        let arc =
+                            Arc::new(center, start, end, counterclockwise);
+                        segments.extend(arc.to_beziers(end).into_iter().map(
+                            |segment| transform_segment(segment, transform),
+                        ));
+                    }
+                }
+                segment => segments.push(transform_segment(segment, transform)),
+            }
+
+            start = end(segment);
+        }
+
+        Self {
+            start: transform.apply(self.start),
+            segments,
+        }
+    }
+}
+
+/// Join paths that share end points into longer paths
+///
+/// File formats like DXF represent outlines as a collection of separate lines
+/// and arcs. This function chains those together, reversing them as required,
+/// and closes the resulting paths, if their ends meet.
+///
+/// End points are considered identical, if their distance is within
+/// `tolerance`.
+fn join(mut pieces: Vec<Outline>, tolerance: f64) -> Vec<Outline> {
+    let mut paths = Vec::new();
+
+    while let Some(mut path) = pieces.pop() {
+        loop {
+            if distance(path.end(), path.start) <= tolerance {
+                path.close(tolerance);
+                paths.push(path);
+                break;
+            }
+
+            let end = path.end();
+            let next = pieces.iter().position(|piece| {
+                distance(piece.start, end) <= tolerance
+                    || distance(piece.end(), end) <= tolerance
+            });
+
+            match next {
+                Some(i) => {
+                    let piece = pieces.swap_remove(i);
+                    let piece = if distance(piece.start, end) <= tolerance {
+                        piece
+                    } else {
+                        piece.reverse()
+                    };
+
+                    path.segments.extend(piece.segments);
+                }
+                None => {
+                    // The path can't be closed. It's ignored.
+                    break;
+                }
+            }
+        }
+    }
+
+    paths
+}
+
+/// An affine transform, using the same conventions as SVG
+///
+/// Transforms `[x, y]` into `[a * x + c * y + e, b * x + d * y + f]`.
+#[derive(Clone, Copy, Debug)]
+struct Affine {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    e: f64,
+    f: f64,
+}
+
+impl Affine {
+    fn identity() -> Self {
+        Self::new(1., 0., 0., 1., 0., 0.)
+    }
+
+    fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    /// Create a transform that applies `other` first, then `self`
+    fn then(&self, other: &Self) -> Self {
+        Self {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    fn apply(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        ]
+    }
+
+    fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// Indicate whether the transform preserves circles
+    fn is_similarity(&self) -> bool {
+        let epsilon = 1e-12 * (self.a.abs() + self.b.abs());
+
+        let rotation = (self.a - self.d).abs() <= epsilon
+            && (self.b + self.c).abs() <= epsilon;
+        let reflection = (self.a + self.d).abs() <= epsilon
+            && (self.b - self.c).abs() <= epsilon;
+
+        rotation || reflection
+    }
+}
+
+/// Transform a segment that isn't an arc
+fn transform_segment(
+    segment: SketchSegment,
+    transform: &Affine,
+) -> SketchSegment {
+    match segment {
+        SketchSegment::Line { end } => SketchSegment::Line {
+            end: transform.apply(end),
+        },
+        SketchSegment::Arc { .. } => {
+            unreachable!("Arcs need to be handled by the caller")
+        }
+        SketchSegment::CubicBezier { controls, end } => {
+            SketchSegment::CubicBezier {
+                controls: controls.map(|point| transform.apply(point)),
+                end: transform.apply(end),
+            }
+        }
+        SketchSegment::BSpline {
+            degree,
+            control_points,
+        } => SketchSegment::BSpline {
+            degree,
+            control_points: control_points
+                .into_iter()
+                .map(|point| transform.apply(point))
+                .collect(),
+        },
+    }
+}
@@ -0,0 +1,263 @@
+use std::{fs, path::Path as FilePath, str::SplitAsciiWhitespace};
+
+use crate::Mesh;
+
+use super::ImportError;
+
+/// Import the triangles of an STL file
+///
+/// Supports binary and ASCII STL files. The normals that are stored in the file
+/// are ignored, as they are implied by the order of each triangle's vertices.
+/// STL files don't specify units, so the coordinates are used as they are.
+pub fn stl(path: impl AsRef<FilePath>) -> Result<Mesh, ImportError> {
+    let data = fs::read(path)?;
+    stl_from_bytes(&data)
+}
+
+/// Import the triangles of STL data
+///
+/// See [`stl`] for more information.
+pub fn stl_from_bytes(data: &[u8]) -> Result<Mesh, ImportError> {
+    // ASCII STL files start with "solid". Unfortunately, some binary files do
+    // too, in their header. Those can still be identified by their size, which
+    // must match the number of triangles.
+    let triangles = if is_binary(data) || !data.starts_with(b"solid") {
+        binary(data)?
+    } else {
+        let data = std::str::from_utf8(data).map_err(|_| {
+            ImportError::Stl("ASCII STL data is not valid UTF-8".to_owned())
+        })?;
+        ascii(data)?
+    };
+
+    Ok(Mesh::from_triangles(triangles))
+}
+
+/// The size of the header of a binary STL file, including the triangle count
+const HEADER: usize = 84;
+
+/// The size of a triangle in a binary STL file
+///
+/// Each triangle consists of its normal, its three vertices, and an "attribute
+/// byte count".
+const TRIANGLE: usize = 50;
+
+fn is_binary(data: &[u8]) -> bool {
+    match triangle_count(data) {
+        Some(count) => HEADER + count * TRIANGLE == data.len(),
+        None => false,
+    }
+}
+
+fn triangle_count(data: &[u8]) -> Option<usize> {
+    let count = data.get(80..HEADER)?;
+    let count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]);
+    Some(count as usize)
+}
+
+fn binary(data: &[u8]) -> Result<Vec<[[f64; 3]; 3]>, ImportError> {
+    let count = triangle_count(data).ok_or_else(|| {
+        ImportError::Stl("Binary STL data is missing its header".to_owned())
+    })?;
+
+    if data.len() < HEADER + count * TRIANGLE {
+        return Err(ImportError::Stl(format!(
+            "Binary STL data is too short for {} triangles",
+            count
+        )));
+    }
+
+    let mut triangles = Vec::new();
+    for triangle in data[HEADER..].chunks_exact(TRIANGLE).take(count) {
+        // Skip the normal.
+        let vertices = &triangle[12..48];
+
+        let vertex = |i: usize| {
+            [0, 1, 2].map(|j| {
+                let offset = i * 12 + j * 4;
+                let bytes = &vertices[offset..offset + 4];
+                let value = f32::from_le_bytes([
+                    bytes[0], bytes[1], bytes[2], bytes[3],
+                ]);
+                f64::from(value)
+            })
+        };
+
+        let triangle = [vertex(0), vertex(1), vertex(2)];
+        if let Some(value) =
+            triangle.iter().flatten().find(|value| !value.is_finite())
+        {
+            return Err(ImportError::Stl(format!(
+                "Invalid coordinate `{}` in STL data",
+                value
+            )));
+        }
+
+        triangles.push(triangle);
+    }
+
+    Ok(triangles)
+}
+
+fn ascii(data: &str) -> Result<Vec<[[f64; 3]; 3]>, ImportError> {
+    let mut triangles = Vec::new();
+    let mut vertices = Vec::new();
+
+    let mut tokens = data.split_ascii_whitespace();
+    while let Some(token) = tokens.next() {
+        match token {
+            "vertex" => {
+                let x = number(&mut tokens)?;
+                let y = number(&mut tokens)?;
+                let z = number(&mut tokens)?;
+
+                vertices.push([x, y, z]);
+            }
+            "endfacet" => {
+                let triangle = match vertices[..] {
+                    [a, b, c] => [a, b, c],
+                    _ => {
+                        return Err(ImportError::Stl(format!(
+                            "Expected facet with 3 vertices, found {}",
+                            vertices.len()
+                        )));
+                    }
+                };
+
+                triangles.push(triangle);
+                vertices.clear();
+            }
+            // Everything else is either a keyword that doesn't carry any
+            // information we need, or the components of a normal.
+            _ => {}
+        }
+    }
+
+    Ok(triangles)
+}
+
+fn number(tokens: &mut SplitAsciiWhitespace) -> Result<f64, ImportError> {
+    let token = tokens.next().ok_or_else(|| {
+        ImportError::Stl("Unexpected end of ASCII STL data".to_owned())
+    })?;
+
+    // `parse` accepts "nan" and "inf", which aren't valid coordinates.
+    match token.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(ImportError::Stl(format!(
+            "Invalid coordinate `{}` in STL data",
+            token
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import::ImportError;
+
+    use super::stl_from_bytes;
+
+    const TRIANGLES: [[[f64; 3]; 3]; 2] = [
+        [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+        [[1., 0., 0.], [1., 1., 0.], [0., 1., 0.]],
+    ];
+
+    #[test]
+    fn ascii() {
+        let stl = "\
+            solid square\n\
+              facet normal 0 0 1\n\
+                outer loop\n\
+                  vertex 0 0 0\n\
+                  vertex 1 0 0\n\
+                  vertex 0 1 0\n\
+                endloop\n\
+              endfacet\n\
+              facet normal 0 0 1\n\
+                outer loop\n\
+                  vertex 1.0e0 0 0\n\
+                  vertex 1 1 0\n\
+                  vertex 0 1 0\n\
+                endloop\n\
+              endfacet\n\
+            endsolid square\n";
+
+        let mesh = stl_from_bytes(stl.as_bytes()).unwrap();
+        assert_eq!(mesh.to_triangles(), TRIANGLES);
+    }
+
+    #[test]
+    fn binary() {
+        let mesh = stl_from_bytes(&binary_stl(b"square", &TRIANGLES)).unwrap();
+        assert_eq!(mesh.to_triangles(), TRIANGLES);
+    }
+
+    #[test]
+    fn binary_with_ascii_header() {
+        // Some exporters start the header of binary files with "solid".
+        let mesh =
+            stl_from_bytes(&binary_stl(b"solid square", &TRIANGLES)).unwrap();
+        assert_eq!(mesh.to_triangles(), TRIANGLES);
+    }
+
+    #[test]
+    fn truncated_binary() {
+        let mut stl = binary_stl(b"square", &TRIANGLES);
+        stl.truncate(stl.len() - 10);
+
+        let err = stl_from_bytes(&stl).unwrap_err();
+        assert!(matches!(err, ImportError::Stl(_)), "{:?}", err);
+
+        let err = stl_from_bytes(&stl[..40]).unwrap_err();
+        assert!(matches!(err, ImportError::Stl(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn malformed_ascii() {
+        let incomplete = "solid a\nfacet normal 0 0 1\nouter loop\n\
+            vertex 0 0 0\nvertex 1 0 0\nendloop\nendfacet\nendsolid a\n";
+        let invalid = "solid a\nfacet normal 0 0 1\nouter loop\n\
+            vertex 0 zero 0\n";
+        let truncated = "solid a\nfacet normal 0 0 1\nouter loop\nvertex 0 0";
+        let not_a_number = "solid a\nfacet normal 0 0 1\nouter loop\n\
+            vertex nan 0 0\nvertex 1 0 0\nvertex 0 1 0\nendloop\nendfacet\n\
+            endsolid a\n";
+        let infinite = "solid a\nfacet normal 0 0 1\nouter loop\n\
+            vertex 0 0 0\nvertex inf 0 0\nvertex 0 1 0\nendloop\nendfacet\n\
+            endsolid a\n";
+
+        for stl in [incomplete, invalid, truncated, not_a_number, infinite] {
+            let err = stl_from_bytes(stl.as_bytes()).unwrap_err();
+            assert!(matches!(err, ImportError::Stl(_)), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn non_finite_binary() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut triangles = TRIANGLES;
+            triangles[1][2][0] = value;
+
+            let err =
+                stl_from_bytes(&binary_stl(b"square", &triangles)).unwrap_err();
+            assert!(matches!(err, ImportError::Stl(_)), "{:?}", err);
+        }
+    }
+
+    fn binary_stl(header: &[u8], triangles: &[[[f64; 3]; 3]]) -> Vec<u8> {
+        let mut data = header.to_vec();
+        data.resize(80, 0);
+        data.extend((triangles.len() as u32).to_le_bytes());
+
+        for triangle in triangles {
+            // The normal is ignored on import.
+            data.extend([0; 12]);
+            for component in triangle.iter().flatten() {
+                data.extend((*component as f32).to_le_bytes());
+            }
+            data.extend([0; 2]);
+        }
+
+        data
+    }
+}
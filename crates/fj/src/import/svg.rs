@@ -0,0 +1,601 @@
+use std::{f64::consts::PI, fs, path::Path as FilePath, str::FromStr};
+
+use svgtypes::{Length, PathParser, PathSegment, PointsParser, Transform};
+
+use crate::{
+    outline::Arc, shapes_from_outlines, Outline, Shape2d, SketchSegment,
+};
+
+use super::{Affine, ImportError};
+
+/// Import the closed paths of an SVG file
+///
+/// Supports the `path`, `rect`, `circle`, `ellipse`, and `polygon` elements,
+/// including any transforms that apply to them. Text, and references to other
+/// elements via `use`, are not supported.
+///
+/// SVG's y axis points down. The y axis of the imported shapes points up, so
+/// the shapes end up upside down, compared to how the file would be displayed.
+/// Circular arcs are imported exactly. Elliptical arcs and ellipses are
+/// approximated using Bézier curves.
+pub fn svg(path: impl AsRef<FilePath>) -> Result<Vec<Shape2d>, ImportError> {
+    let svg = fs::read_to_string(path)?;
+    svg_from_str(&svg)
+}
+
+/// Import the closed paths of SVG data
+///
+/// See [`svg`] for more information.
+pub fn svg_from_str(svg: &str) -> Result<Vec<Shape2d>, ImportError> {
+    let document = roxmltree::Document::parse(svg)?;
+
+    // Flip the y axis.
+    let transform = Affine::new(1., 0., 0., -1., 0., 0.);
+
+    let mut paths = Vec::new();
+    collect_paths(document.root_element(), &transform, &mut paths)?;
+
+    Ok(shapes_from_outlines(paths))
+}
+
+fn collect_paths(
+    node: roxmltree::Node,
+    transform: &Affine,
+    paths: &mut Vec<Outline>,
+) -> Result<(), ImportError> {
+    let transform = match node.attribute("transform") {
+        Some(value) => {
+            let Transform { a, b, c, d, e, f } = Transform::from_str(value)?;
+            transform.then(&Affine::new(a, b, c, d, e, f))
+        }
+        None => *transform,
+    };
+
+    let local_paths = match node.tag_name().name() {
+        // These elements aren't rendered directly.
+        "clipPath" | "defs" | "marker" | "mask" | "pattern" | "symbol" => {
+            return Ok(());
+        }
+
+        "path" => match node.attribute("d") {
+            Some(data) => path_data(data)?,
+            None => Vec::new(),
+        },
+        "rect" => rect(node)?,
+        "circle" => {
+            let [cx, cy, r] = lengths(node, ["cx", "cy", "r"])?;
+            ellipse([cx, cy], [r, r])
+        }
+        "ellipse" => {
+            let [cx, cy, rx, ry] = lengths(node, ["cx", "cy", "rx", "ry"])?;
+            ellipse([cx, cy], [rx, ry])
+        }
+        "polygon" => {
+            let points = node.attribute("points").unwrap_or("");
+            let mut points = PointsParser::from(points).map(|(x, y)| [x, y]);
+
+            match points.next() {
+                Some(start) => {
+                    let mut path = Outline::new(start);
+                    path.segments
+                        .extend(points.map(|end| SketchSegment::Line { end }));
+                    path.close(0.);
+
+                    vec![path]
+                }
+                None => Vec::new(),
+            }
+        }
+
+        _ => Vec::new(),
+    };
+
+    paths.extend(local_paths.iter().map(|path| path.transform(&transform)));
+
+    for child in node.children().filter(|node| node.is_element()) {
+        collect_paths(child, &transform, paths)?;
+    }
+
+    Ok(())
+}
+
+/// Convert path data into paths
+///
+/// Only closed subpaths are returned.
+fn path_data(data: &str) -> Result<Vec<Outline>, ImportError> {
+    let mut paths = Vec::new();
+    let mut path: Option<Outline> = None;
+
+    let mut current = [0., 0.];
+
+    // The control point of the previous segment, if it was a Bézier curve.
+    // Required to handle the "smooth" variants of Bézier curves.
+    let mut previous_cubic = None;
+    let mut previous_quadratic = None;
+
+    for segment in PathParser::from(data) {
+        let segment = segment?;
+
+        let absolute = |abs: bool, [x, y]: [f64; 2]| {
+            if abs {
+                [x, y]
+            } else {
+                [current[0] + x, current[1] + y]
+            }
+        };
+        let reflect = |control: Option<[f64; 2]>| match control {
+            Some([x, y]) => [2. * current[0] - x, 2. * current[1] - y],
+            None => current,
+        };
+
+        let mut cubic = None;
+        let mut quadratic = None;
+
+        let (segments, end) = match segment {
+            PathSegment::MoveTo { abs, x, y } => {
+                let start = absolute(abs, [x, y]);
+
+                paths.extend(path.take().filter(Outline::is_closed));
+                path = Some(Outline::new(start));
+
+                current = start;
+                continue;
+            }
+            PathSegment::ClosePath { .. } => {
+                if let Some(mut path) = path.take() {
+                    // Account for floating point accuracy issues that might
+                    // result from adding up relative coordinates.
+                    let [x, y] = path.start;
+                    path.close(1e-9 * (1. + x.abs() + y.abs()));
+
+                    current = path.start;
+                    paths.push(path);
+                }
+
+                // Any following segments start a new subpath at the same
+                // point.
+                path = Some(Outline::new(current));
+                continue;
+            }
+
+            PathSegment::LineTo { abs, x, y } => {
+                let end = absolute(abs, [x, y]);
+                (vec![SketchSegment::Line { end }], end)
+            }
+            PathSegment::HorizontalLineTo { abs, x } => {
+                let end = if abs {
+                    [x, current[1]]
+                } else {
+                    [current[0] + x, current[1]]
+                };
+                (vec![SketchSegment::Line { end }], end)
+            }
+            PathSegment::VerticalLineTo { abs, y } => {
+                let end = if abs {
+                    [current[0], y]
+                } else {
+                    [current[0], current[1] + y]
+                };
+                (vec![SketchSegment::Line { end }], end)
+            }
+            PathSegment::CurveTo {
+                abs,
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let a = absolute(abs, [x1, y1]);
+                let b = absolute(abs, [x2, y2]);
+                let end = absolute(abs, [x, y]);
+
+                cubic = Some(b);
+                (
+                    vec![SketchSegment::CubicBezier {
+                        controls: [a, b],
+                        end,
+                    }],
+                    end,
+                )
+            }
+            PathSegment::SmoothCurveTo { abs, x2, y2, x, y } => {
+                let a = reflect(previous_cubic);
+                let b = absolute(abs, [x2, y2]);
+                let end = absolute(abs, [x, y]);
+
+                cubic = Some(b);
+                (
+                    vec![SketchSegment::CubicBezier {
+                        controls: [a, b],
+                        end,
+                    }],
+                    end,
+                )
+            }
+            PathSegment::Quadratic { abs, x1, y1, x, y } => {
+                let control = absolute(abs, [x1, y1]);
+                let end = absolute(abs, [x, y]);
+
+                quadratic = Some(control);
+                (vec![quadratic_bezier(control, end)], end)
+            }
+            PathSegment::SmoothQuadratic { abs, x, y } => {
+                let control = reflect(previous_quadratic);
+                let end = absolute(abs, [x, y]);
+
+                quadratic = Some(control);
+                (vec![quadratic_bezier(control, end)], end)
+            }
+            PathSegment::EllipticalArc {
+                abs,
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => {
+                let end = absolute(abs, [x, y]);
+                let segments = elliptical_arc(
+                    current,
+                    end,
+                    [rx, ry],
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                );
+                (segments, end)
+            }
+        };
+
+        previous_cubic = cubic;
+        previous_quadratic = quadratic;
+
+        // Segments of zero length don't contribute anything, but would result
+        // in invalid edges.
+        if end != current {
+            if let Some(path) = &mut path {
+                path.segments.extend(segments);
+            }
+        }
+
+        current = end;
+    }
+
+    paths.extend(path.take().filter(Outline::is_closed));
+
+    Ok(paths)
+}
+
+/// Convert a `rect` element into a path
+fn rect(node: roxmltree::Node) -> Result<Vec<Outline>, ImportError> {
+    let [x, y, width, height] = lengths(node, ["x", "y", "width", "height"])?;
+
+    if width <= 0. || height <= 0. {
+        return Ok(Vec::new());
+    }
+
+    // If only one of the radii is specified, it's used for both.
+    let rx = node.attribute("rx").map(length).transpose()?;
+    let ry = node.attribute("ry").map(length).transpose()?;
+    let (rx, ry) = match (rx, ry) {
+        (Some(rx), Some(ry)) => (rx, ry),
+        (Some(r), None) | (None, Some(r)) => (r, r),
+        (None, None) => (0., 0.),
+    };
+    let rx = rx.clamp(0., width / 2.);
+    let ry = ry.clamp(0., height / 2.);
+
+    let mut path = Outline::new([x + rx, y]);
+
+    let corner = |center: [f64; 2], start: [f64; 2], end: [f64; 2]| {
+        if rx == 0. || ry == 0. {
+            Vec::new()
+        } else {
+            elliptical_arc(start, end, [rx, ry], 0., false, true)
+                .into_iter()
+                .map(|segment| match segment {
+                    // The arc is computed from its end points. Replace its
+                    // center with the exact one, if it's circular.
+                    SketchSegment::Arc {
+                        end,
+                        counterclockwise,
+                        ..
+                    } => SketchSegment::Arc {
+                        center,
+                        end,
+                        counterclockwise,
+                    },
+                    segment => segment,
+                })
+                .collect()
+        }
+    };
+
+    let [left, top, right, bottom] = [x, y, x + width, y + height];
+
+    let corners = [
+        ([right - rx, top], [right, top + ry], [right - rx, top + ry]),
+        (
+            [right, bottom - ry],
+            [right - rx, bottom],
+            [right - rx, bottom - ry],
+        ),
+        (
+            [left + rx, bottom],
+            [left, bottom - ry],
+            [left + rx, bottom - ry],
+        ),
+        ([left, top + ry], [left + rx, top], [left + rx, top + ry]),
+    ];
+    for (line_end, corner_end, center) in corners {
+        if line_end != path.end() {
+            path.segments.push(SketchSegment::Line { end: line_end });
+        }
+        if corner_end != line_end {
+            path.segments.extend(corner(center, line_end, corner_end));
+        }
+    }
+    path.close(0.);
+
+    Ok(vec![path])
+}
+
+/// Create a closed path for an ellipse
+fn ellipse(center: [f64; 2], [rx, ry]: [f64; 2]) -> Vec<Outline> {
+    if rx <= 0. || ry <= 0. {
+        return Vec::new();
+    }
+
+    let [cx, cy] = center;
+    let start = [cx + rx, cy];
+
+    let mut path = Outline::new(start);
+    if rx == ry {
+        path.segments.push(SketchSegment::Arc {
+            center,
+            end: start,
+            counterclockwise: true,
+        });
+    } else {
+        let arc = Arc {
+            center,
+            a: [rx, 0.],
+            b: [0., ry],
+            angle: PI * 2.,
+        };
+        path.segments.extend(arc.to_beziers(start));
+    }
+
+    vec![path]
+}
+
+/// Convert an SVG arc into sketch segments
+///
+/// Follows the SVG specification's advice on converting arcs from endpoint to
+/// center parameterization, including the correction of out-of-range radii.
+fn elliptical_arc(
+    start: [f64; 2],
+    end: [f64; 2],
+    [rx, ry]: [f64; 2],
+    x_axis_rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+) -> Vec<SketchSegment> {
+    let mut rx = rx.abs();
+    let mut ry = ry.abs();
+
+    if rx == 0. || ry == 0. {
+        return vec![SketchSegment::Line { end }];
+    }
+
+    let (sin, cos) = x_axis_rotation.to_radians().sin_cos();
+
+    // Transform the midpoint between start and end into a coordinate system
+    // whose axes are aligned with the ellipse's.
+    let dx = (start[0] - end[0]) / 2.;
+    let dy = (start[1] - end[1]) / 2.;
+    let x1 = cos * dx + sin * dy;
+    let y1 = -sin * dx + cos * dy;
+
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1. {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut factor = (numerator / denominator).max(0.).sqrt();
+    if large_arc == sweep {
+        factor = -factor;
+    }
+    let cx1 = factor * rx * y1 / ry;
+    let cy1 = -factor * ry * x1 / rx;
+
+    let center = [
+        cos * cx1 - sin * cy1 + (start[0] + end[0]) / 2.,
+        sin * cx1 + cos * cy1 + (start[1] + end[1]) / 2.,
+    ];
+
+    if (rx - ry).abs() <= 1e-9 * rx {
+        // In SVG's coordinate system, a positive sweep is counter-clockwise,
+        // in the mathematical sense.
+        return vec![SketchSegment::Arc {
+            center,
+            end,
+            counterclockwise: sweep,
+        }];
+    }
+
+    // The arc is elliptical. Compute its start angle and sweep angle.
+    let angle = |x: f64, y: f64| f64::atan2(y, x);
+    let theta = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut delta = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - theta;
+    if sweep && delta < 0. {
+        delta += PI * 2.;
+    }
+    if !sweep && delta > 0. {
+        delta -= PI * 2.;
+    }
+
+    // Express the arc with conjugate semi-diameters that start at the start
+    // of the arc, as `Arc` expects.
+    let u = [cos * rx, sin * rx];
+    let v = [-sin * ry, cos * ry];
+    let (sin_theta, cos_theta) = theta.sin_cos();
+    let arc = Arc {
+        center,
+        a: [
+            u[0] * cos_theta + v[0] * sin_theta,
+            u[1] * cos_theta + v[1] * sin_theta,
+        ],
+        b: [
+            v[0] * cos_theta - u[0] * sin_theta,
+            v[1] * cos_theta - u[1] * sin_theta,
+        ],
+        angle: delta,
+    };
+
+    arc.to_beziers(end)
+}
+
+/// Create a quadratic Bézier curve
+///
+/// A B-spline of degree 2 with 3 control points and a clamped, uniform knot
+/// vector is a quadratic Bézier curve.
+fn quadratic_bezier(control: [f64; 2], end: [f64; 2]) -> SketchSegment {
+    SketchSegment::BSpline {
+        degree: 2,
+        control_points: vec![control, end],
+    }
+}
+
+/// Read length attributes, defaulting to zero
+fn lengths<const N: usize>(
+    node: roxmltree::Node,
+    names: [&str; N],
+) -> Result<[f64; N], ImportError> {
+    let mut lengths = [0.; N];
+
+    for (length_, name) in lengths.iter_mut().zip(names) {
+        if let Some(value) = node.attribute(name) {
+            *length_ = length(value)?;
+        }
+    }
+
+    Ok(lengths)
+}
+
+/// Parse a length
+///
+/// Units are ignored, meaning all lengths are treated as user units.
+fn length(value: &str) -> Result<f64, ImportError> {
+    Ok(Length::from_str(value)?.number)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{import::ImportError, Shape2d, SketchSegment};
+
+    use super::svg_from_str;
+
+    #[test]
+    fn rectangle_with_two_holes() {
+        let svg = svg(
+            r#"<path d="M0 0 H10 V5 H0 Z M1 1 H3 V3 H1 Z M6 1 V3 H8 V1 Z"/>"#,
+        );
+
+        let shapes = svg_from_str(&svg).unwrap();
+        assert_eq!(shapes.len(), 1);
+
+        match &shapes[0] {
+            Shape2d::Difference(difference) => {
+                assert_eq!(difference.holes().len(), 2);
+            }
+            shape => panic!("Expected difference, got {:?}", shape),
+        }
+    }
+
+    #[test]
+    fn arc() {
+        // A half-disk. The y axis is flipped on import.
+        let svg = svg(r#"<path d="M0 1 A1 1 0 0 0 0 -1 Z"/>"#);
+
+        let shapes = svg_from_str(&svg).unwrap();
+        assert_eq!(shapes.len(), 1);
+
+        match &shapes[0] {
+            Shape2d::Sketch(sketch) => {
+                assert_eq!(sketch.start(), Some([0., -1.]));
+                assert_eq!(
+                    sketch.to_segments(),
+                    [
+                        SketchSegment::Arc {
+                            center: [0., 0.],
+                            end: [0., 1.],
+                            counterclockwise: true,
+                        },
+                        SketchSegment::Line { end: [0., -1.] },
+                    ]
+                );
+            }
+            shape => panic!("Expected sketch, got {:?}", shape),
+        }
+    }
+
+    #[test]
+    fn nested_island() {
+        let svg =
+            svg(r#"<circle r="10"/><circle r="5"/><circle cx="1" r="2"/>"#);
+
+        let shapes = svg_from_str(&svg).unwrap();
+        assert_eq!(shapes.len(), 2);
+
+        match &shapes[0] {
+            Shape2d::Difference(difference) => {
+                assert_eq!(difference.holes().len(), 1);
+            }
+            shape => panic!("Expected difference, got {:?}", shape),
+        }
+        match &shapes[1] {
+            Shape2d::Sketch(sketch) => {
+                assert_eq!(
+                    sketch.to_segments(),
+                    [SketchSegment::Arc {
+                        center: [1., 0.],
+                        end: [3., 0.],
+                        counterclockwise: true,
+                    }]
+                );
+            }
+            shape => panic!("Expected sketch, got {:?}", shape),
+        }
+    }
+
+    #[test]
+    fn malformed_input() {
+        assert!(matches!(
+            svg_from_str("<svg><path d=\"M0 0 H10\"></svg>"),
+            Err(ImportError::Xml(_))
+        ));
+        assert!(matches!(
+            svg_from_str(&svg(r#"<path d="M0 0 H10 V"/>"#)),
+            Err(ImportError::Svg(_))
+        ));
+        assert!(matches!(
+            svg_from_str(&svg(r#"<circle r="ten"/>"#)),
+            Err(ImportError::Svg(_))
+        ));
+    }
+
+    fn svg(elements: &str) -> String {
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg">{}</svg>"#,
+            elements
+        )
+    }
+}
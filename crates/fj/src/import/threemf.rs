@@ -0,0 +1,516 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Cursor, Read as _},
+    path::Path as FilePath,
+};
+
+use crate::Mesh;
+
+use super::ImportError;
+
+/// Import the triangles of a 3MF file
+///
+/// All objects that are part of the file's build are imported, including their
+/// components and transforms, and combined into a single mesh. Colors and other
+/// materials are ignored.
+///
+/// The coordinates are converted into millimeters, according to the units
+/// specified in the file.
+pub fn threemf(path: impl AsRef<FilePath>) -> Result<Mesh, ImportError> {
+    let data = fs::read(path)?;
+    threemf_from_bytes(&data)
+}
+
+/// Import the triangles of 3MF data
+///
+/// See [`threemf`] for more information.
+pub fn threemf_from_bytes(data: &[u8]) -> Result<Mesh, ImportError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))?;
+
+    let relationships = read_file(&mut archive, "_rels/.rels")?;
+    let model_path = model_path(&relationships)?;
+    let model = read_file(&mut archive, &model_path)?;
+
+    let document = roxmltree::Document::parse(&model)?;
+    let model = document.root_element();
+
+    let scale = match model.attribute("unit").unwrap_or("millimeter") {
+        "micron" => 0.001,
+        "millimeter" => 1.,
+        "centimeter" => 10.,
+        "inch" => 25.4,
+        "foot" => 304.8,
+        "meter" => 1000.,
+        unit => {
+            return Err(ImportError::ThreeMf(format!(
+                "Unknown unit `{}`",
+                unit
+            )));
+        }
+    };
+
+    let objects = children(model, "resources")
+        .flat_map(|resources| children(resources, "object"))
+        .map(|object| Ok((attribute(object, "id")?, object)))
+        .collect::<Result<HashMap<_, _>, ImportError>>()?;
+
+    let mut triangles = Vec::new();
+    for item in
+        children(model, "build").flat_map(|build| children(build, "item"))
+    {
+        let transform = Transform::parse(item.attribute("transform"))?;
+        let transform = Transform::scale(scale).then(&transform);
+
+        add_object(
+            &objects,
+            attribute(item, "objectid")?,
+            &transform,
+            0,
+            &mut triangles,
+        )?;
+    }
+
+    Ok(Mesh::from_triangles(triangles))
+}
+
+fn read_file(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> Result<String, ImportError> {
+    let mut file = archive.by_name(name)?;
+
+    let mut data = String::new();
+    file.read_to_string(&mut data)?;
+
+    Ok(data)
+}
+
+/// Find the path of the model within the archive
+fn model_path(relationships: &str) -> Result<String, ImportError> {
+    const MODEL: &str =
+        "http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel";
+
+    let document = roxmltree::Document::parse(relationships)?;
+
+    let target = document
+        .root_element()
+        .children()
+        .filter(|node| node.tag_name().name() == "Relationship")
+        .find(|node| node.attribute("Type") == Some(MODEL))
+        .and_then(|node| node.attribute("Target"))
+        .ok_or_else(|| {
+            ImportError::ThreeMf("Archive doesn't contain a model".to_owned())
+        })?;
+
+    // Targets are absolute paths within the archive, but the archive doesn't
+    // use a leading slash for its file names.
+    Ok(target.trim_start_matches('/').to_owned())
+}
+
+/// Add the triangles of an object, and those of its components
+fn add_object(
+    objects: &HashMap<u32, roxmltree::Node>,
+    id: u32,
+    transform: &Transform,
+    depth: usize,
+    triangles: &mut Vec<[[f64; 3]; 3]>,
+) -> Result<(), ImportError> {
+    // Objects must not reference themselves, directly or indirectly. Guard
+    // against invalid files that do anyway.
+    if depth > 64 {
+        return Err(ImportError::ThreeMf(format!(
+            "Components of object {} are nested too deeply",
+            id
+        )));
+    }
+
+    let object = objects.get(&id).ok_or_else(|| {
+        ImportError::ThreeMf(format!("Reference to unknown object {}", id))
+    })?;
+
+    for mesh in children(*object, "mesh") {
+        let vertices = children(mesh, "vertices")
+            .flat_map(|vertices| children(vertices, "vertex"))
+            .map(|vertex| {
+                let x = attribute(vertex, "x")?;
+                let y = attribute(vertex, "y")?;
+                let z = attribute(vertex, "z")?;
+
+                // Non-finite values can come from the vertex or from one of
+                // the transforms. Either way, they make the mesh unusable.
+                let point = transform.apply([x, y, z]);
+                if point.iter().any(|value: &f64| !value.is_finite()) {
+                    return Err(ImportError::ThreeMf(format!(
+                        "Vertex of object {} has invalid coordinates {:?}",
+                        id, point
+                    )));
+                }
+
+                Ok(point)
+            })
+            .collect::<Result<Vec<_>, ImportError>>()?;
+
+        for triangle in children(mesh, "triangles")
+            .flat_map(|triangles| children(triangles, "triangle"))
+        {
+            let mut indices = [
+                attribute::<usize>(triangle, "v1")?,
+                attribute(triangle, "v2")?,
+                attribute(triangle, "v3")?,
+            ];
+
+            // A transform that mirrors the object would turn its triangles
+            // inside out.
+            if transform.determinant() < 0. {
+                indices.swap(1, 2);
+            }
+
+            let mut points = [[0.; 3]; 3];
+            for (point, index) in points.iter_mut().zip(indices) {
+                *point = *vertices.get(index).ok_or_else(|| {
+                    ImportError::ThreeMf(format!(
+                        "Triangle of object {} references unknown vertex {}",
+                        id, index
+                    ))
+                })?;
+            }
+
+            triangles.push(points);
+        }
+    }
+
+    for component in children(*object, "components")
+        .flat_map(|components| children(components, "component"))
+    {
+        let local = Transform::parse(component.attribute("transform"))?;
+
+        add_object(
+            objects,
+            attribute(component, "objectid")?,
+            &transform.then(&local),
+            depth + 1,
+            triangles,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Iterate over the child elements with the given name
+fn children<'a, 'input: 'a>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    node.children()
+        .filter(move |node| node.tag_name().name() == name)
+}
+
+/// Parse a required attribute
+fn attribute<T: std::str::FromStr>(
+    node: roxmltree::Node,
+    name: &str,
+) -> Result<T, ImportError> {
+    let value = node.attribute(name).ok_or_else(|| {
+        ImportError::ThreeMf(format!(
+            "`{}` element is missing attribute `{}`",
+            node.tag_name().name(),
+            name
+        ))
+    })?;
+
+    value.parse().map_err(|_| {
+        ImportError::ThreeMf(format!(
+            "Invalid value `{}` for attribute `{}`",
+            value, name
+        ))
+    })
+}
+
+/// An affine transform, as used by 3MF
+///
+/// The 3MF specification treats points as row vectors, which are multiplied
+/// with a 4x3 matrix. Its rows are stored here.
+#[derive(Clone, Copy, Debug)]
+struct Transform([[f64; 3]; 4]);
+
+impl Transform {
+    fn scale(factor: f64) -> Self {
+        Self([
+            [factor, 0., 0.],
+            [0., factor, 0.],
+            [0., 0., factor],
+            [0., 0., 0.],
+        ])
+    }
+
+    /// Parse a transform attribute
+    ///
+    /// A missing attribute means the identity transform.
+    fn parse(value: Option<&str>) -> Result<Self, ImportError> {
+        let value = match value {
+            Some(value) => value,
+            None => return Ok(Self::scale(1.)),
+        };
+
+        let numbers = value
+            .split_ascii_whitespace()
+            .map(|number| number.parse())
+            .collect::<Result<Vec<f64>, _>>();
+
+        match numbers.as_deref() {
+            Ok(
+                &[m00, m01, m02, m10, m11, m12, m20, m21, m22, m30, m31, m32],
+            ) => Ok(Self([
+                [m00, m01, m02],
+                [m10, m11, m12],
+                [m20, m21, m22],
+                [m30, m31, m32],
+            ])),
+            _ => Err(ImportError::ThreeMf(format!(
+                "Invalid transform `{}`",
+                value
+            ))),
+        }
+    }
+
+    /// Create a transform that applies `other` first, then `self`
+    fn then(&self, other: &Self) -> Self {
+        let Self(a) = self;
+        let Self(b) = other;
+
+        let mut rows = [[0.; 3]; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| b[i][k] * a[k][j]).sum();
+            }
+        }
+
+        // The translation of `other` is transformed by `self`, like a point.
+        rows[3] = self.apply(b[3]);
+
+        Self(rows)
+    }
+
+    fn apply(&self, point: [f64; 3]) -> [f64; 3] {
+        let Self(m) = self;
+        [0, 1, 2]
+            .map(|j| (0..3).map(|i| point[i] * m[i][j]).sum::<f64>() + m[3][j])
+    }
+
+    fn determinant(&self) -> f64 {
+        let Self([a, b, c, _]) = self;
+
+        a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write as _};
+
+    use zip::{
+        write::{FileOptions, ZipWriter},
+        CompressionMethod,
+    };
+
+    use crate::import::ImportError;
+
+    use super::threemf_from_bytes;
+
+    const TRIANGLE: &str = r#"
+        <mesh>
+            <vertices>
+                <vertex x="0" y="0" z="0" />
+                <vertex x="1" y="0" z="0" />
+                <vertex x="0" y="1" z="0" />
+            </vertices>
+            <triangles>
+                <triangle v1="0" v2="1" v3="2" />
+            </triangles>
+        </mesh>"#;
+
+    #[test]
+    fn multiple_objects() {
+        let model = model(
+            "centimeter",
+            &format!(
+                r#"
+                <resources>
+                    <object id="1" type="model">{}</object>
+                    <object id="2" type="model">
+                        <components>
+                            <component objectid="1"
+                                transform="1 0 0 0 1 0 0 0 1 0 0 1" />
+                        </components>
+                    </object>
+                </resources>
+                <build>
+                    <item objectid="1" />
+                    <item objectid="2"
+                        transform="1 0 0 0 1 0 0 0 1 2 0 0" />
+                </build>"#,
+                TRIANGLE
+            ),
+        );
+
+        let mesh = threemf_from_bytes(&archive(&model)).unwrap();
+        assert_eq!(
+            mesh.to_triangles(),
+            [
+                [[0., 0., 0.], [10., 0., 0.], [0., 10., 0.]],
+                [[20., 0., 10.], [30., 0., 10.], [20., 10., 10.]],
+            ]
+        );
+    }
+
+    #[test]
+    fn mirrored_object() {
+        let model = model(
+            "millimeter",
+            &format!(
+                r#"
+                <resources>
+                    <object id="1" type="model">{}</object>
+                </resources>
+                <build>
+                    <item objectid="1"
+                        transform="-1 0 0 0 1 0 0 0 1 0 0 0" />
+                </build>"#,
+                TRIANGLE
+            ),
+        );
+
+        // The triangle's orientation is preserved by flipping it.
+        let mesh = threemf_from_bytes(&archive(&model)).unwrap();
+        assert_eq!(
+            mesh.to_triangles(),
+            [[[0., 0., 0.], [0., 1., 0.], [-1., 0., 0.]]]
+        );
+    }
+
+    #[test]
+    fn truncated_archive() {
+        let model = model(
+            "millimeter",
+            &format!(
+                r#"
+                <resources>
+                    <object id="1" type="model">{}</object>
+                </resources>
+                <build>
+                    <item objectid="1" />
+                </build>"#,
+                TRIANGLE
+            ),
+        );
+
+        let data = archive(&model);
+        let err = threemf_from_bytes(&data[..data.len() / 2]).unwrap_err();
+        assert!(matches!(err, ImportError::Zip(_)), "{:?}", err);
+
+        let err = threemf_from_bytes(b"not an archive").unwrap_err();
+        assert!(matches!(err, ImportError::Zip(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn invalid_model() {
+        let unknown_object =
+            model("millimeter", r#"<build><item objectid="1" /></build>"#);
+        let unknown_vertex = model(
+            "millimeter",
+            r#"
+            <resources>
+                <object id="1" type="model">
+                    <mesh>
+                        <vertices><vertex x="0" y="0" z="0" /></vertices>
+                        <triangles>
+                            <triangle v1="0" v2="1" v3="2" />
+                        </triangles>
+                    </mesh>
+                </object>
+            </resources>
+            <build><item objectid="1" /></build>"#,
+        );
+        let recursive = model(
+            "millimeter",
+            r#"
+            <resources>
+                <object id="1" type="model">
+                    <components><component objectid="1" /></components>
+                </object>
+            </resources>
+            <build><item objectid="1" /></build>"#,
+        );
+        let unknown_unit = model("furlong", "");
+        let not_a_number = model(
+            "millimeter",
+            &format!(
+                r#"
+                <resources>
+                    <object id="1" type="model">{}</object>
+                </resources>
+                <build><item objectid="1" /></build>"#,
+                TRIANGLE.replace(r#"x="1""#, r#"x="NaN""#)
+            ),
+        );
+        let infinite_transform = model(
+            "millimeter",
+            &format!(
+                r#"
+                <resources>
+                    <object id="1" type="model">{}</object>
+                </resources>
+                <build>
+                    <item objectid="1"
+                        transform="inf 0 0 0 1 0 0 0 1 0 0 0" />
+                </build>"#,
+                TRIANGLE
+            ),
+        );
+
+        for model in [
+            unknown_object,
+            unknown_vertex,
+            recursive,
+            unknown_unit,
+            not_a_number,
+            infinite_transform,
+        ] {
+            let err = threemf_from_bytes(&archive(&model)).unwrap_err();
+            assert!(matches!(err, ImportError::ThreeMf(_)), "{:?}", err);
+        }
+    }
+
+    fn model(unit: &str, content: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <model unit="{}" xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02">
+                {}
+            </model>"#,
+            unit, content
+        )
+    }
+
+    fn archive(model: &str) -> Vec<u8> {
+        let relationships = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+                <Relationship Id="rel0" Target="/3D/model.model"
+                    Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel" />
+            </Relationships>"#;
+
+        let options = FileOptions::default()
+            .compression_method(CompressionMethod::Stored);
+
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in
+            [("_rels/.rels", relationships), ("3D/model.model", model)]
+        {
+            archive.start_file(name, options).unwrap();
+            archive.write_all(content.as_bytes()).unwrap();
+        }
+
+        archive.finish().unwrap().into_inner()
+    }
+}
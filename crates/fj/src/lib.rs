@@ -13,18 +13,34 @@
 //! need the Fornjot application. Please refer to the [Fornjot repository] for
 //! usage examples.
 //!
+//! # Features
+//!
+//! - `import`: Import shapes from DXF, SVG, STL, and 3MF files, using the
+//!   `import` module.
+//! - `text`: Load fonts, to create [`Text`].
+//! - `serialization`: Serialize and deserialize shapes, using Serde.
+//!
 //! [Fornjot]: https://www.fornjot.app/
 //! [Fornjot repository]: https://github.com/hannobraun/Fornjot
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "import")]
+pub mod import;
 pub mod syntax;
 
 mod angle;
+mod outline;
+mod raw_vec;
 mod shape_2d;
 mod shape_3d;
 
-pub use self::{angle::*, shape_2d::*, shape_3d::*};
+pub use self::{
+    angle::*,
+    outline::{shapes_from_outlines, Outline},
+    shape_2d::*,
+    shape_3d::*,
+};
 
 /// A shape
 #[derive(Clone, Debug)]
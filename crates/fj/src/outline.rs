@@ -0,0 +1,501 @@
+//! Outlines, and how they nest within each other
+//!
+//! Importers and text both create shapes from a bunch of closed outlines,
+//! without knowing which of them are holes in others. The code here works that
+//! out.
+
+use std::f64::consts::PI;
+
+use crate::{Difference2d, Shape2d, Sketch, SketchSegment};
+
+/// Convert closed outlines into shapes
+///
+/// Outlines that are nested within an even number of other outlines are
+/// exteriors. Each becomes a [`Sketch`], with the outlines directly within it
+/// as its holes. All holes of an exterior are subtracted from it by a single
+/// [`Difference2d`]. Outlines that aren't closed are ignored.
+///
+/// The orientation of the outlines doesn't matter.
+pub fn shapes_from_outlines(
+    outlines: impl IntoIterator<Item = Outline>,
+) -> Vec<Shape2d> {
+    let outlines: Vec<_> = outlines
+        .into_iter()
+        .filter(Outline::is_closed)
+        .map(|outline| {
+            // Sketches are expected to be counter-clockwise.
+            if outline.area() < 0. {
+                outline.reverse()
+            } else {
+                outline
+            }
+        })
+        .collect();
+
+    let depths: Vec<_> = outlines
+        .iter()
+        .map(|outline| {
+            outlines
+                .iter()
+                .filter(|other| other.contains(outline))
+                .count()
+        })
+        .collect();
+
+    let mut shapes = Vec::new();
+    for (i, exterior) in outlines.iter().enumerate() {
+        if depths[i] % 2 != 0 {
+            continue;
+        }
+
+        let holes: Vec<Shape2d> = outlines
+            .iter()
+            .enumerate()
+            .filter(|&(j, interior)| {
+                depths[j] == depths[i] + 1 && exterior.contains(interior)
+            })
+            .map(|(_, interior)| interior.to_sketch().into())
+            .collect();
+
+        let shape = exterior.to_sketch().into();
+        let shape = if holes.is_empty() {
+            shape
+        } else {
+            Difference2d::from_shape_and_holes(shape, holes).into()
+        };
+
+        shapes.push(shape);
+    }
+
+    shapes
+}
+
+/// An outline, made up of sketch segments
+///
+/// Outlines are the input to [`shapes_from_outlines`], which works out how
+/// they are nested within each other.
+#[derive(Clone, Debug)]
+pub struct Outline {
+    pub(crate) start: [f64; 2],
+    pub(crate) segments: Vec<SketchSegment>,
+}
+
+impl Outline {
+    /// Create an outline from a start point and a bunch of segments
+    ///
+    /// Each segment begins where the previous one ended, the first one at
+    /// `start`, same as with [`Sketch::from_segments`].
+    pub fn from_segments(
+        start: [f64; 2],
+        segments: Vec<SketchSegment>,
+    ) -> Self {
+        Self { start, segments }
+    }
+
+    #[cfg(feature = "import")]
+    pub(crate) fn new(start: [f64; 2]) -> Self {
+        Self::from_segments(start, Vec::new())
+    }
+
+    pub(crate) fn end(&self) -> [f64; 2] {
+        self.segments.last().map_or(self.start, end)
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        !self.segments.is_empty() && self.end() == self.start
+    }
+
+    fn to_sketch(&self) -> Sketch {
+        Sketch::from_segments(self.start, self.segments.clone())
+    }
+
+    /// Close the outline
+    ///
+    /// If the outline ends within `tolerance` of its start, its end is moved to
+    /// its start. Otherwise, a line to its start is added.
+    #[cfg(feature = "import")]
+    pub(crate) fn close(&mut self, tolerance: f64) {
+        let start = self.start;
+
+        match self.segments.last_mut() {
+            Some(segment) if distance(end(segment), start) <= tolerance => {
+                set_end(segment, start);
+            }
+            Some(_) => self.segments.push(SketchSegment::Line { end: start }),
+            None => {}
+        }
+    }
+
+    /// Create an outline that runs in the opposite direction
+    pub(crate) fn reverse(&self) -> Self {
+        let mut segments = Vec::new();
+
+        let mut start = self.start;
+        for segment in &self.segments {
+            segments.push(match segment.clone() {
+                SketchSegment::Line { .. } => {
+                    SketchSegment::Line { end: start }
+                }
+                SketchSegment::Arc {
+                    center,
+                    counterclockwise,
+                    ..
+                } => SketchSegment::Arc {
+                    center,
+                    end: start,
+                    counterclockwise: !counterclockwise,
+                },
+                SketchSegment::CubicBezier {
+                    controls: [a, b], ..
+                } => SketchSegment::CubicBezier {
+                    controls: [b, a],
+                    end: start,
+                },
+                SketchSegment::BSpline {
+                    degree,
+                    mut control_points,
+                } => {
+                    // The uniform, clamped knot vector is symmetric, so
+                    // reversing the control points reverses the curve.
+                    control_points.pop();
+                    control_points.reverse();
+                    control_points.push(start);
+
+                    SketchSegment::BSpline {
+                        degree,
+                        control_points,
+                    }
+                }
+            });
+
+            start = end(segment);
+        }
+
+        segments.reverse();
+
+        Self {
+            start: self.end(),
+            segments,
+        }
+    }
+
+    /// Compute a polygon that approximates the outline
+    ///
+    /// This is only meant for classifying outlines, not for creating geometry.
+    pub(crate) fn polygon(&self) -> Vec<[f64; 2]> {
+        const SAMPLES: usize = 16;
+
+        let mut polygon = Vec::new();
+
+        let mut start = self.start;
+        for segment in &self.segments {
+            match segment {
+                SketchSegment::Line { .. } => polygon.push(start),
+                SketchSegment::Arc {
+                    center,
+                    end,
+                    counterclockwise,
+                } => {
+                    let arc = Arc::new(*center, start, *end, *counterclockwise);
+                    for i in 0..SAMPLES {
+                        let t = i as f64 / SAMPLES as f64;
+                        polygon.push(arc.point(arc.angle * t));
+                    }
+                }
+                SketchSegment::CubicBezier {
+                    controls: [a, b],
+                    end,
+                } => {
+                    for i in 0..SAMPLES {
+                        let t = i as f64 / SAMPLES as f64;
+                        polygon.push(de_casteljau(&[start, *a, *b, *end], t));
+                    }
+                }
+                SketchSegment::BSpline { control_points, .. } => {
+                    // The control polygon is a rough approximation, but it's
+                    // good enough for classifying paths.
+                    polygon.push(start);
+                    polygon.extend(
+                        control_points[..control_points.len() - 1]
+                            .iter()
+                            .copied(),
+                    );
+                }
+            }
+
+            start = end(segment);
+        }
+
+        polygon
+    }
+
+    /// Compute the signed area of the outline
+    ///
+    /// The area is positive, if the outline is counter-clockwise.
+    pub(crate) fn area(&self) -> f64 {
+        let polygon = self.polygon();
+
+        let mut area = 0.;
+        for (i, [ax, ay]) in polygon.iter().copied().enumerate() {
+            let [bx, by] = polygon[(i + 1) % polygon.len()];
+            area += ax * by - bx * ay;
+        }
+
+        area / 2.
+    }
+
+    /// Indicate whether `other` lies within this outline
+    ///
+    /// Closed outlines are expected not to intersect each other, but they might
+    /// touch. To be robust against that, `other` is considered to be within
+    /// this outline, if most of its points are.
+    pub(crate) fn contains(&self, other: &Outline) -> bool {
+        if std::ptr::eq(self, other) {
+            return false;
+        }
+
+        let polygon = self.polygon();
+        let points = other.polygon();
+
+        let inside = points
+            .iter()
+            .filter(|&&point| polygon_contains(&polygon, point))
+            .count();
+
+        inside * 2 > points.len()
+    }
+}
+
+/// An elliptical arc, in center parameterization
+///
+/// The arc consists of the points `center + a * cos(t) + b * sin(t)`, with `t`
+/// going from zero to `angle`.
+pub(crate) struct Arc {
+    pub(crate) center: [f64; 2],
+    pub(crate) a: [f64; 2],
+    pub(crate) b: [f64; 2],
+    pub(crate) angle: f64,
+}
+
+impl Arc {
+    /// Create a circular arc from `start` to `end`
+    ///
+    /// If `start` and `end` are identical, the arc is a full circle.
+    pub(crate) fn new(
+        center: [f64; 2],
+        start: [f64; 2],
+        end: [f64; 2],
+        counterclockwise: bool,
+    ) -> Self {
+        let a = [start[0] - center[0], start[1] - center[1]];
+        let b = if counterclockwise {
+            [-a[1], a[0]]
+        } else {
+            [a[1], -a[0]]
+        };
+
+        let v = [end[0] - center[0], end[1] - center[1]];
+        let mut angle = f64::atan2(dot(v, b), dot(v, a));
+        if angle <= 0. || start == end {
+            angle += PI * 2.;
+        }
+
+        Self {
+            center,
+            a,
+            b,
+            angle,
+        }
+    }
+
+    pub(crate) fn point(&self, t: f64) -> [f64; 2] {
+        let (sin, cos) = t.sin_cos();
+        [
+            self.center[0] + self.a[0] * cos + self.b[0] * sin,
+            self.center[1] + self.a[1] * cos + self.b[1] * sin,
+        ]
+    }
+
+    #[cfg(feature = "import")]
+    pub(crate) fn derivative(&self, t: f64) -> [f64; 2] {
+        let (sin, cos) = t.sin_cos();
+        [
+            self.b[0] * cos - self.a[0] * sin,
+            self.b[1] * cos - self.a[1] * sin,
+        ]
+    }
+
+    /// Approximate the arc using cubic Bézier curves
+    ///
+    /// Each curve spans at most an eighth of a turn, which keeps the relative
+    /// error of the approximation below `1e-5`. The last curve ends exactly at
+    /// `end`.
+    #[cfg(feature = "import")]
+    pub(crate) fn to_beziers(&self, end: [f64; 2]) -> Vec<SketchSegment> {
+        let n = (self.angle.abs() / (PI / 4.)).ceil().max(1.) as usize;
+        let step = self.angle / n as f64;
+
+        // The length of the tangents at both ends of each curve, relative to
+        // the derivative of the arc.
+        let k = 4. / 3. * (step / 4.).tan();
+
+        (0..n)
+            .map(|i| {
+                let t0 = step * i as f64;
+                let t1 = t0 + step;
+
+                let [p0, p3] = [self.point(t0), self.point(t1)];
+                let [d0, d1] = [self.derivative(t0), self.derivative(t1)];
+
+                SketchSegment::CubicBezier {
+                    controls: [
+                        [p0[0] + d0[0] * k, p0[1] + d0[1] * k],
+                        [p3[0] - d1[0] * k, p3[1] - d1[1] * k],
+                    ],
+                    end: if i + 1 == n { end } else { p3 },
+                }
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn end(segment: &SketchSegment) -> [f64; 2] {
+    match segment {
+        SketchSegment::Line { end }
+        | SketchSegment::Arc { end, .. }
+        | SketchSegment::CubicBezier { end, .. } => *end,
+        SketchSegment::BSpline { control_points, .. } => {
+            control_points[control_points.len() - 1]
+        }
+    }
+}
+
+#[cfg(feature = "import")]
+pub(crate) fn set_end(segment: &mut SketchSegment, point: [f64; 2]) {
+    match segment {
+        SketchSegment::Line { end }
+        | SketchSegment::Arc { end, .. }
+        | SketchSegment::CubicBezier { end, .. } => *end = point,
+        SketchSegment::BSpline { control_points, .. } => {
+            let last = control_points.len() - 1;
+            control_points[last] = point;
+        }
+    }
+}
+
+/// Indicate whether a point lies within a polygon
+fn polygon_contains(polygon: &[[f64; 2]], [x, y]: [f64; 2]) -> bool {
+    let mut inside = false;
+
+    for (i, [ax, ay]) in polygon.iter().copied().enumerate() {
+        let [bx, by] = polygon[(i + 1) % polygon.len()];
+
+        if (ay > y) != (by > y) && ax + (bx - ax) * (y - ay) / (by - ay) > x {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+/// Evaluate a Bézier curve using de Casteljau's algorithm
+fn de_casteljau(points: &[[f64; 2]], t: f64) -> [f64; 2] {
+    let mut points = points.to_vec();
+
+    while points.len() > 1 {
+        points = points
+            .windows(2)
+            .map(|ab| {
+                let [[ax, ay], [bx, by]] = [ab[0], ab[1]];
+                [ax + (bx - ax) * t, ay + (by - ay) * t]
+            })
+            .collect();
+    }
+
+    points[0]
+}
+
+#[cfg(feature = "import")]
+pub(crate) fn distance([ax, ay]: [f64; 2], [bx, by]: [f64; 2]) -> f64 {
+    (bx - ax).hypot(by - ay)
+}
+
+fn dot([ax, ay]: [f64; 2], [bx, by]: [f64; 2]) -> f64 {
+    ax * bx + ay * by
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Shape2d, SketchSegment};
+
+    use super::{shapes_from_outlines, Outline};
+
+    #[test]
+    fn rectangle_with_two_holes() {
+        // The orientation of the outlines must not matter.
+        let shapes = shapes_from_outlines([
+            rectangle([0., 0.], [10., 5.]),
+            rectangle([1., 1.], [3., 3.]),
+            rectangle([6., 1.], [8., 3.]).reverse(),
+        ]);
+        assert_eq!(shapes.len(), 1);
+
+        let difference = match &shapes[0] {
+            Shape2d::Difference(difference) => difference,
+            shape => panic!("Expected difference, got {:?}", shape),
+        };
+        assert!(matches!(difference.shape(), Shape2d::Sketch(_)));
+
+        // All holes are subtracted from the rectangle directly.
+        assert_eq!(difference.holes().len(), 2);
+        for hole in difference.holes() {
+            assert!(matches!(hole, Shape2d::Sketch(_)));
+        }
+    }
+
+    #[test]
+    fn nested_island() {
+        let shapes = shapes_from_outlines([
+            rectangle([0., 0.], [10., 10.]),
+            rectangle([2., 2.], [8., 8.]),
+            rectangle([4., 4.], [6., 6.]),
+        ]);
+        assert_eq!(shapes.len(), 2);
+
+        // The island is not a hole in the outer rectangle, but a shape of its
+        // own.
+        let [outer, island] = [&shapes[0], &shapes[1]];
+        match outer {
+            Shape2d::Difference(difference) => {
+                assert_eq!(difference.holes().len(), 1);
+            }
+            shape => panic!("Expected difference, got {:?}", shape),
+        }
+        match island {
+            Shape2d::Sketch(sketch) => {
+                assert_eq!(sketch.start(), Some([4., 4.]));
+            }
+            shape => panic!("Expected sketch, got {:?}", shape),
+        }
+    }
+
+    #[test]
+    fn open_outlines_are_ignored() {
+        let open = Outline::from_segments(
+            [0., 0.],
+            vec![SketchSegment::Line { end: [1., 0.] }],
+        );
+
+        assert!(shapes_from_outlines([open]).is_empty());
+    }
+
+    fn rectangle([x0, y0]: [f64; 2], [x1, y1]: [f64; 2]) -> Outline {
+        Outline::from_segments(
+            [x0, y0],
+            [[x1, y0], [x1, y1], [x0, y1], [x0, y0]]
+                .into_iter()
+                .map(|end| SketchSegment::Line { end })
+                .collect(),
+        )
+    }
+}
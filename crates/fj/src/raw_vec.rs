@@ -0,0 +1,58 @@
+use std::mem;
+
+/// The raw parts of a `Vec`
+///
+/// Shapes need to be FFI-safe, meaning they can't store a `Vec` directly. They
+/// need to take this detour.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub(crate) struct RawVec<T> {
+    ptr: *mut T,
+    length: usize,
+    capacity: usize,
+}
+
+impl<T: Clone> RawVec<T> {
+    pub(crate) fn from_vec(mut vec: Vec<T>) -> Self {
+        // This can be cleaned up, once `Vec::into_raw_parts` is stable.
+        let ptr = vec.as_mut_ptr();
+        let length = vec.len();
+        let capacity = vec.capacity();
+
+        // We're taking ownership of the memory here, so we can't allow `vec` to
+        // deallocate it.
+        mem::forget(vec);
+
+        Self {
+            ptr,
+            length,
+            capacity,
+        }
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<T> {
+        // This is sound. All invariants are automatically kept, as the raw
+        // parts come from an original `Vec` that is identical to the new one we
+        // create here, and aren't being modified anywhere.
+        let vec = unsafe {
+            Vec::from_raw_parts(self.ptr, self.length, self.capacity)
+        };
+
+        // Ownership of the pointer in `self` transferred to `vec`. We work
+        // around that, by returning a clone of `vec` (hence not giving
+        // ownership to the caller).
+        let ret = vec.clone();
+
+        // Now we just need to forget that `vec` ever existed, and we keep
+        // ownership of the pointer.
+        mem::forget(vec);
+
+        ret
+    }
+
+    pub(crate) fn as_slice(&self) -> &[T] {
+        // This is sound, for the same reasons as given in `to_vec`. The slice
+        // borrows `self`, so the memory can't be given away while it exists.
+        unsafe { std::slice::from_raw_parts(self.ptr, self.length) }
+    }
+}
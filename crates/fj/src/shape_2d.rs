@@ -1,6 +1,7 @@
-use std::mem;
+#[cfg(feature = "text")]
+use std::{fmt, fs, io, path::Path};
 
-use crate::Shape;
+use crate::{raw_vec::RawVec, Shape};
 
 /// A 2-dimensional shape
 #[derive(Clone, Debug)]
@@ -13,8 +14,14 @@ pub enum Shape2d {
     /// A difference between two shapes
     Difference(Box<Difference2d>),
 
+    /// An ellipse
+    Ellipse(Ellipse),
+
     /// A sketch
     Sketch(Sketch),
+
+    /// Text
+    Text(Text),
 }
 
 impl Shape2d {
@@ -24,6 +31,8 @@ impl Shape2d {
             Shape2d::Circle(c) => c.color(),
             Shape2d::Sketch(s) => s.color(),
             Shape2d::Difference(d) => d.color(),
+            Shape2d::Ellipse(e) => e.color(),
+            Shape2d::Text(t) => t.color(),
         }
     }
 }
@@ -82,28 +91,104 @@ impl From<Circle> for Shape2d {
     }
 }
 
+/// An ellipse
+///
+/// The ellipse is centered on the origin, and its axes are aligned with the x
+/// and y axes.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Ellipse {
+    /// The radii of the ellipse, along the x and y axes
+    radii: [f64; 2],
+    // The color of the ellipse in RGBA
+    color: [u8; 4],
+}
+
+impl Ellipse {
+    /// Construct a new ellipse with specific radii along the x and y axes
+    pub fn from_radii(radii: [f64; 2]) -> Self {
+        Self {
+            radii,
+            color: [255, 0, 0, 255],
+        }
+    }
+
+    /// Access the ellipse's radii along the x and y axes
+    pub fn radii(&self) -> [f64; 2] {
+        self.radii
+    }
+
+    /// Set the rendering color of the ellipse in RGBA
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the rendering color of the ellipse in RGBA
+    pub fn set_color(&mut self, color: [u8; 4]) {
+        self.color = color;
+    }
+
+    /// Get the rendering color of the ellipse in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.color
+    }
+}
+
+impl From<Ellipse> for Shape {
+    fn from(shape: Ellipse) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<Ellipse> for Shape2d {
+    fn from(shape: Ellipse) -> Self {
+        Self::Ellipse(shape)
+    }
+}
+
 /// A difference between two shapes
+///
+/// Any number of shapes, the holes, can be subtracted from the first shape.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub struct Difference2d {
-    shapes: [Shape2d; 2],
+    shape: Shape2d,
+    holes: RawVec<Shape2d>,
 }
 
 impl Difference2d {
     /// Create a `Difference2d` from two shapes
-    pub fn from_shapes(shapes: [Shape2d; 2]) -> Self {
-        Self { shapes }
+    pub fn from_shapes([shape, hole]: [Shape2d; 2]) -> Self {
+        Self::from_shape_and_holes(shape, vec![hole])
+    }
+
+    /// Create a `Difference2d` by subtracting multiple shapes from one
+    ///
+    /// The holes are all subtracted from `shape` directly. They are expected
+    /// not to overlap each other.
+    pub fn from_shape_and_holes(shape: Shape2d, holes: Vec<Shape2d>) -> Self {
+        Self {
+            shape,
+            holes: RawVec::from_vec(holes),
+        }
     }
 
     /// Get the rendering color of the larger object in RGBA
     pub fn color(&self) -> [u8; 4] {
-        self.shapes[0].color()
+        self.shape.color()
+    }
+
+    /// Access the shape that the holes are subtracted from
+    pub fn shape(&self) -> &Shape2d {
+        &self.shape
     }
 
-    /// Access the shapes that make up the difference
-    pub fn shapes(&self) -> &[Shape2d; 2] {
-        &self.shapes
+    /// Access the shapes that are subtracted
+    pub fn holes(&self) -> &[Shape2d] {
+        self.holes.as_slice()
     }
 }
 
@@ -121,65 +206,160 @@ impl From<Difference2d> for Shape2d {
 
 /// A sketch
 ///
-/// Sketches are currently limited to a single cycle of straight lines,
-/// represented by a number of points. For example, if the points a, b, and c
-/// are provided, the edges ab, bc, and ca are assumed.
+/// Sketches are currently limited to a single cycle. The cycle begins at a
+/// start point, and consists of a number of segments, each of which continues
+/// where the previous one ended. If the last segment doesn't end at the start
+/// point, the cycle is closed with a straight line.
 ///
-/// Nothing about these edges is checked right now, but algorithms might assume
-/// that the edges are non-overlapping. If you create a `Sketch` with
-/// overlapping edges, you're on your own.
+/// Nothing about these segments is checked right now, but algorithms might
+/// assume that they are non-overlapping. If you create a `Sketch` with
+/// overlapping segments, you're on your own.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub struct Sketch {
-    // The fields are the raw parts of a `Vec`. `Sketch` needs to be FFI-safe,
-    // meaning it can't store a `Vec` directly. It needs to take this detour.
-    ptr: *mut [f64; 2],
-    length: usize,
-    capacity: usize,
+    // The points of all segments, in order, starting with the start point of
+    // the sketch.
+    points: RawVec<[f64; 2]>,
+    // The segments, without their points.
+    segments: RawVec<RawSegment>,
     // The color of the sketch in RGBA
     color: [u8; 4],
 }
 
 impl Sketch {
     /// Create a sketch from a bunch of points
-    pub fn from_points(mut points: Vec<[f64; 2]>) -> Self {
-        // This can be cleaned up, once `Vec::into_raw_parts` is stable.
-        let ptr = points.as_mut_ptr();
-        let length = points.len();
-        let capacity = points.capacity();
+    ///
+    /// If the points a, b, and c are provided, the sketch consists of the
+    /// straight lines ab, bc, and ca.
+    pub fn from_points(points: Vec<[f64; 2]>) -> Self {
+        let segments = vec![RawSegment::Line; points.len().saturating_sub(1)];
 
-        // We're taking ownership of the memory here, so we can't allow `points`
-        // to deallocate it.
-        mem::forget(points);
+        Self {
+            points: RawVec::from_vec(points),
+            segments: RawVec::from_vec(segments),
+            color: [255, 0, 0, 255],
+        }
+    }
+
+    /// Create a sketch from a start point and a bunch of segments
+    ///
+    /// # Panics
+    ///
+    /// Panics, if a B-spline segment has a degree of zero, or doesn't have
+    /// enough control points for its degree.
+    pub fn from_segments(
+        start: [f64; 2],
+        segments: Vec<SketchSegment>,
+    ) -> Self {
+        let mut points = vec![start];
+        let mut raw_segments = Vec::new();
+
+        for segment in segments {
+            match segment {
+                SketchSegment::Line { end } => {
+                    points.push(end);
+                    raw_segments.push(RawSegment::Line);
+                }
+                SketchSegment::Arc {
+                    center,
+                    end,
+                    counterclockwise,
+                } => {
+                    points.extend([center, end]);
+                    raw_segments.push(RawSegment::Arc { counterclockwise });
+                }
+                SketchSegment::CubicBezier { controls, end } => {
+                    points.extend(controls);
+                    points.push(end);
+                    raw_segments.push(RawSegment::CubicBezier);
+                }
+                SketchSegment::BSpline {
+                    degree,
+                    control_points,
+                } => {
+                    // The start of the segment is its first control point, so
+                    // it has one more than those that are provided here.
+                    assert!(degree > 0, "B-spline must have a degree of 1+");
+                    assert!(
+                        control_points.len() >= degree,
+                        "B-spline of degree {} needs at least {} control \
+                        points, in addition to its start point",
+                        degree,
+                        degree,
+                    );
+
+                    raw_segments.push(RawSegment::BSpline {
+                        degree,
+                        num_points: control_points.len(),
+                    });
+                    points.extend(control_points);
+                }
+            }
+        }
 
         Self {
-            ptr,
-            length,
-            capacity,
+            points: RawVec::from_vec(points),
+            segments: RawVec::from_vec(raw_segments),
             color: [255, 0, 0, 255],
         }
     }
 
-    /// Return the points of the sketch
-    pub fn to_points(&self) -> Vec<[f64; 2]> {
-        // This is sound. All invariants are automatically kept, as the raw
-        // parts come from an original `Vec` that is identical to the new one we
-        // create here, and aren't being modified anywhere.
-        let points = unsafe {
-            Vec::from_raw_parts(self.ptr, self.length, self.capacity)
-        };
+    /// Return the start point of the sketch
+    ///
+    /// Returns `None`, if the sketch is empty.
+    pub fn start(&self) -> Option<[f64; 2]> {
+        self.points.to_vec().first().copied()
+    }
 
-        // Ownership of the pointer in `self.raw_parts` transferred to `points`.
-        // We work around that, by returning a clone of `points` (hence not
-        // giving ownership to the caller).
-        let ret = points.clone();
+    /// Return the segments of the sketch
+    ///
+    /// The first segment begins at the start point of the sketch.
+    pub fn to_segments(&self) -> Vec<SketchSegment> {
+        let points = self.points.to_vec();
+
+        // Skip the start point. It's not part of any segment.
+        let mut points = points.into_iter().skip(1);
+        let mut next = || {
+            points
+                .next()
+                .expect("Sketch has fewer points than its segments require")
+        };
 
-        // Now we just need to forget that `points` ever existed, and we keep
-        // ownership of the pointer.
-        mem::forget(points);
+        self.segments
+            .to_vec()
+            .into_iter()
+            .map(|segment| match segment {
+                RawSegment::Line => SketchSegment::Line { end: next() },
+                RawSegment::Arc { counterclockwise } => SketchSegment::Arc {
+                    center: next(),
+                    end: next(),
+                    counterclockwise,
+                },
+                RawSegment::CubicBezier => SketchSegment::CubicBezier {
+                    controls: [next(), next()],
+                    end: next(),
+                },
+                RawSegment::BSpline { degree, num_points } => {
+                    SketchSegment::BSpline {
+                        degree,
+                        control_points: (0..num_points)
+                            .map(|_| next())
+                            .collect(),
+                    }
+                }
+            })
+            .collect()
+    }
 
-        ret
+    /// Return the points of the sketch
+    ///
+    /// This includes the start point and the points of all segments, including
+    /// the control points of curves and the centers of arcs. Curves lie within
+    /// the convex hull of their control points, but arcs don't necessarily lie
+    /// within the convex hull of these points.
+    pub fn to_points(&self) -> Vec<[f64; 2]> {
+        self.points.to_vec()
     }
 
     /// Set the rendering color of the sketch in RGBA
@@ -211,6 +391,266 @@ impl From<Sketch> for Shape2d {
     }
 }
 
-// `Sketch` can be `Send`, because it encapsulates the raw pointer it contains,
-// making sure memory ownership rules are observed.
+/// A segment of a [`Sketch`]
+///
+/// Each segment begins where the previous segment ended, or at the start point
+/// of the sketch, if it is the first segment.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SketchSegment {
+    /// A straight line
+    Line {
+        /// The end point of the line
+        end: [f64; 2],
+    },
+
+    /// A circular arc
+    ///
+    /// The start of the segment and `end` must be at the same distance from
+    /// `center`. If `end` is identical to the start of the segment, the arc is
+    /// a full circle.
+    Arc {
+        /// The center of the arc
+        center: [f64; 2],
+
+        /// The end point of the arc
+        end: [f64; 2],
+
+        /// Whether the arc goes counter-clockwise, as opposed to clockwise
+        counterclockwise: bool,
+    },
+
+    /// A cubic Bézier curve
+    CubicBezier {
+        /// The two inner control points of the curve
+        controls: [[f64; 2]; 2],
+
+        /// The end point of the curve
+        end: [f64; 2],
+    },
+
+    /// A B-spline curve with a clamped, uniform knot vector
+    ///
+    /// The curve begins at its first control point, which is the beginning of
+    /// the segment, and ends at its last control point.
+    BSpline {
+        /// The degree of the curve
+        degree: usize,
+
+        /// The control points of the curve, except the first one
+        ///
+        /// There must be at least `degree` control points. The last one is the
+        /// end point of the segment.
+        control_points: Vec<[f64; 2]>,
+    },
+}
+
+/// The FFI-safe representation of a [`SketchSegment`]
+///
+/// The points of the segment are stored separately.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+enum RawSegment {
+    Line,
+    Arc { counterclockwise: bool },
+    CubicBezier,
+    BSpline { degree: usize, num_points: usize },
+}
+
+/// Text
+///
+/// The text is made up of the outlines of its glyphs. Outlines within other
+/// outlines, like the counter in an "o", become holes, as described in
+/// [`shapes_from_outlines`]. The text starts
+/// at the origin, with the origin on its baseline. Line breaks move subsequent
+/// text down, by the line height of the font.
+///
+/// Text is laid out by placing each glyph after the previous one, according to
+/// the glyph's advance. More advanced layout features, like kerning, ligatures,
+/// or right-to-left text, are not supported.
+///
+/// [`shapes_from_outlines`]: crate::shapes_from_outlines
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Text {
+    // The text, encoded as UTF-8
+    text: RawVec<u8>,
+    font: Font,
+    size: f64,
+    // The color of the text in RGBA
+    color: [u8; 4],
+}
+
+impl Text {
+    /// Create text from a string, a font, and a font size
+    ///
+    /// The size is the size of the font's em square, in model units.
+    pub fn new(text: impl Into<String>, font: Font, size: f64) -> Self {
+        Self {
+            text: RawVec::from_vec(text.into().into_bytes()),
+            font,
+            size,
+            color: [255, 0, 0, 255],
+        }
+    }
+
+    /// Access the text
+    pub fn text(&self) -> String {
+        String::from_utf8(self.text.to_vec())
+            .expect("Text was created from a `String`")
+    }
+
+    /// Access the font of the text
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Access the size of the text
+    pub fn size(&self) -> f64 {
+        self.size
+    }
+
+    /// Set the rendering color of the text in RGBA
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the rendering color of the text in RGBA
+    pub fn set_color(&mut self, color: [u8; 4]) {
+        self.color = color;
+    }
+
+    /// Get the rendering color of the text in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.color
+    }
+}
+
+impl From<Text> for Shape {
+    fn from(shape: Text) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<Text> for Shape2d {
+    fn from(shape: Text) -> Self {
+        Shape2d::Text(shape)
+    }
+}
+
+/// A TrueType or OpenType font, used by [`Text`]
+///
+/// Fonts can be bundled with a model, by passing the contents of a font file
+/// that are included using `include_bytes!` to `Font::from_bytes`. They can
+/// also be read from a local file, using `Font::from_path`. Both require the
+/// `text` feature.
+///
+/// The type itself is always available, so the layout of [`Shape2d`] doesn't
+/// depend on the features that a model enables.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Font {
+    data: RawVec<u8>,
+}
+
+impl Font {
+    /// Create a font from the contents of a font file
+    ///
+    /// Font collections are not supported. Only the first font of a collection
+    /// is used.
+    #[cfg(feature = "text")]
+    pub fn from_bytes(data: impl Into<Vec<u8>>) -> Result<Self, FontError> {
+        let data = data.into();
+
+        // Make sure the font can be parsed, so the code that processes the text
+        // can rely on it.
+        ttf_parser::Face::from_slice(&data, 0)?;
+
+        Ok(Self {
+            data: RawVec::from_vec(data),
+        })
+    }
+
+    /// Read a font from a file
+    #[cfg(feature = "text")]
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, FontError> {
+        let data = fs::read(path)?;
+        Self::from_bytes(data)
+    }
+
+    /// Return the contents of the font file
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.data.to_vec()
+    }
+
+    /// Access the contents of the font file, without copying them
+    ///
+    /// Clones of a font share its contents, so they can be used by any number
+    /// of texts.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+}
+
+/// An error that can occur when creating a [`Font`]
+#[cfg(feature = "text")]
+#[derive(Debug)]
+pub enum FontError {
+    /// The font file could not be read
+    Io(io::Error),
+
+    /// The font could not be parsed
+    Parse(ttf_parser::FaceParsingError),
+}
+
+#[cfg(feature = "text")]
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Error reading font: {}", err),
+            Self::Parse(err) => write!(f, "Error parsing font: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "text")]
+impl std::error::Error for FontError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "text")]
+impl From<io::Error> for FontError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(feature = "text")]
+impl From<ttf_parser::FaceParsingError> for FontError {
+    fn from(err: ttf_parser::FaceParsingError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+// `Difference2d`, `Sketch`, `Text`, and `Font` can be `Send`, because they
+// encapsulate the raw pointers they contain, making sure memory ownership rules
+// are observed.
+unsafe impl Send for Difference2d {}
 unsafe impl Send for Sketch {}
+unsafe impl Send for Text {}
+unsafe impl Send for Font {}
+
+// `Difference2d`, `Sketch`, `Text`, and `Font` can be `Sync`, because the
+// memory behind their raw pointers is only ever read through a shared
+// reference.
+unsafe impl Sync for Difference2d {}
+unsafe impl Sync for Sketch {}
+unsafe impl Sync for Text {}
+unsafe impl Sync for Font {}
@@ -1,4 +1,4 @@
-use crate::{Angle, Shape, Shape2d};
+use crate::{raw_vec::RawVec, Angle, Shape, Shape2d};
 
 /// A 3-dimensional shape
 #[derive(Clone, Debug)]
@@ -8,6 +8,18 @@ pub enum Shape3d {
     /// A group of two 3-dimensional shapes
     Group(Box<Group>),
 
+    /// A linear pattern of a 3-dimensional shape
+    LinearPattern(Box<LinearPattern>),
+
+    /// A triangle mesh, usually imported from a file
+    Mesh(Mesh),
+
+    /// A 3-dimensional shape, mirrored about a plane
+    Mirror(Box<Mirror>),
+
+    /// A polar pattern of a 3-dimensional shape
+    PolarPattern(Box<PolarPattern>),
+
     /// A sweep of 2-dimensional shape along the z-axis
     Sweep(Sweep),
 
@@ -52,12 +64,223 @@ impl From<Group> for Shape3d {
     }
 }
 
+/// A linear pattern of a 3-dimensional shape
+///
+/// Creates `count` copies of `shape`. The first copy is identical to the
+/// original shape, while each subsequent copy is translated by `offset`,
+/// relative to the previous one.
+///
+/// The original shape is only evaluated once, regardless of the number of
+/// copies.
+///
+/// # Limitations
+///
+/// Like the shapes in a [`Group`], the copies are not allowed to touch or
+/// overlap. This is not currently checked.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct LinearPattern {
+    /// The shape being copied
+    pub shape: Shape3d,
+
+    /// The offset between two consecutive copies
+    pub offset: [f64; 3],
+
+    /// The number of copies, including the first one
+    ///
+    /// Must be at least one.
+    pub count: u32,
+}
+
+impl From<LinearPattern> for Shape {
+    fn from(shape: LinearPattern) -> Self {
+        Self::Shape3d(Shape3d::LinearPattern(Box::new(shape)))
+    }
+}
+
+impl From<LinearPattern> for Shape3d {
+    fn from(shape: LinearPattern) -> Self {
+        Self::LinearPattern(Box::new(shape))
+    }
+}
+
+/// A triangle mesh
+///
+/// Meshes are meant for including existing parts in a model, for example
+/// purchased parts that are only available as STL or 3MF files. They can be
+/// loaded using `import::stl` and `import::threemf`, which require the `import`
+/// feature.
+///
+/// A mesh can be marked as a reference body, using [`Mesh::with_reference`].
+/// Reference bodies are displayed along with the rest of the model, but are
+/// not included, when the model is exported.
+///
+/// # Limitations
+///
+/// Meshes are included in the model as they are. Like the shapes in a
+/// [`Group`], they are not allowed to touch or overlap other shapes. This is
+/// not currently checked.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Mesh {
+    triangles: RawVec<[[f64; 3]; 3]>,
+    // The color of the mesh in RGBA
+    color: [u8; 4],
+    reference: bool,
+}
+
+impl Mesh {
+    /// Create a mesh from triangles
+    ///
+    /// The points of each triangle are expected to be ordered
+    /// counter-clockwise, when looking at the triangle from outside of the
+    /// mesh.
+    pub fn from_triangles(triangles: Vec<[[f64; 3]; 3]>) -> Self {
+        Self {
+            triangles: RawVec::from_vec(triangles),
+            color: [255, 0, 0, 255],
+            reference: false,
+        }
+    }
+
+    /// Return the triangles of the mesh
+    pub fn to_triangles(&self) -> Vec<[[f64; 3]; 3]> {
+        self.triangles.to_vec()
+    }
+
+    /// Access the triangles of the mesh, without copying them
+    pub fn triangles(&self) -> &[[[f64; 3]; 3]] {
+        self.triangles.as_slice()
+    }
+
+    /// Set the rendering color of the mesh in RGBA
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the rendering color of the mesh in RGBA
+    pub fn set_color(&mut self, color: [u8; 4]) {
+        self.color = color;
+    }
+
+    /// Get the rendering color of the mesh in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.color
+    }
+
+    /// Set whether the mesh is a reference body
+    pub fn with_reference(mut self, reference: bool) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    /// Indicate whether the mesh is a reference body
+    pub fn is_reference(&self) -> bool {
+        self.reference
+    }
+}
+
+impl From<Mesh> for Shape {
+    fn from(shape: Mesh) -> Self {
+        Self::Shape3d(shape.into())
+    }
+}
+
+impl From<Mesh> for Shape3d {
+    fn from(shape: Mesh) -> Self {
+        Self::Mesh(shape)
+    }
+}
+
+/// A 3-dimensional shape, mirrored about a plane
+///
+/// The plane passes through `origin` and is perpendicular to `normal`. The
+/// mirrored shape does not include the original shape. Use a [`Group`], to
+/// combine both.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct Mirror {
+    /// The shape being mirrored
+    pub shape: Shape3d,
+
+    /// The normal of the mirror plane
+    pub normal: [f64; 3],
+
+    /// A point on the mirror plane
+    pub origin: [f64; 3],
+}
+
+impl From<Mirror> for Shape {
+    fn from(shape: Mirror) -> Self {
+        Self::Shape3d(Shape3d::Mirror(Box::new(shape)))
+    }
+}
+
+impl From<Mirror> for Shape3d {
+    fn from(shape: Mirror) -> Self {
+        Self::Mirror(Box::new(shape))
+    }
+}
+
+/// A polar pattern of a 3-dimensional shape
+///
+/// Creates `count` copies of `shape`. The first copy is identical to the
+/// original shape, while each subsequent copy is rotated by `angle` around an
+/// axis defined by `axis`, relative to the previous one. The axis goes through
+/// the origin.
+///
+/// The original shape is only evaluated once, regardless of the number of
+/// copies.
+///
+/// # Limitations
+///
+/// Like the shapes in a [`Group`], the copies are not allowed to touch or
+/// overlap. This is not currently checked.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct PolarPattern {
+    /// The shape being copied
+    pub shape: Shape3d,
+
+    /// The axis of the rotation
+    ///
+    /// Must not have zero length.
+    pub axis: [f64; 3],
+
+    /// The angle between two consecutive copies
+    pub angle: Angle,
+
+    /// The number of copies, including the first one
+    ///
+    /// Must be at least one.
+    pub count: u32,
+}
+
+impl From<PolarPattern> for Shape {
+    fn from(shape: PolarPattern) -> Self {
+        Self::Shape3d(Shape3d::PolarPattern(Box::new(shape)))
+    }
+}
+
+impl From<PolarPattern> for Shape3d {
+    fn from(shape: PolarPattern) -> Self {
+        Self::PolarPattern(Box::new(shape))
+    }
+}
+
 /// A transformed 3-dimensional shape
 ///
+/// The shape is first scaled, then rotated, then translated.
+///
 /// # Limitations
 ///
-/// Transformations are currently limited to a rotation, followed by a
-/// translation.
+/// Transformations are currently limited to a scaling, followed by a rotation,
+/// followed by a translation.
 ///
 /// See issue:
 /// <https://github.com/hannobraun/Fornjot/issues/101>
@@ -68,6 +291,12 @@ pub struct Transform {
     /// The shape being transformed
     pub shape: Shape3d,
 
+    /// The scale factors along the x, y, and z axes
+    ///
+    /// The scaling can be non-uniform, i.e. the factors can differ from each
+    /// other. A negative factor mirrors the shape along the respective axis.
+    pub scale: [f64; 3],
+
     /// The axis of the rotation
     pub axis: [f64; 3],
 
@@ -130,3 +359,11 @@ impl From<Sweep> for Shape3d {
         Self::Sweep(shape)
     }
 }
+
+// `Mesh` can be `Send`, because it encapsulates the raw pointer it contains,
+// making sure memory ownership rules are observed.
+unsafe impl Send for Mesh {}
+
+// `Mesh` can be `Sync`, because the memory behind its raw pointer is only ever
+// read through a shared reference.
+unsafe impl Sync for Mesh {}
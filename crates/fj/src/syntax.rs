@@ -53,6 +53,100 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::LinearPattern`]
+///
+/// [`fj::LinearPattern`]: crate::LinearPattern
+pub trait LinearPattern {
+    /// Create a linear pattern
+    ///
+    /// Create `count` copies of `self`, each one translated by `offset`
+    /// relative to the previous one.
+    fn linear_pattern(
+        &self,
+        offset: [f64; 3],
+        count: u32,
+    ) -> crate::LinearPattern;
+}
+
+impl<T> LinearPattern for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn linear_pattern(
+        &self,
+        offset: [f64; 3],
+        count: u32,
+    ) -> crate::LinearPattern {
+        let shape = self.clone().into();
+        crate::LinearPattern {
+            shape,
+            offset,
+            count,
+        }
+    }
+}
+
+/// Convenient syntax to create an [`fj::Mirror`]
+///
+/// [`fj::Mirror`]: crate::Mirror
+pub trait Mirror {
+    /// Create a mirrored shape
+    ///
+    /// Mirror `self` about the plane that passes through `origin` and is
+    /// perpendicular to `normal`.
+    fn mirror(&self, normal: [f64; 3], origin: [f64; 3]) -> crate::Mirror;
+}
+
+impl<T> Mirror for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn mirror(&self, normal: [f64; 3], origin: [f64; 3]) -> crate::Mirror {
+        let shape = self.clone().into();
+        crate::Mirror {
+            shape,
+            normal,
+            origin,
+        }
+    }
+}
+
+/// Convenient syntax to create an [`fj::PolarPattern`]
+///
+/// [`fj::PolarPattern`]: crate::PolarPattern
+pub trait PolarPattern {
+    /// Create a polar pattern
+    ///
+    /// Create `count` copies of `self`, each one rotated by `angle` around an
+    /// axis defined by `axis`, relative to the previous one.
+    fn polar_pattern(
+        &self,
+        axis: [f64; 3],
+        angle: crate::Angle,
+        count: u32,
+    ) -> crate::PolarPattern;
+}
+
+impl<T> PolarPattern for T
+where
+    T: Clone + Into<crate::Shape3d>,
+{
+    fn polar_pattern(
+        &self,
+        axis: [f64; 3],
+        angle: crate::Angle,
+        count: u32,
+    ) -> crate::PolarPattern {
+        let shape = self.clone().into();
+        crate::PolarPattern {
+            shape,
+            axis,
+            angle,
+            count,
+        }
+    }
+}
+
 /// Convenient syntax to create an [`fj::Sketch`]
 ///
 /// [`fj::Sketch`]: crate::Sketch
@@ -105,6 +199,12 @@ pub trait Transform {
     ///
     /// Create a translation that translates `shape` by `offset`.
     fn translate(&self, offset: [f64; 3]) -> crate::Transform;
+
+    /// Create a scaling
+    ///
+    /// Create a scaling that scales `shape` by `factors` along the x, y, and z
+    /// axes, relative to the origin.
+    fn scale(&self, factors: [f64; 3]) -> crate::Transform;
 }
 
 impl<T> Transform for T
@@ -115,6 +215,7 @@ where
         let shape = self.clone().into();
         crate::Transform {
             shape,
+            scale: [1.; 3],
             axis,
             angle,
             offset: [0.; 3],
@@ -125,9 +226,21 @@ where
         let shape = self.clone().into();
         crate::Transform {
             shape,
+            scale: [1.; 3],
             axis: [1., 0., 0.],
             angle: crate::Angle::from_rad(0.),
             offset,
         }
     }
+
+    fn scale(&self, factors: [f64; 3]) -> crate::Transform {
+        let shape = self.clone().into();
+        crate::Transform {
+            shape,
+            scale: factors,
+            axis: [1., 0., 0.],
+            angle: crate::Angle::from_rad(0.),
+            offset: [0.; 3],
+        }
+    }
 }